@@ -14,9 +14,13 @@ function parsePermission(file: string): FnValue["permission"] {
     return m[0].replace(reg, "$1");
   };
 
+  // 不需要权限的函数
+  if (!text.includes("PermissionKey::")) {
+    return undefined;
+  }
   const key = extract(/key: PermissionKey::(\w+),/);
 
-  const level: FnValue["permission"]["level"] = text.includes(
+  const level: NonNullable<FnValue["permission"]>["level"] = text.includes(
     "judge_perm_level(&arg)",
   )
     ? "JUDGE_WITH_PATH"
@@ -40,19 +40,23 @@ function functionFieldRenderer(
   info: FnValue,
   { titleLevel }: { titleLevel: number },
 ): string {
-  const permissionLevel =
-    info.permission.level === "JUDGE_WITH_PATH"
+  const { permission } = info;
+  const permissionText = permission
+    ? `
+  * 类型：[\`${permission.key}\`](/nep/definition/3-permissions#${
+    permission.key
+  })
+  * 等级：${
+    permission.level === "JUDGE_WITH_PATH"
       ? "根据输入路径决定"
-      : `[\`${info.permission.level}\`](/nep/definition/3-permissions#${info.permission.level})`;
+      : `[\`${permission.level}\`](/nep/definition/3-permissions#${permission.level})`
+  }`
+    : "无";
   return `${"#".repeat(titleLevel)} ${info.name}
 ${info.wiki ?? ""}
 * 入参校验：${info.validationRules ?? ""}
 * 示例：\`${info.demo ?? ""}\`
-* 权限：
-  * 类型：[\`${info.permission.key}\`](/nep/definition/3-permissions#${
-    info.permission.key
-  })
-  * 等级：${permissionLevel}`;
+* 权限：${permissionText}`;
 }
 
 export function functionRenderer(
@@ -11,7 +11,7 @@ export interface FnValue {
   name: string;
   wiki?: string;
   demo?: string;
-  permission: {
+  permission?: {
     key: string;
     level: PermissionLevel | "JUDGE_WITH_PATH";
   };
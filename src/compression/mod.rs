@@ -1,64 +1,51 @@
 mod tar;
 mod zstd;
 
-use crate::{log, p2s};
+use crate::types::pack::PackOptions;
 
-pub use self::tar::{pack_tar, release_tar};
-pub use self::zstd::fast_decompress_zstd;
-use self::zstd::{compress_zstd, decompress_zstd};
+pub use self::tar::{pack_tar, pack_tar_reproducible, release_tar};
+use self::tar::{pack_tar_into, walk_tar};
+use self::zstd::{zstd_decoder, zstd_encoder};
 use anyhow::{anyhow, Result};
-use std::fs::remove_file;
-use std::path::Path;
+use std::fs::File;
+use std::io::Read;
+
+pub fn compress(source_dir: &String, into_file: &String, options: &PackOptions) -> Result<()> {
+    let file = File::create(into_file)
+        .map_err(|e| anyhow!("Error:Failed to create file at '{into_file}' : {e}"))?;
+    // tar 流直接写入编码器，不再生成临时 tar 文件
+    let encoder = zstd_encoder(file, options)?;
+    let encoder = pack_tar_into(source_dir, encoder, options.reproducible_mtime)
+        .map_err(|res| anyhow!("Error:Can't compress '{source_dir}' into '{into_file}' : {res}"))?;
+    encoder
+        .finish()
+        .map_err(|e| anyhow!("Error:Failed to finish compressing '{into_file}' : {e}"))?;
 
-fn get_temp_tar(zstd_file: &Path) -> String {
-    let stem = p2s!(zstd_file.file_stem().unwrap());
-    p2s!(zstd_file.with_file_name(stem))
-}
-
-#[test]
-fn test_get_temp_tar() {
-    let res = get_temp_tar(Path::new(
-        r"D:\Desktop\Projects\EdgelessPE\ept\examples\VSCode\Pack.tar.zst",
-    ));
-    assert_eq!(
-        res,
-        "D:/Desktop/Projects/EdgelessPE/ept/examples/VSCode/Pack.tar".to_string()
-    );
+    Ok(())
 }
 
-pub fn compress(source_dir: &String, into_file: &String) -> Result<()> {
-    let temp_tar = get_temp_tar(Path::new(into_file));
-    pack_tar(source_dir, &temp_tar)
-        .map_err(|res| anyhow!("Error:Can't archive '{source_dir}' into '{temp_tar}' : {res}"))?;
-
-    compress_zstd(&temp_tar, into_file)
-        .map_err(|res| anyhow!("Error:Can't compress '{temp_tar}' into '{into_file}' : {res}"))?;
-
-    if let Err(e) = remove_file(&temp_tar) {
-        log!("Warning:Can't remove temp tar '{temp_tar}' : {e}");
-    }
+// 从读取流中解压 tar.zst 到目录，不生成临时 tar 文件
+pub fn decompress<R: Read>(reader: R, into_dir: &String) -> Result<()> {
+    let decoder = zstd_decoder(reader)?;
+    release_tar(decoder, into_dir)
+        .map_err(|res| anyhow!("Error:Can't decompress into '{into_dir}' : {res}"))?;
 
     Ok(())
 }
 
-pub fn decompress(source_file: &String, into_dir: &String) -> Result<()> {
-    let temp_tar = get_temp_tar(Path::new(source_file));
-    decompress_zstd(source_file, &temp_tar).map_err(|res| {
-        anyhow!("Error:Can't decompress '{source_file}' into '{temp_tar}' : {res}")
-    })?;
-
-    release_tar(&temp_tar, into_dir)
-        .map_err(|res| anyhow!("Error:Can't release '{temp_tar}' into '{into_dir}' : {res}"))?;
-
-    if let Err(e) = remove_file(&temp_tar) {
-        log!("Warning:Can't remove temp tar '{temp_tar}' : {e}");
-    }
-
-    Ok(())
+// 流式遍历 tar.zst 中的条目，不解压到磁盘
+pub fn walk_compressed<R, F>(reader: R, visit: F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(&::tar::Header, String, &mut dyn Read) -> Result<()>,
+{
+    let decoder = zstd_decoder(reader)?;
+    walk_tar(decoder, visit).map_err(|res| anyhow!("Error:Can't read compressed tar : {res}"))
 }
 
 #[test]
 fn test_compress() {
+    use std::{fs::remove_file, path::Path};
     crate::utils::test::_ensure_clear_test_dir();
     let p = Path::new("./test/VSCode_1.0.0.0_Cno.tar.zst");
     if p.exists() {
@@ -67,6 +54,7 @@ fn test_compress() {
     compress(
         &"examples/VSCode".to_string(),
         &"./test/VSCode_1.0.0.0_Cno.tar.zst".to_string(),
+        &PackOptions::default(),
     )
     .unwrap();
     assert!(p.exists());
@@ -74,6 +62,7 @@ fn test_compress() {
 
 #[test]
 fn test_decompress() {
+    use std::path::Path;
     crate::utils::test::_ensure_clear_test_dir();
     if !Path::new("./test/VSCode_1.0.0.0_Cno.tar.zst").exists() {
         test_compress();
@@ -84,10 +73,29 @@ fn test_decompress() {
     }
 
     decompress(
-        &"./test/VSCode_1.0.0.0_Cno.tar.zst".to_string(),
+        File::open("./test/VSCode_1.0.0.0_Cno.tar.zst").unwrap(),
         &"./test/VSCode_1.0.0.0_Cno".to_string(),
     )
     .unwrap();
 
     assert!(Path::new("test/VSCode_1.0.0.0_Cno/package.toml").exists());
 }
+
+#[test]
+fn test_walk_compressed() {
+    crate::utils::test::_ensure_clear_test_dir();
+    if !std::path::Path::new("./test/VSCode_1.0.0.0_Cno.tar.zst").exists() {
+        test_compress();
+    }
+    let mut names = Vec::new();
+    walk_compressed(
+        File::open("./test/VSCode_1.0.0.0_Cno.tar.zst").unwrap(),
+        |_, name, _| {
+            names.push(name);
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert!(names.contains(&"package.toml".to_string()));
+    assert!(names.contains(&"VSCode/Code.exe".to_string()));
+}
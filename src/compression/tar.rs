@@ -1,11 +1,11 @@
 use anyhow::{anyhow, Result};
-use std::fs::{create_dir_all, remove_dir_all, File};
-use std::path::Path;
-use tar::{Archive, Builder};
+use std::fs::{create_dir_all, read_dir, remove_dir_all, File};
+use std::io::{empty, Read, Write};
+use std::path::{Path, PathBuf};
+use tar::{Archive, Builder, EntryType, Header};
 
-pub fn release_tar(source: &String, into: &String) -> Result<()> {
-    let file = File::open(source)?;
-    let mut archive = Archive::new(file);
+pub fn release_tar<R: Read>(reader: R, into: &String) -> Result<()> {
+    let mut archive = Archive::new(reader);
 
     // 覆盖解压
     let p = Path::new(into);
@@ -18,12 +18,96 @@ pub fn release_tar(source: &String, into: &String) -> Result<()> {
     Ok(())
 }
 
+// 流式遍历 tar 中的条目，不写入磁盘
+pub fn walk_tar<R, F>(reader: R, mut visit: F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(&Header, String, &mut dyn Read) -> Result<()>,
+{
+    let mut archive = Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let header = entry.header().clone();
+        visit(&header, name, &mut entry)?;
+    }
+    Ok(())
+}
+
 pub fn pack_tar(source: &String, store_at: &String) -> Result<()> {
     let file = File::create(store_at)
         .map_err(|e| anyhow!("Error:Failed to create file at '{store_at}' : {e}"))?;
-    let mut archive = Builder::new(file);
-    archive.append_dir_all(".", source)?;
-    archive.finish()?;
+    pack_tar_into(source, file, None)?;
+    Ok(())
+}
+
+// 递归列出目录中的全部条目，同级条目按名称排序
+fn collect_sorted_entries(dir: &Path, res: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = Vec::new();
+    for entry in read_dir(dir)? {
+        entries.push(entry?.path());
+    }
+    entries.sort();
+    for path in entries {
+        res.push(path.clone());
+        if path.is_dir() {
+            collect_sorted_entries(&path, res)?;
+        }
+    }
+    Ok(())
+}
+
+// 条目按路径排序，并统一修改时间、所有者与权限
+fn append_reproducible<W: Write>(
+    archive: &mut Builder<W>,
+    source: &String,
+    mtime: u64,
+) -> Result<()> {
+    let source_path = Path::new(source);
+    let mut entries = Vec::new();
+    collect_sorted_entries(source_path, &mut entries)?;
+    for path in entries {
+        let name = Path::new(".").join(path.strip_prefix(source_path)?);
+        let mut header = Header::new_gnu();
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        if path.is_dir() {
+            header.set_entry_type(EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_size(0);
+            archive.append_data(&mut header, name, empty())?;
+        } else {
+            let f = File::open(&path)?;
+            header.set_entry_type(EntryType::Regular);
+            header.set_mode(0o644);
+            header.set_size(f.metadata()?.len());
+            archive.append_data(&mut header, name, f)?;
+        }
+    }
+    Ok(())
+}
+
+// 将目录打包写入输出流，返回输出流以便调用方继续处理，如结束压缩
+pub fn pack_tar_into<W: Write>(
+    source: &String,
+    writer: W,
+    reproducible_mtime: Option<u64>,
+) -> Result<W> {
+    let mut archive = Builder::new(writer);
+    if let Some(mtime) = reproducible_mtime {
+        append_reproducible(&mut archive, source, mtime)?;
+    } else {
+        archive.append_dir_all(".", source)?;
+    }
+    Ok(archive.into_inner()?)
+}
+
+// 可复现地打包，相同内容的目录得到字节一致的文件
+pub fn pack_tar_reproducible(source: &String, store_at: &String, mtime: u64) -> Result<()> {
+    let file = File::create(store_at)
+        .map_err(|e| anyhow!("Error:Failed to create file at '{store_at}' : {e}"))?;
+    pack_tar_into(source, file, Some(mtime))?;
     Ok(())
 }
 
@@ -32,7 +116,7 @@ fn test_pack_tar() {
     crate::utils::test::_ensure_clear_test_dir();
     let p = Path::new("./test/VSCode_1.0.0.0_Cno.tar");
     if p.exists() {
-        crate::compression::remove_file(p).unwrap();
+        std::fs::remove_file(p).unwrap();
     }
     pack_tar(
         &"examples/VSCode".to_string(),
@@ -50,7 +134,7 @@ fn test_release_tar() {
     }
 
     release_tar(
-        &"./test/VSCode_1.0.0.0_Cno.tar".to_string(),
+        File::open("./test/VSCode_1.0.0.0_Cno.tar").unwrap(),
         &"./test/VSCode_1.0.0.0_Cno".to_string(),
     )
     .unwrap();
@@ -61,10 +145,50 @@ fn test_release_tar() {
     use crate::utils::fs::try_recycle;
     try_recycle("test/VSCode_1.0.0.0_Cno/package.toml").unwrap();
     release_tar(
-        &"./test/VSCode_1.0.0.0_Cno.tar".to_string(),
+        File::open("./test/VSCode_1.0.0.0_Cno.tar").unwrap(),
         &"./test/VSCode_1.0.0.0_Cno".to_string(),
     )
     .unwrap();
 
     assert!(Path::new("test/VSCode_1.0.0.0_Cno/package.toml").exists());
 }
+
+#[test]
+fn test_pack_tar_reproducible() {
+    use crate::utils::fs::copy_dir;
+    use std::fs::read;
+    crate::utils::test::_ensure_clear_test_dir();
+
+    // 复制得到的目录修改时间与原目录不同
+    copy_dir("examples/VSCode", "test/VSCode_Copy").unwrap();
+    pack_tar_reproducible(
+        &"examples/VSCode".to_string(),
+        &"./test/VSCode_a.tar".to_string(),
+        946684800,
+    )
+    .unwrap();
+    pack_tar_reproducible(
+        &"test/VSCode_Copy".to_string(),
+        &"./test/VSCode_b.tar".to_string(),
+        946684800,
+    )
+    .unwrap();
+    assert_eq!(
+        read("test/VSCode_a.tar").unwrap(),
+        read("test/VSCode_b.tar").unwrap()
+    );
+
+    let mut archive = Archive::new(File::open("test/VSCode_a.tar").unwrap());
+    for entry in archive.entries().unwrap() {
+        let header = entry.unwrap().header().clone();
+        assert_eq!(header.mtime().unwrap(), 946684800);
+        assert_eq!(header.uid().unwrap(), 0);
+    }
+
+    release_tar(
+        File::open("./test/VSCode_a.tar").unwrap(),
+        &"./test/VSCode_a".to_string(),
+    )
+    .unwrap();
+    assert!(Path::new("test/VSCode_a/workflows/setup.toml").exists());
+}
@@ -1,51 +1,68 @@
-use std::{cmp::max, fs::File};
+use std::{
+    io::{BufReader, Read, Write},
+    thread::available_parallelism,
+};
 
-use anyhow::{anyhow, Result};
-use zstd::stream::{copy_decode, copy_encode};
+use anyhow::Result;
+use zstd::stream::{Decoder, Encoder};
 
-pub fn compress_zstd(source: &String, into: &String) -> Result<()> {
-    let source_file = File::open(source)?;
-    let into_file = File::create(into)?;
-    copy_encode(source_file, into_file, 0)?;
-    Ok(())
-}
+use crate::types::pack::PackOptions;
 
-pub fn decompress_zstd(source: &String, into: &String) -> Result<()> {
-    let source_file = File::open(source)?;
-    let into_file = File::create(into)?;
-    copy_decode(source_file, into_file)?;
-    Ok(())
-}
+// 长窗口模式使用的窗口大小，不超过解压端默认接受的上限（128 MiB）
+const LONG_WINDOW_LOG: u32 = 27;
 
-pub fn fast_decompress_zstd(raw: &Vec<u8>) -> Result<Vec<u8>> {
-    // 首先尝试将目标缓存配置为 *6，对应 16% 的压缩率，理论上能应付 95% 的包
-    if let Ok(res) = zstd::bulk::decompress(raw, max(raw.capacity() * 6, 1024 * 1024)) {
-        return Ok(res);
+// 创建流式 zstd 编码器，调用方写入完成后需要调用 finish
+pub fn zstd_encoder<W: Write>(writer: W, options: &PackOptions) -> Result<Encoder<'static, W>> {
+    let mut encoder = Encoder::new(writer, options.level)?;
+    let threads = if options.threads == 0 {
+        available_parallelism().map(|n| n.get() as u32).unwrap_or(1)
+    } else {
+        options.threads
+    };
+    // 多线程模式的输出与线程数无关，不影响可复现打包
+    encoder.multithread(threads)?;
+    if options.long_window {
+        encoder.long_distance_matching(true)?;
+        encoder.window_log(LONG_WINDOW_LOG)?;
     }
+    Ok(encoder)
+}
 
-    // 10 倍兜底
-    zstd::bulk::decompress(raw, max(raw.capacity() * 10, 1024 * 1024))
-        .map_err(|e| anyhow!("Error:Failed to fast decompress : {e}"))
+// 创建流式 zstd 解码器，允许长窗口模式打包的内容
+pub fn zstd_decoder<R: Read>(reader: R) -> Result<Decoder<'static, BufReader<R>>> {
+    let mut decoder = Decoder::new(reader)?;
+    decoder.window_log_max(LONG_WINDOW_LOG)?;
+    Ok(decoder)
 }
 
 #[test]
 fn test_compress_zstd() {
+    use std::fs::File;
     crate::utils::test::_ensure_clear_test_dir();
     use std::path::Path;
     let p = Path::new("./test/package.toml.zst");
     if p.exists() {
         std::fs::remove_file(p).unwrap();
     }
-    compress_zstd(
-        &"examples/VSCode/package.toml".to_string(),
-        &"./test/package.toml.zst".to_string(),
+    let options = PackOptions {
+        level: 19,
+        threads: 2,
+        long_window: true,
+        ..Default::default()
+    };
+    let mut encoder = zstd_encoder(File::create(p).unwrap(), &options).unwrap();
+    std::io::copy(
+        &mut File::open("examples/VSCode/package.toml").unwrap(),
+        &mut encoder,
     )
     .unwrap();
+    encoder.finish().unwrap();
     assert!(p.exists());
 }
 
 #[test]
 fn test_decompress_zstd() {
+    use std::fs::File;
     crate::utils::test::_ensure_clear_test_dir();
     use std::path::Path;
     if !Path::new("./test/package.toml.zst").exists() {
@@ -56,11 +73,8 @@ fn test_decompress_zstd() {
         std::fs::remove_file(target).unwrap();
     }
 
-    decompress_zstd(
-        &"./test/package.toml.zst".to_string(),
-        &"test/package.toml".to_string(),
-    )
-    .unwrap();
+    let mut decoder = zstd_decoder(File::open("./test/package.toml.zst").unwrap()).unwrap();
+    std::io::copy(&mut decoder, &mut File::create(target).unwrap()).unwrap();
 
     assert!(target.exists());
 }
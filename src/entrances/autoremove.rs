@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use std::time::Instant;
+
+use crate::{
+    log,
+    types::{
+        history::{HistoryOperation, HistoryPackage},
+        install_record::{InstallReason, InstallRecord},
+    },
+    utils::{
+        fmt_print::fmt_package_line, fs::read_sub_dir, get_bare_apps, history::record_history,
+        install_record::read_install_record, term::ask_yn_strict,
+    },
+};
+
+use super::{info, info_local, uninstall};
+
+// 查找未被用户显式请求安装的包，即安装时通过 --reason 标记为 batch 或 import 的包，没有安装记录的包视为显式安装
+pub fn get_autoremove_candidates() -> Result<Vec<(String, String, InstallRecord)>> {
+    let app_dir = get_bare_apps()?;
+    let mut res = Vec::new();
+    for scope in read_sub_dir(app_dir.clone())? {
+        for name in read_sub_dir(app_dir.join(&scope))? {
+            // 跳过无效的安装目录，交给 clean 和 doctor 处理
+            if info(Some(scope.clone()), &name).is_err() {
+                continue;
+            }
+            let ctx_path = app_dir.join(&scope).join(&name).join(".nep_context");
+            if let Some(record) = read_install_record(&ctx_path)? {
+                if record.reason != InstallReason::Explicit {
+                    res.push((scope.clone(), name, record));
+                }
+            }
+        }
+    }
+    Ok(res)
+}
+
+pub fn autoremove() -> Result<usize> {
+    let candidates = get_autoremove_candidates()?;
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    // 询问是否执行
+    let tip = candidates.iter().fold(
+        "\nPackages never explicitly requested:\n".to_string(),
+        |acc, (scope, name, record)| {
+            acc + &fmt_package_line(
+                scope,
+                name,
+                &record.reason.to_string(),
+                Some(record.source.to_string()),
+            )
+        },
+    );
+    println!("{tip}");
+    if !ask_yn_strict(
+        format!(
+            "Ready to uninstall those {} packages, continue?",
+            candidates.len()
+        ),
+        false,
+    ) {
+        return Err(anyhow!("Error:Operation canceled by user"));
+    }
+
+    let started = Instant::now();
+    let mut journal = Vec::new();
+    let mut errors = Vec::new();
+    for (scope, name, _) in candidates {
+        let version = info_local(&scope, &name).ok().map(|(_, diff)| diff.version);
+        journal.push(HistoryPackage::new(&scope, &name, version, None));
+        if let Err(e) = uninstall(Some(scope.clone()), &name) {
+            log!("Warning:Failed to uninstall '{scope}/{name}' : {e}");
+            errors.push(format!("{scope}/{name} : {e}"));
+        }
+    }
+    let count = journal.len();
+    let res = if errors.is_empty() {
+        Ok(count)
+    } else {
+        Err(anyhow!(
+            "Error:Failed to remove {} of {count} packages : {}",
+            errors.len(),
+            errors.join("; ")
+        ))
+    };
+    record_history(HistoryOperation::Uninstall, journal, started, &res);
+    res
+}
+
+#[test]
+fn test_autoremove() {
+    use crate::types::install_record::InstallSource;
+    use crate::utils::{get_path_apps, install_record::write_install_record};
+
+    crate::utils::test::_ensure_testing_vscode();
+    let ctx_path = get_path_apps(&"Microsoft".to_string(), &"VSCode".to_string(), false)
+        .unwrap()
+        .join(".nep_context");
+    let record_bak = read_install_record(&ctx_path).unwrap().unwrap();
+
+    let is_candidate = || {
+        get_autoremove_candidates()
+            .unwrap()
+            .iter()
+            .any(|(_, name, _)| name == "VSCode")
+    };
+    assert!(!is_candidate());
+
+    write_install_record(
+        &ctx_path,
+        &InstallRecord::new(
+            InstallReason::Batch,
+            InstallSource::Local {
+                path: "test/VSCode".to_string(),
+            },
+        ),
+    )
+    .unwrap();
+    assert!(is_candidate());
+
+    write_install_record(&ctx_path, &record_bak).unwrap();
+}
+
+#[test]
+fn test_autoremove_after_install() {
+    use crate::utils::{
+        flags::{set_flag, Flag},
+        fs::copy_dir,
+        parse_inputs::ParseInputResEnum,
+    };
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_clear_test_dir();
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+    crate::utils::test::_ensure_testing_uninstalled("Microsoft", "Notepad");
+
+    // 同时安装多个包时，命令行中给出的包仍然是显式安装
+    copy_dir("examples/VSCode", "test/VSCode").unwrap();
+    copy_dir("examples/Notepad", "test/Notepad").unwrap();
+    crate::entrances::install_using_parsed(
+        vec![
+            ParseInputResEnum::LocalPath("test/VSCode".to_string()),
+            ParseInputResEnum::LocalPath("test/Notepad".to_string()),
+        ],
+        false,
+        None,
+    )
+    .unwrap();
+    let candidates = get_autoremove_candidates().unwrap();
+    assert!(!candidates
+        .iter()
+        .any(|(_, name, _)| name == "VSCode" || name == "Notepad"));
+
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+    crate::utils::test::_ensure_testing_uninstalled("Microsoft", "Notepad");
+}
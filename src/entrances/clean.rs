@@ -3,21 +3,22 @@ use std::{
     collections::HashSet,
     fs::{read_dir, remove_dir_all, remove_file},
     path::Path,
+    time::Instant,
 };
 
 use crate::{
     log, log_ok_last, p2s,
     parsers::parse_workflow,
-    types::{steps::Step, workflow::WorkflowNode},
+    types::{history::HistoryOperation, steps::Step, workflow::WorkflowNode},
     utils::{
-        get_bare_apps, get_path_apps, get_path_bin, get_path_cache, get_path_meta, parse_bare_temp,
-        term::ask_yn,
+        get_bare_apps, get_path_apps, get_path_bin, get_path_cache, get_path_meta,
+        history::record_history, parse_bare_temp, term::ask_yn,
     },
 };
 
 use super::info_local;
 
-fn get_valid_entrances(setup: Vec<WorkflowNode>) -> Vec<String> {
+pub(super) fn get_valid_entrances(setup: Vec<WorkflowNode>) -> Vec<String> {
     setup
         .into_iter()
         .filter_map(|node| {
@@ -34,6 +35,13 @@ fn get_valid_entrances(setup: Vec<WorkflowNode>) -> Vec<String> {
 }
 
 pub fn clean() -> Result<usize> {
+    let started = Instant::now();
+    let res = clean_trashes();
+    record_history(HistoryOperation::Clean, Vec::new(), started, &res);
+    res
+}
+
+fn clean_trashes() -> Result<usize> {
     let mut clean_list = Vec::new();
     let mut valid_entrances = HashSet::new();
 
@@ -1,7 +1,7 @@
 use crate::{
-    p2s,
-    types::cfg::Cfg,
-    utils::cfg::{get_config, set_config},
+    log, p2s,
+    types::{cfg::Cfg, mixed_fs::MixedFS, verifiable::Verifiable},
+    utils::cfg::{get_config, get_profile, set_config, set_config_value},
 };
 use anyhow::{anyhow, Error, Result};
 use toml::Value;
@@ -29,10 +29,10 @@ pub fn config_set(table: &String, key: &String, value: &String) -> Result<()> {
         |e: Error| anyhow!("Error:Failed to set value of '${key}' as '${value}' : ${e}");
     // 拿到这个值研究一下类型
     let (val, mut cfg) = get_toml_value(table, key).map_err(err_wrapper)?;
-    let table = cfg.get_mut(table).unwrap();
+    let tab = cfg.get_mut(table).unwrap();
     match val {
         Value::String(_) => {
-            table[key] = Value::String(value.to_owned());
+            tab[key] = Value::String(value.to_owned());
         }
         // Value::Boolean(_) => {
         //     let bool_value = value
@@ -60,11 +60,27 @@ pub fn config_set(table: &String, key: &String, value: &String) -> Result<()> {
         }
     }
 
-    // 写回
-    let updated_cfg = cfg.try_into().map_err(|e| {
+    // 校验
+    let updated_cfg: Cfg = cfg.try_into().map_err(|e| {
         anyhow!("Error:Failed to convert modified config to valid config struct : {e}")
     })?;
-    set_config(updated_cfg)?;
+    updated_cfg
+        .verify_self(&MixedFS::new(""))
+        .map_err(|e| anyhow!("Error:Invalid config : {e}"))?;
+
+    // 仅写回修改的配置项
+    set_config_value(table, key, Value::String(value.to_owned()))?;
+
+    // 检查是否被更高优先级的层级覆盖
+    if &config_get(table, key)? != value {
+        let origin = get_config()
+            .get_origins(get_profile().as_deref())?
+            .into_iter()
+            .find(|(k, _, _)| k == &format!("{table}.{key}"))
+            .map(|(_, _, origin)| origin.to_string())
+            .unwrap_or_default();
+        log!("Warning:Config value of '{table}.{key}' is overridden by '{origin}'");
+    }
 
     Ok(())
 }
@@ -82,9 +98,17 @@ pub fn config_get(table: &String, key: &String) -> Result<String> {
     Ok(str)
 }
 
-pub fn config_list() -> Result<String> {
+pub fn config_list(show_origin: bool) -> Result<String> {
     let cfg = get_config();
-    Ok(format!("{cfg:#?}"))
+    if !show_origin {
+        return Ok(format!("{cfg:#?}"));
+    }
+    let lines: Vec<String> = cfg
+        .get_origins(get_profile().as_deref())?
+        .into_iter()
+        .map(|(key, val, origin)| format!("{origin}\t{key} = {val}"))
+        .collect();
+    Ok(lines.join("\n"))
 }
 
 pub fn config_init() -> Result<String> {
@@ -94,7 +118,7 @@ pub fn config_init() -> Result<String> {
 }
 
 pub fn config_which() -> Result<String> {
-    let which = Cfg::use_which()?;
+    let which = Cfg::use_which(get_profile().as_deref())?;
     Ok(p2s!(which))
 }
 
@@ -147,7 +171,10 @@ fn test_config() {
     assert_eq!(get_base, new_base);
 
     // 测试 list
-    assert_eq!(config_list().unwrap(), format!("{new_cfg:#?}"));
+    assert_eq!(config_list(false).unwrap(), format!("{new_cfg:#?}"));
+    assert!(config_list(true)
+        .unwrap()
+        .contains(&format!("project:eptrc.toml\tlocal.base = \"{new_base}\"")));
 
     // 测试 which
     assert_eq!(config_which().unwrap(), "eptrc.toml".to_string());
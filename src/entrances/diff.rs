@@ -0,0 +1,359 @@
+use anyhow::{anyhow, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{metadata, read_to_string, remove_dir_all},
+    path::{Path, PathBuf},
+};
+use toml::Value;
+
+use super::{
+    meta::{find_workflows, generalize_workflows_permissions},
+    utils::{delta::collect_relative_paths, package::unpack_nep},
+    verify::verify,
+};
+use crate::{
+    log, log_ok_last, p2s,
+    parsers::parse_workflow_with_source,
+    signature::blake3::compute_hash_blake3,
+    types::{
+        diff::{FieldChange, FileChange, FileStat, PackageDiff, StepChange},
+        workflow::WorkflowNode,
+    },
+    utils::{is_debug_mode, permissions::diff_permissions},
+};
+
+// 准备用于对比的包目录，返回 (包目录，需要清理的临时目录)
+fn prepare_source(source: &String, verify_signature: bool) -> Result<(PathBuf, Option<PathBuf>)> {
+    let source_path = Path::new(source);
+    if !source_path.exists() {
+        return Err(anyhow!("Error:Can't find package '{source}'"));
+    }
+    // 源目录直接对比，无需复制到临时目录
+    if source_path.is_dir() {
+        verify(source)?;
+        return Ok((source_path.to_path_buf(), None));
+    }
+    let (inner, _, _, _) = unpack_nep(source, verify_signature)?;
+    let temp = inner.parent().map(|p| p.to_path_buf());
+    Ok((inner, temp))
+}
+
+fn remove_temp(temp: Option<PathBuf>) {
+    if let Some(temp) = temp {
+        if is_debug_mode() {
+            log!("Debug:Leaving temporary directory '{}'", p2s!(temp));
+        } else if remove_dir_all(&temp).is_err() {
+            log!(
+                "Warning:Failed to remove temporary directory '{}'",
+                p2s!(temp)
+            );
+        }
+    }
+}
+
+fn read_toml(path: &Path) -> Result<Value> {
+    let text =
+        read_to_string(path).map_err(|e| anyhow!("Error:Can't read '{}' : {e}", p2s!(path)))?;
+    toml::from_str(&text).map_err(|e| {
+        anyhow!(
+            "Error:Can't parse '{}' as legal toml file : {e}",
+            p2s!(path)
+        )
+    })
+}
+
+// 将 toml 值展开为 (字段路径，值) 表，数组作为整体比较
+fn flatten_value(prefix: &str, value: &Value, into: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Table(table) => {
+            for (key, val) in table {
+                let path = if prefix.is_empty() {
+                    key.to_owned()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_value(&path, val, into);
+            }
+        }
+        Value::String(s) => {
+            into.insert(prefix.to_string(), s.to_owned());
+        }
+        _ => {
+            into.insert(prefix.to_string(), value.to_string());
+        }
+    }
+}
+
+fn diff_fields(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let (mut old_map, mut new_map) = (BTreeMap::new(), BTreeMap::new());
+    flatten_value("", old, &mut old_map);
+    flatten_value("", new, &mut new_map);
+    let keys: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (old, new) = (old_map.get(key), new_map.get(key));
+            if old == new {
+                None
+            } else {
+                Some(FieldChange {
+                    key: key.to_owned(),
+                    old: old.cloned(),
+                    new: new.cloned(),
+                })
+            }
+        })
+        .collect()
+}
+
+// 将步骤头与步骤体合并为一张表，步骤体取出枚举内部的字段
+fn step_to_value(node: &WorkflowNode) -> Result<Value> {
+    let mut table = match Value::try_from(&node.header)? {
+        Value::Table(table) => table,
+        _ => toml::map::Map::new(),
+    };
+    if let Value::Table(body) = Value::try_from(&node.body)? {
+        for (_, fields) in body {
+            if let Value::Table(fields) = fields {
+                table.extend(fields);
+            }
+        }
+    }
+    Ok(Value::Table(table))
+}
+
+fn load_steps(
+    workflows: &[(String, String)],
+    name: &String,
+) -> Result<Vec<(String, WorkflowNode)>> {
+    match workflows.iter().find(|(n, _)| n == name) {
+        Some((_, path)) => Ok(parse_workflow_with_source(path)?.0),
+        None => Ok(Vec::new()),
+    }
+}
+
+// 按步骤键对比各工作流中步骤的增删改
+fn diff_workflows(old_dir: &Path, new_dir: &Path) -> Result<Vec<StepChange>> {
+    let old_workflows = find_workflows(&old_dir.join("workflows"));
+    let new_workflows = find_workflows(&new_dir.join("workflows"));
+    let mut names: Vec<&String> = old_workflows.iter().map(|(name, _)| name).collect();
+    for (name, _) in &new_workflows {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for name in names {
+        let old_steps = load_steps(&old_workflows, name)?;
+        let new_steps = load_steps(&new_workflows, name)?;
+        for (key, old_node) in &old_steps {
+            match new_steps.iter().find(|(k, _)| k == key) {
+                Some((_, new_node)) => {
+                    if old_node != new_node {
+                        changes.push(StepChange {
+                            workflow: name.to_owned(),
+                            key: key.to_owned(),
+                            old: Some(old_node.header.step.to_owned()),
+                            new: Some(new_node.header.step.to_owned()),
+                            fields: diff_fields(
+                                &step_to_value(old_node)?,
+                                &step_to_value(new_node)?,
+                            ),
+                        });
+                    }
+                }
+                None => changes.push(StepChange {
+                    workflow: name.to_owned(),
+                    key: key.to_owned(),
+                    old: Some(old_node.header.step.to_owned()),
+                    new: None,
+                    fields: Vec::new(),
+                }),
+            }
+        }
+        for (key, new_node) in &new_steps {
+            if !old_steps.iter().any(|(k, _)| k == key) {
+                changes.push(StepChange {
+                    workflow: name.to_owned(),
+                    key: key.to_owned(),
+                    old: None,
+                    new: Some(new_node.header.step.to_owned()),
+                    fields: Vec::new(),
+                });
+            }
+        }
+    }
+    Ok(changes)
+}
+
+// 收集包中除 package.toml 与工作流以外的文件
+fn collect_files(dir: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let (mut files, mut dirs) = (Vec::new(), Vec::new());
+    collect_relative_paths(dir, "", &mut files, &mut dirs)?;
+    Ok(files
+        .into_iter()
+        .filter(|file| file != "package.toml" && !file.starts_with("workflows/"))
+        .map(|file| {
+            let path = dir.join(&file);
+            (file, path)
+        })
+        .collect())
+}
+
+fn stat_file(path: &Path) -> Result<FileStat> {
+    Ok(FileStat {
+        size: metadata(path)?.len(),
+        hash: compute_hash_blake3(&p2s!(path))?,
+    })
+}
+
+fn diff_files(old_dir: &Path, new_dir: &Path) -> Result<Vec<FileChange>> {
+    let old_files = collect_files(old_dir)?;
+    let new_files = collect_files(new_dir)?;
+    let paths: BTreeSet<&String> = old_files.keys().chain(new_files.keys()).collect();
+
+    let mut changes = Vec::new();
+    for path in paths {
+        let old = old_files.get(path).map(|p| stat_file(p)).transpose()?;
+        let new = new_files.get(path).map(|p| stat_file(p)).transpose()?;
+        if old != new {
+            changes.push(FileChange {
+                path: path.to_owned(),
+                old,
+                new,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+fn diff_dirs(old_dir: &Path, new_dir: &Path) -> Result<PackageDiff> {
+    log!("Info:Comparing packages...");
+    let fields = diff_fields(
+        &read_toml(&old_dir.join("package.toml"))?,
+        &read_toml(&new_dir.join("package.toml"))?,
+    );
+    let steps = diff_workflows(old_dir, new_dir)?;
+    let (permissions_added, permissions_removed) = diff_permissions(
+        &generalize_workflows_permissions(&old_dir.join("workflows"))?,
+        &generalize_workflows_permissions(&new_dir.join("workflows"))?,
+    );
+    let files = diff_files(old_dir, new_dir)?;
+    log_ok_last!("Info:Comparing packages...");
+
+    Ok(PackageDiff {
+        fields,
+        steps,
+        permissions_added,
+        permissions_removed,
+        files,
+    })
+}
+
+// 对比两个包的字段、工作流、权限与文件，输入可以是 nep 包或源目录
+pub fn diff(old: &String, new: &String, verify_signature: bool) -> Result<PackageDiff> {
+    let (old_dir, old_temp) = prepare_source(old, verify_signature)?;
+    let res = prepare_source(new, verify_signature).and_then(|(new_dir, new_temp)| {
+        let res = diff_dirs(&old_dir, &new_dir);
+        remove_temp(new_temp);
+        res
+    });
+    remove_temp(old_temp);
+    res
+}
+
+#[test]
+fn test_diff() {
+    use crate::utils::{fs::copy_dir, fs::try_recycle};
+    use std::fs::write;
+    crate::utils::test::_ensure_clear_test_dir();
+
+    crate::pack(
+        &"./examples/VSCode".to_string(),
+        Some("./test/VSCode_1.75.4.0_Cno.nep".to_string()),
+        false,
+        &crate::types::pack::PackOptions::default(),
+    )
+    .unwrap();
+
+    // 相同的包没有差异
+    let res = diff(
+        &"./test/VSCode_1.75.4.0_Cno.nep".to_string(),
+        &"./examples/VSCode".to_string(),
+        false,
+    )
+    .unwrap();
+    assert!(res.is_empty());
+
+    // 修改版本号、工作流与文件
+    copy_dir("examples/VSCode", "test/VSCode").unwrap();
+    let package_text = read_to_string("test/VSCode/package.toml")
+        .unwrap()
+        .replace("1.75.4.0", "1.75.4.1");
+    write("test/VSCode/package.toml", package_text).unwrap();
+    let workflow_text = r#"[create_shortcut]
+name = "Create shortcut"
+step = "Link"
+source_file = "Code.exe"
+target_name = "VSCode"
+
+[run]
+step = "Execute"
+command = "Code.exe --version"
+"#;
+    write("test/VSCode/workflows/setup.toml", workflow_text).unwrap();
+    write("test/VSCode/VSCode/new.txt", "new").unwrap();
+    try_recycle("test/VSCode/VSCode/favicon.ico").unwrap();
+
+    let res = diff(
+        &"./test/VSCode_1.75.4.0_Cno.nep".to_string(),
+        &"test/VSCode".to_string(),
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        res.fields,
+        vec![FieldChange {
+            key: "package.version".to_string(),
+            old: Some("1.75.4.0".to_string()),
+            new: Some("1.75.4.1".to_string()),
+        }]
+    );
+
+    let modified = res
+        .steps
+        .iter()
+        .find(|s| s.key == "create_shortcut")
+        .unwrap();
+    assert_eq!(modified.old, Some("Link".to_string()));
+    assert_eq!(modified.new, Some("Link".to_string()));
+    assert_eq!(
+        modified.fields,
+        vec![FieldChange {
+            key: "target_name".to_string(),
+            old: Some("Visual Studio Code".to_string()),
+            new: Some("VSCode".to_string()),
+        }]
+    );
+    let removed = res.steps.iter().find(|s| s.key == "add_path").unwrap();
+    assert_eq!(removed.new, None);
+    let added = res.steps.iter().find(|s| s.key == "run").unwrap();
+    assert_eq!(added.old, None);
+    assert_eq!(added.new, Some("Execute".to_string()));
+    assert!(!res.permissions_added.is_empty());
+    assert!(!res.permissions_removed.is_empty());
+
+    let files: Vec<(&str, bool, bool)> = res
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.old.is_some(), f.new.is_some()))
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            ("VSCode/favicon.ico", true, false),
+            ("VSCode/new.txt", false, true)
+        ]
+    );
+    assert_eq!(res.files[1].new.as_ref().unwrap().size, 3);
+}
@@ -0,0 +1,424 @@
+use anyhow::{anyhow, Result};
+use std::{
+    collections::HashSet,
+    fs::{read_dir, read_to_string, remove_dir_all},
+    path::Path,
+};
+
+use crate::{
+    executor::{values_replacer, workflow_executor},
+    log, log_ok_last, p2s,
+    parsers::parse_workflow,
+    types::{
+        doctor::{DoctorIssue, DoctorIssueKind},
+        install_record::InstallReason,
+        interpretable::Interpretable,
+        matcher::PackageMatcher,
+        package::GlobalPackage,
+        software::Software,
+    },
+    utils::{
+        constants::{MIRROR_FILE_HELLO, MIRROR_FILE_PKG_SOFTWARE},
+        fs::try_recycle,
+        get_bare_apps, get_path_apps, get_path_bin, get_path_mirror,
+        install_record::{read_install_record, resolve_url_source},
+        mirror::{get_url_with_version_req, read_local_mirror_pkg_software},
+        parse_bare_temp,
+        path::parse_relative_path_with_located,
+        term::{ask_yn, ask_yn_strict},
+    },
+};
+
+use super::{
+    clean::get_valid_entrances, info_local, install::install_using_url,
+    meta::generalize_workflows_permissions, uninstall, utils::validator::installed_validator,
+};
+
+// 主程序缺失时包的校验会失败，此时不经校验读取并解释包信息
+fn read_package_unverified(app_path: &Path) -> Result<GlobalPackage> {
+    let app_str = p2s!(app_path);
+    let ctx_str = installed_validator(&app_str)?;
+    let pkg_path = p2s!(Path::new(&ctx_str).join("package.toml"));
+    let text = read_to_string(&pkg_path)
+        .map_err(|e| anyhow!("Error:Failed to read '{pkg_path}' : {e}"))?;
+    let global: GlobalPackage =
+        toml::from_str(&text).map_err(|e| anyhow!("Error:Invalid toml file '{pkg_path}' : {e}"))?;
+    let package_version = global.package.version.clone();
+    Ok(global.interpret(|raw| values_replacer(raw, 0, &app_str, &package_version)))
+}
+
+fn read_software_missing_main_program(app_path: &Path) -> Option<Software> {
+    let software = read_package_unverified(app_path).ok()?.software?;
+    let mp = software.main_program.as_ref()?;
+    if parse_relative_path_with_located(mp, &p2s!(app_path)).exists() {
+        None
+    } else {
+        Some(software)
+    }
+}
+
+// 检查已安装的包，返回有效的入口名称
+fn scan_apps(issues: &mut Vec<DoctorIssue>) -> Result<HashSet<String>> {
+    let mut valid_entrances = HashSet::new();
+    for scope_entry in read_dir(get_bare_apps()?)? {
+        let scope_entry = scope_entry?;
+        let scope_path = scope_entry.path();
+        if !scope_path.is_dir() {
+            continue;
+        }
+        let scope_name = p2s!(scope_entry.file_name());
+        for app_entry in read_dir(&scope_path)? {
+            let app_entry = app_entry?;
+            let app_path = app_entry.path();
+            if !app_path.is_dir() {
+                continue;
+            }
+            let app_name = p2s!(app_entry.file_name());
+            let app_str = p2s!(app_path);
+
+            // 校验安装目录并读取包信息
+            let software = match info_local(&scope_name, &app_name) {
+                Ok((global, _)) => global.software.unwrap(),
+                Err(e) => match read_software_missing_main_program(&app_path) {
+                    Some(software) => software,
+                    None => {
+                        issues.push(DoctorIssue {
+                            kind: DoctorIssueKind::BrokenPackage {
+                                scope: scope_name.clone(),
+                                name: app_name,
+                            },
+                            target: app_path,
+                            reason: e.to_string(),
+                        });
+                        continue;
+                    }
+                },
+            };
+
+            // 检查主程序是否存在
+            if let Some(mp) = software.main_program {
+                if !parse_relative_path_with_located(&mp, &app_str).exists() {
+                    issues.push(DoctorIssue {
+                        kind: DoctorIssueKind::MissingMainProgram {
+                            scope: scope_name.clone(),
+                            name: app_name.clone(),
+                            main_program: mp.clone(),
+                        },
+                        target: app_path.clone(),
+                        reason: format!("Main program '{mp}' not exist"),
+                    });
+                }
+            }
+
+            // 解析有效的入口名称
+            let setup_path = p2s!(app_path.join(".nep_context/workflows/setup.toml"));
+            if let Ok(setup) = parse_workflow(&setup_path) {
+                for entrance_full_name in get_valid_entrances(setup) {
+                    valid_entrances.insert(software.scope.clone() + "-" + &entrance_full_name);
+                    valid_entrances.insert(entrance_full_name);
+                }
+            }
+        }
+    }
+
+    Ok(valid_entrances)
+}
+
+pub fn doctor_scan() -> Result<Vec<DoctorIssue>> {
+    let mut issues = Vec::new();
+
+    // apps 目录
+    let valid_entrances = scan_apps(&mut issues)?;
+
+    // bin 目录，查找无主的入口
+    for entry in read_dir(get_path_bin()?)? {
+        let entry = entry?;
+        let name = p2s!(entry.file_name());
+        if !valid_entrances.contains(&name) {
+            issues.push(DoctorIssue {
+                kind: DoctorIssueKind::OrphanEntrance,
+                target: entry.path(),
+                reason: format!("Entrance '{name}' doesn't belong to any installed package"),
+            });
+        }
+    }
+
+    // mirror 目录，查找缺失索引的镜像源
+    for entry in read_dir(get_path_mirror()?)? {
+        let entry = entry?;
+        let p = entry.path();
+        if !p.is_dir() {
+            continue;
+        }
+        let name = p2s!(entry.file_name());
+        let reason = if !p.join(MIRROR_FILE_HELLO).exists() {
+            Some(format!("Missing '{MIRROR_FILE_HELLO}'"))
+        } else if !p.join("index").exists() {
+            Some("Missing search index".to_string())
+        } else {
+            read_local_mirror_pkg_software(&name)
+                .err()
+                .map(|e| format!("Failed to read '{MIRROR_FILE_PKG_SOFTWARE}' : {e}"))
+        };
+        if let Some(reason) = reason {
+            issues.push(DoctorIssue {
+                kind: DoctorIssueKind::DanglingMirror { name },
+                target: p,
+                reason,
+            });
+        }
+    }
+
+    // temp 目录，查找残留的临时文件
+    let temp = parse_bare_temp()?;
+    if temp.exists() {
+        for entry in read_dir(temp)? {
+            let entry = entry?;
+            issues.push(DoctorIssue {
+                kind: DoctorIssueKind::TempLeftover,
+                target: entry.path(),
+                reason: "Temporary files left by a previous operation".to_string(),
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+// 从镜像源重新安装
+fn reinstall_from_mirror(scope: &String, name: &String, verify_signature: bool) -> Result<()> {
+    let (url, _) = get_url_with_version_req(PackageMatcher {
+        name: name.to_owned(),
+        scope: Some(scope.to_owned()),
+        mirror: None,
+        version_req: None,
+    })
+    .map_err(|e| anyhow!("Error:Can't find package '{scope}/{name}' in mirrors : {e}"))?;
+
+    // 移除原有的安装目录，保留原有的安装原因
+    let app_path = get_path_apps(scope, name, false)?;
+    let reason = read_install_record(&app_path.join(".nep_context"))
+        .ok()
+        .flatten()
+        .map(|record| record.reason)
+        .unwrap_or(InstallReason::Explicit);
+    if installed_validator(&p2s!(app_path)).is_ok() {
+        uninstall(Some(scope.to_owned()), name)?;
+    } else if app_path.exists() {
+        remove_dir_all(&app_path).map_err(|e| {
+            anyhow!(
+                "Error:Failed to remove '{p}', try deleting it manually : {e}",
+                p = p2s!(app_path)
+            )
+        })?;
+    }
+
+    let source = resolve_url_source(scope, name, &url);
+    install_using_url(&url, verify_signature, reason, source)?;
+    Ok(())
+}
+
+// 重新执行安装工作流
+fn rerun_setup(scope: &String, name: &String, main_program: &str) -> Result<()> {
+    let app_path = get_path_apps(scope, name, false)?;
+    let global = read_package_unverified(&app_path)?;
+    let located = p2s!(app_path);
+    let workflows = Path::new(&located).join(".nep_context/workflows");
+    let setup_flow = parse_workflow(&p2s!(workflows.join("setup.toml")))?;
+    let permissions = generalize_workflows_permissions(&workflows)?;
+
+    log!("Info:Running setup workflow...");
+    workflow_executor(setup_flow, located.clone(), global, permissions)?;
+    log_ok_last!("Info:Running setup workflow...");
+
+    if !parse_relative_path_with_located(main_program, &located).exists() {
+        return Err(anyhow!(
+            "Error:Main program '{main_program}' still not exist after running setup workflow"
+        ));
+    }
+    Ok(())
+}
+
+pub fn doctor_fix(issue: &DoctorIssue, verify_signature: bool) -> Result<()> {
+    match &issue.kind {
+        DoctorIssueKind::BrokenPackage { scope, name } => {
+            let res = reinstall_from_mirror(scope, name, verify_signature);
+            if let Err(e) = res {
+                // 无法重新安装时询问是否移除
+                if ask_yn_strict(
+                    format!(
+                        "Failed to reinstall '{scope}/{name}' : {e}, remove the broken folder?"
+                    ),
+                    false,
+                ) {
+                    try_recycle(&issue.target)
+                } else {
+                    Err(e)
+                }
+            } else {
+                Ok(())
+            }
+        }
+        DoctorIssueKind::MissingMainProgram {
+            scope,
+            name,
+            main_program,
+        } => {
+            let res = rerun_setup(scope, name, main_program);
+            if let Err(e) = res {
+                // 重新执行安装工作流无效时询问是否重新安装
+                if ask_yn(
+                    format!("Failed to repair '{scope}/{name}' : {e}, reinstall it from mirror?"),
+                    true,
+                ) {
+                    reinstall_from_mirror(scope, name, verify_signature)
+                } else {
+                    Err(e)
+                }
+            } else {
+                Ok(())
+            }
+        }
+        DoctorIssueKind::OrphanEntrance
+        | DoctorIssueKind::DanglingMirror { .. }
+        | DoctorIssueKind::TempLeftover => try_recycle(&issue.target),
+    }
+}
+
+// 返回（发现的问题数量，修复的问题数量）
+pub fn doctor(verify_signature: bool) -> Result<(usize, usize)> {
+    log!("Info:Diagnosing...");
+    let issues = doctor_scan()?;
+    log_ok_last!("Info:Diagnosing...");
+    if issues.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let tip = issues.iter().fold(
+        format!("\nFound {} issues:\n", issues.len()),
+        |acc, node| acc + &node.to_string(),
+    );
+    println!("{tip}");
+
+    // 逐个询问并修复
+    let mut fixed_count = 0;
+    for issue in &issues {
+        if !ask_yn(issue.fix_prompt(), true) {
+            continue;
+        }
+        if let Err(e) = doctor_fix(issue, verify_signature) {
+            log!(
+                "Warning:Failed to fix issue of '{p}' : {e}",
+                p = p2s!(issue.target)
+            );
+        } else {
+            fixed_count += 1;
+        }
+    }
+
+    Ok((issues.len(), fixed_count))
+}
+
+#[test]
+fn test_doctor() {
+    use crate::utils::flags::{set_flag, Flag};
+    use std::fs::{copy, create_dir_all, remove_file};
+    set_flag(Flag::Confirm, true);
+
+    // 安装 vscode
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+    crate::utils::test::_ensure_testing_vscode();
+    let app_path = get_path_apps(&"Microsoft".to_string(), &"VSCode".to_string(), false).unwrap();
+
+    // 创建无主的入口
+    let bin_path = get_path_bin().unwrap();
+    let entrance = read_dir(&bin_path)
+        .unwrap()
+        .find_map(|entry| entry.ok().map(|e| e.path()))
+        .unwrap();
+    let orphan_path = bin_path.join("doctor-orphan.cmd");
+    copy(entrance, &orphan_path).unwrap();
+
+    // 删除主程序
+    let mp_path = app_path.join("Code.exe");
+    remove_file(&mp_path).unwrap();
+
+    // 创建损坏的包
+    let broken_path = get_bare_apps().unwrap().join("FakeScope").join("Broken");
+    create_dir_all(&broken_path).unwrap();
+
+    let issues = doctor_scan().unwrap();
+    assert!(issues
+        .iter()
+        .any(|i| i.kind == DoctorIssueKind::OrphanEntrance && i.target == orphan_path));
+    assert!(issues.iter().any(|i| matches!(
+        &i.kind,
+        DoctorIssueKind::MissingMainProgram { name, .. } if name == "VSCode"
+    )));
+    assert!(issues.iter().any(|i| i.kind
+        == DoctorIssueKind::BrokenPackage {
+            scope: "FakeScope".to_string(),
+            name: "Broken".to_string()
+        }));
+
+    // 修复无主入口
+    let orphan = issues
+        .iter()
+        .find(|i| i.kind == DoctorIssueKind::OrphanEntrance && i.target == orphan_path)
+        .unwrap();
+    doctor_fix(orphan, false).unwrap();
+    assert!(!orphan_path.exists());
+
+    // 清理
+    remove_dir_all(broken_path.parent().unwrap()).unwrap();
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+}
+
+#[test]
+fn test_doctor_rerun_setup() {
+    use crate::entrances::install_using_package;
+    use crate::utils::flags::{set_flag, Flag};
+    use std::fs::{copy, create_dir_all, remove_file, write};
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+
+    // 安装工作流从备份中复制主程序
+    let pkg_path = crate::utils::test::_fork_example_with_version("examples/VSCode", "1.75.4.0");
+    let inner_path = Path::new(&pkg_path).join("VSCode");
+    create_dir_all(inner_path.join("backup")).unwrap();
+    copy(
+        inner_path.join("Code.exe"),
+        inner_path.join("backup/Code.exe"),
+    )
+    .unwrap();
+    let setup_path = Path::new(&pkg_path).join("workflows/setup.toml");
+    let setup_text = read_to_string(&setup_path).unwrap();
+    write(
+        &setup_path,
+        "[restore]\nstep = \"Copy\"\nfrom = \"./backup/*\"\nto = \"./\"\noverwrite = true\n\n"
+            .to_string()
+            + &setup_text,
+    )
+    .unwrap();
+    install_using_package(&pkg_path, false, InstallReason::Explicit, None).unwrap();
+
+    // 删除主程序后通过重新执行安装工作流修复
+    let mp_path = get_path_apps(&"Microsoft".to_string(), &"VSCode".to_string(), false)
+        .unwrap()
+        .join("Code.exe");
+    remove_file(&mp_path).unwrap();
+    let issues = doctor_scan().unwrap();
+    let issue = issues
+        .iter()
+        .find(|i| {
+            matches!(
+                &i.kind,
+                DoctorIssueKind::MissingMainProgram { name, .. } if name == "VSCode"
+            )
+        })
+        .unwrap();
+    doctor_fix(issue, false).unwrap();
+    assert!(mp_path.exists());
+
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::{cmp::Reverse, path::Path};
+
+use crate::{
+    signature::blake3::compute_hash_blake3_from_string,
+    types::du::{DiskUsage, PackageUsage},
+    utils::{
+        download::fill_url_template,
+        fs::{get_dir_size, read_sub_dir},
+        get_bare_apps, get_path_apps, get_path_cache, get_path_mirror, parse_bare_temp,
+    },
+};
+
+use super::info_online;
+
+fn get_size_or_zero(p: &Path) -> Result<u64> {
+    if p.exists() {
+        get_dir_size(p)
+    } else {
+        Ok(0)
+    }
+}
+
+// 缓存文件以下载 URL 的哈希命名，因此通过镜像源中的发行版本还原出该包的缓存
+fn get_package_cache_size(scope: &String, name: &String) -> Result<u64> {
+    let (item, url_template) = if let Ok(res) = info_online(scope, name, None) {
+        res
+    } else {
+        return Ok(0);
+    };
+    let cache_path = get_path_cache()?;
+    let mut size = 0;
+    for release in item.releases {
+        let url = fill_url_template(&url_template, scope, &item.name, &release.file_name)?;
+        let p = cache_path.join(compute_hash_blake3_from_string(&url)?);
+        if p.is_file() {
+            size += p.metadata()?.len();
+        }
+    }
+    Ok(size)
+}
+
+pub fn get_package_usage(scope: &String, name: &String) -> Result<PackageUsage> {
+    let app_path = get_path_apps(scope, name, false)?;
+    let total = get_size_or_zero(&app_path)?;
+    let context = get_size_or_zero(&app_path.join(".nep_context"))?;
+
+    Ok(PackageUsage {
+        scope: scope.to_owned(),
+        name: name.to_owned(),
+        app: total.saturating_sub(context),
+        context,
+        cache: get_package_cache_size(scope, name)?,
+    })
+}
+
+pub fn du() -> Result<DiskUsage> {
+    let app_dir = get_bare_apps()?;
+    let mut packages = Vec::new();
+    // 扫描本地 apps 目录，无效的安装目录同样占用空间
+    for scope in read_sub_dir(app_dir.clone())? {
+        for name in read_sub_dir(app_dir.join(&scope))? {
+            packages.push(get_package_usage(&scope, &name)?);
+        }
+    }
+    packages.sort_by_key(|node| Reverse(node.total()));
+
+    Ok(DiskUsage {
+        packages,
+        cache: get_size_or_zero(&get_path_cache()?)?,
+        temp: get_size_or_zero(&parse_bare_temp()?)?,
+        mirror: get_size_or_zero(&get_path_mirror()?)?,
+    })
+}
+
+#[test]
+fn test_du() {
+    crate::utils::test::_ensure_testing_vscode();
+    let res = du().unwrap();
+    let node = res
+        .packages
+        .iter()
+        .find(|node| node.scope == "Microsoft" && node.name == "VSCode")
+        .unwrap();
+    assert!(node.app > 0);
+    assert!(node.context > 0);
+    assert!(res.total() >= node.app + node.context);
+
+    // 按占用空间降序排列
+    for pair in res.packages.windows(2) {
+        assert!(pair[0].total() >= pair[1].total());
+    }
+}
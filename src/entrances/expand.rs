@@ -1,3 +1,4 @@
+use super::meta::generalize_workflows_permissions;
 use crate::{
     executor::workflow_executor,
     log, log_ok_last, p2s,
@@ -30,10 +31,12 @@ pub fn expand_workshop(workshop_path: &String) -> Result<()> {
 
     // 执行展开工作流
     let expand_workflow = parse_workflow(&p2s!(expand_workflow_path))?;
+    let permissions = generalize_workflows_permissions(&base.join("workflows"))?;
     workflow_executor(
         expand_workflow,
         p2s!(base.join(&package_struct.package.name)),
         package_struct,
+        permissions,
     ).map_err(|e|anyhow!("Error:Failed to execute expand workflow : '{e}'. If this error persists, consider changing 'preference.expandable' to 'low-priority' in config"))?;
 
     // 删掉展开工作流
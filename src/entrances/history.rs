@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use humantime::parse_duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{types::history::HistoryEntry, utils::history::read_history};
+
+// 按包名和时间范围筛选历史记录，较新的在前
+pub fn history(package: Option<String>, since: Option<String>) -> Result<Vec<HistoryEntry>> {
+    let since_timestamp = if let Some(since) = since {
+        let duration = parse_duration(&since).map_err(|e| {
+            anyhow!("Error:Failed to parse '{since}' as valid time span : {e}, e.g. '7d' '12h'")
+        })?;
+        let time = SystemTime::now()
+            .checked_sub(duration)
+            .unwrap_or(UNIX_EPOCH);
+        time.duration_since(UNIX_EPOCH)?.as_secs()
+    } else {
+        0
+    };
+
+    let mut res: Vec<HistoryEntry> = read_history()?
+        .into_iter()
+        .filter(|entry| entry.timestamp >= since_timestamp)
+        .filter(|entry| package.as_ref().is_none_or(|p| entry.involves(p)))
+        .collect();
+    res.reverse();
+    Ok(res)
+}
+
+#[test]
+fn test_history() {
+    use crate::types::history::{HistoryOperation, HistoryPackage};
+    use crate::utils::history::record_history;
+    use std::time::Instant;
+
+    let res: Result<()> = Ok(());
+    record_history(
+        HistoryOperation::Install,
+        vec![HistoryPackage::new(
+            "Microsoft",
+            "HistoryQuery",
+            None,
+            Some("1.0.0.0".to_string()),
+        )],
+        Instant::now(),
+        &res,
+    );
+    let entries = history(Some("historyquery".to_string()), Some("1h".to_string())).unwrap();
+    assert_eq!(entries[0].operation, HistoryOperation::Install);
+    assert!(entries.iter().all(|entry| entry.involves("HistoryQuery")));
+    assert!(history(None, Some("7 days ago".to_string())).is_err());
+}
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{fs::metadata, path::Path, time::UNIX_EPOCH};
 
 use anyhow::{anyhow, Result};
 
@@ -6,19 +6,20 @@ use crate::{
     p2s,
     parsers::parse_package,
     types::{
-        info::{Info, InfoDiff},
+        info::{Info, InfoDiff, InfoInstalled, InfoRelease},
         mirror::TreeItem,
         package::GlobalPackage,
     },
     utils::{
-        fs::read_sub_dir,
+        fs::{get_dir_size, read_sub_dir},
         get_path_apps, get_path_mirror,
+        install_record::read_install_record,
         mirror::{filter_release, read_local_mirror_pkg_software},
         path::find_scope_with_name,
     },
 };
 
-use super::utils::validator::installed_validator;
+use super::{meta::generalize_workflows_permissions, utils::validator::installed_validator};
 
 pub fn info_local(scope: &String, package_name: &String) -> Result<(GlobalPackage, InfoDiff)> {
     let local_path = get_path_apps(scope, package_name, false)?;
@@ -41,19 +42,67 @@ pub fn info_local(scope: &String, package_name: &String) -> Result<(GlobalPackag
     Ok((global.clone(), local))
 }
 
+// 优先使用安装记录中的安装时间，否则使用上下文目录的创建时间，单位为秒
+pub fn get_installed_at(scope: &String, package_name: &String) -> Result<u64> {
+    let ctx_path = get_path_apps(scope, package_name, false)?.join(".nep_context");
+    if let Some(record) = read_install_record(&ctx_path)? {
+        return Ok(record.installed_at);
+    }
+    let meta = metadata(&ctx_path).map_err(|e| {
+        anyhow!(
+            "Error:Failed to read metadata of '{p}' : {e}",
+            p = p2s!(ctx_path)
+        )
+    })?;
+    Ok(meta
+        .created()
+        .or(meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0))
+}
+
+// 读取已安装包的安装时间、磁盘占用、权限和安装记录
+fn info_installed(scope: &String, package_name: &String) -> Result<InfoInstalled> {
+    let local_path = get_path_apps(scope, package_name, false)?;
+    let ctx_path = local_path.join(".nep_context");
+
+    Ok(InfoInstalled {
+        installed_at: get_installed_at(scope, package_name)?,
+        disk_usage: get_dir_size(&local_path)?,
+        permissions: generalize_workflows_permissions(&ctx_path.join("workflows"))?,
+        record: read_install_record(&ctx_path)?,
+    })
+}
+
 // 第二个参数为 URL 模板
 pub fn info_online(
     scope: &String,
     package_name: &String,
     mirror: Option<String>,
 ) -> Result<(TreeItem, String)> {
+    info_online_with_mirror(scope, package_name, mirror)
+        .map(|(item, url_template, _)| (item, url_template))
+}
+
+// 额外返回提供该包的镜像源名称
+pub fn info_online_with_mirror(
+    scope: &String,
+    package_name: &String,
+    mirror: Option<String>,
+) -> Result<(TreeItem, String, String)> {
     // 定义匹配函数
     let item_matcher = |mirror_name: &String| {
         let pkg_software = read_local_mirror_pkg_software(mirror_name)?;
         if let Some(entry) = pkg_software.tree.get(scope) {
             for item in entry {
                 if &item.name == package_name {
-                    return Ok((item.to_owned(), pkg_software.url_template));
+                    return Ok((
+                        item.to_owned(),
+                        pkg_software.url_template,
+                        mirror_name.to_owned(),
+                    ));
                 }
             }
         }
@@ -88,9 +137,13 @@ pub fn info(scope: Option<String>, package_name: &String) -> Result<Info> {
         name: package_name.clone(),
         template: String::from("Software"),
         license: None,
+        icon: None,
         local: None,
         online: None,
         software: None,
+        releases: Vec::new(),
+        mirror: None,
+        installed: None,
     };
 
     // 扫描本地安装目录
@@ -98,12 +151,25 @@ pub fn info(scope: Option<String>, package_name: &String) -> Result<Info> {
     if local_path.exists() {
         let (global, local) = info_local(&scope, &package_name)?;
         info.license = global.package.license;
+        info.icon = global.package.icon;
         info.local = Some(local);
         info.software = global.software;
     }
 
     // 在线检查
-    if let Ok((item, _)) = info_online(&scope, &package_name, None) {
+    if let Ok((item, _, mirror_name)) = info_online_with_mirror(&scope, &package_name, None) {
+        info.mirror = Some(mirror_name);
+        let mut releases = item.releases.clone();
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+        info.releases = releases
+            .into_iter()
+            .map(|node| InfoRelease {
+                version: node.version.to_string(),
+                file_name: node.file_name,
+                size: node.size,
+                timestamp: node.timestamp,
+            })
+            .collect();
         let latest = filter_release(item.releases, None, false)?;
         info.online = Some(InfoDiff {
             version: latest.version.to_string(),
@@ -119,6 +185,15 @@ pub fn info(scope: Option<String>, package_name: &String) -> Result<Info> {
     }
 }
 
+// 在 info 的基础上补充需要扫描安装目录的详细信息
+pub fn info_detailed(scope: Option<String>, package_name: &String) -> Result<Info> {
+    let mut res = info(scope, package_name)?;
+    if let (Some(_), Some(software)) = (&res.local, &res.software) {
+        res.installed = Some(info_installed(&software.scope, &res.name)?);
+    }
+    Ok(res)
+}
+
 // #[test]
 // fn test_info() {
 // use crate::utils::test::_ensure_testing_vscode,
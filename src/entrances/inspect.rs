@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Result};
+
+use super::utils::package::inspect_nep;
+use crate::types::inspect::PackageEntry;
+
+// 统一路径分隔符并去除开头的 "./"
+fn normalize_entry_path(raw: &str) -> String {
+    let path = raw.replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    path.to_string()
+}
+
+// 列出内包中的全部条目，目录排在其内容之前
+pub fn ls_package(source_file: &String, verify_signature: bool) -> Result<Vec<PackageEntry>> {
+    let mut entries = Vec::new();
+    inspect_nep(source_file, verify_signature, |header, name, _| {
+        let path = normalize_entry_path(&name);
+        if !path.is_empty() && path != "." {
+            entries.push(PackageEntry {
+                path,
+                size: header.size()?,
+                is_dir: header.entry_type().is_dir(),
+            });
+        }
+        Ok(())
+    })?;
+    entries.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+    Ok(entries)
+}
+
+// 读取内包中单个文件的内容，签名校验通过后才会返回
+pub fn cat(source_file: &String, file: &String, verify_signature: bool) -> Result<Vec<u8>> {
+    let target = normalize_entry_path(file);
+    let mut content = None;
+    let mut is_dir = false;
+    inspect_nep(source_file, verify_signature, |header, name, reader| {
+        if normalize_entry_path(&name) == target {
+            if header.entry_type().is_dir() {
+                is_dir = true;
+            } else {
+                let mut buffer = Vec::new();
+                reader.read_to_end(&mut buffer)?;
+                content = Some(buffer);
+            }
+        }
+        Ok(())
+    })?;
+    if is_dir {
+        return Err(anyhow!(
+            "Error:'{file}' is a directory, use 'ept ls-package' to list its content"
+        ));
+    }
+    content.ok_or(anyhow!(
+        "Error:File '{file}' not found in package '{source_file}'"
+    ))
+}
+
+#[test]
+fn test_inspect() {
+    use crate::parsers::fast_parse_signature;
+    use std::fs::{metadata, read, File};
+    crate::utils::test::_ensure_clear_test_dir();
+
+    crate::pack(
+        &"./examples/VSCode".to_string(),
+        Some("./test/VSCode_1.75.4.0_Cno.nep".to_string()),
+        true,
+        &crate::types::pack::PackOptions::default(),
+    )
+    .unwrap();
+    let source_file = "./test/VSCode_1.75.4.0_Cno.nep".to_string();
+
+    let entries = ls_package(&source_file, true).unwrap();
+    let code = entries
+        .iter()
+        .find(|entry| entry.path == "VSCode/Code.exe")
+        .unwrap();
+    assert_eq!(
+        code.size,
+        metadata("examples/VSCode/VSCode/Code.exe").unwrap().len()
+    );
+    let dir_index = entries
+        .iter()
+        .position(|entry| entry.path == "VSCode" && entry.is_dir)
+        .unwrap();
+    let code_index = entries
+        .iter()
+        .position(|entry| entry.path == "VSCode/Code.exe")
+        .unwrap();
+    assert!(dir_index < code_index);
+
+    assert_eq!(
+        cat(&source_file, &"workflows/setup.toml".to_string(), true).unwrap(),
+        read("examples/VSCode/workflows/setup.toml").unwrap()
+    );
+    assert_eq!(
+        cat(&source_file, &".\\package.toml".to_string(), true).unwrap(),
+        read("examples/VSCode/package.toml").unwrap()
+    );
+    assert!(cat(&source_file, &"VSCode".to_string(), true).is_err());
+    assert!(cat(&source_file, &"README.md".to_string(), true).is_err());
+    assert!(ls_package(&"./examples/VSCode".to_string(), false).is_err());
+
+    // 签名者不是第一作者
+    crate::compression::release_tar(
+        File::open(&source_file).unwrap(),
+        &"./test/BadAuth".to_string(),
+    )
+    .unwrap();
+    let mut signature_struct =
+        fast_parse_signature(&mut read("test/BadAuth/signature.toml").unwrap()).unwrap();
+    signature_struct.package.signer = "Jack".to_string();
+    let text = toml::to_string_pretty(&signature_struct).unwrap();
+    std::fs::write("test/BadAuth/signature.toml", text).unwrap();
+    crate::compression::pack_tar(
+        &"test/BadAuth".to_string(),
+        &"test/BadAuth_1.75.4.0_Cno.nep".to_string(),
+    )
+    .unwrap();
+    let bad_file = "test/BadAuth_1.75.4.0_Cno.nep".to_string();
+    assert!(ls_package(&bad_file, true).is_err());
+    assert!(cat(&bad_file, &"package.toml".to_string(), true).is_err());
+    assert!(ls_package(&bad_file, false).is_ok());
+}
@@ -1,9 +1,11 @@
 use anyhow::{anyhow, Result};
 use std::fs::remove_dir_all;
 use std::path::Path;
+use std::time::Instant;
 
 use super::{
     info_local,
+    meta::generalize_workflows_permissions,
     utils::{
         package::{clean_temp, unpack_nep},
         validator::installed_validator,
@@ -14,24 +16,39 @@ use crate::{
     signature::blake3::compute_hash_blake3_from_string,
     utils::{
         cache::spawn_cache, download::download_nep, fs::move_or_copy, get_path_cache, is_qa_mode,
-        path::parse_relative_path_with_located, term::ask_yn,
+        path::parse_relative_path_with_located, permissions::check_permissions, term::ask_yn,
     },
 };
 use crate::{
-    entrances::{info, update_using_package},
-    utils::parse_inputs::ParseInputResEnum,
+    entrances::{info, update::update_using_package},
+    types::{
+        history::{HistoryOperation, HistoryPackage},
+        install_record::{InstallReason, InstallRecord, InstallSource},
+    },
+    utils::{
+        history::record_history,
+        install_record::{resolve_url_source, write_install_record},
+        parse_inputs::ParseInputResEnum,
+    },
 };
 use crate::{executor::workflow_executor, parsers::parse_workflow, utils::get_path_apps};
 use crate::{log, log_ok_last, p2s};
 
+// 安装并记录安装原因和来源，未提供来源时视为本地文件
 pub fn install_using_package(
     source_file: &String,
     verify_signature: bool,
+    reason: InstallReason,
+    source: Option<InstallSource>,
 ) -> Result<(String, String)> {
     log!("Info:Preparing to install with package '{source_file}'");
+    let source = source.unwrap_or_else(|| InstallSource::Local {
+        path: source_file.to_owned(),
+    });
 
     // 解包
-    let (temp_dir_inner_path, package_struct) = unpack_nep(source_file, verify_signature)?;
+    let (temp_dir_inner_path, package_struct, package_hash, signer) =
+        unpack_nep(source_file, verify_signature)?;
     log!(
         "Info:If installation fails, use 'ept uninstall \"{name}\"' to roll back",
         name = &package_struct.package.name
@@ -68,11 +85,15 @@ pub fn install_using_package(
             name = package.name,
             ver = diff.version,
         );
-        let res = update_using_package(source_file, verify_signature)?;
+        let res = update_using_package(source_file, verify_signature, Some(source))?;
         return Ok((res.scope, res.name));
     }
     log_ok_last!("Info:Resolving package...");
 
+    // 在执行任何工作流前检查权限
+    let permissions = generalize_workflows_permissions(&temp_dir_inner_path.join("workflows"))?;
+    check_permissions(&software.scope, &package.name, signer, &permissions)?;
+
     // 执行展开工作流
     let temp_dir_inner = p2s!(temp_dir_inner_path);
     if is_workshop_expandable(&temp_dir_inner) {
@@ -105,12 +126,21 @@ pub fn install_using_package(
     // 执行安装工作流
     let into_dir = p2s!(into_dir);
     log!("Info:Running setup workflow...");
-    workflow_executor(setup_workflow, into_dir.clone(), package_struct)?;
+    workflow_executor(
+        setup_workflow,
+        into_dir.clone(),
+        package_struct,
+        permissions,
+    )?;
     log_ok_last!("Info:Running setup workflow...");
 
     // 保存 nep 包的元信息
     let ctx_path = Path::new(&into_dir).join(".nep_context");
-    move_or_copy(temp_dir_inner_path, ctx_path)?;
+    move_or_copy(temp_dir_inner_path, ctx_path.clone())?;
+    write_install_record(
+        &ctx_path,
+        &InstallRecord::new(reason, source).with_package_hash(package_hash),
+    )?;
 
     // 检查安装是否完整
     log!("Info:Validating setup...");
@@ -142,14 +172,19 @@ pub fn install_using_package(
     Ok((software.scope, package.name))
 }
 
-pub fn install_using_url(url: &str, verify_signature: bool) -> Result<(String, String)> {
+pub fn install_using_url(
+    url: &str,
+    verify_signature: bool,
+    reason: InstallReason,
+    source: InstallSource,
+) -> Result<(String, String)> {
     // 下载文件到临时目录
     let cache_path = get_path_cache()?;
     let url_hash = compute_hash_blake3_from_string(url)?;
     let (p, cache_ctx) = download_nep(url, Some((cache_path, url_hash)))?;
 
     // 安装
-    let info = install_using_package(&p2s!(p), verify_signature)?;
+    let info = install_using_package(&p2s!(p), verify_signature, reason, Some(source))?;
 
     // 缓存下载的包
     spawn_cache(cache_ctx)?;
@@ -157,26 +192,70 @@ pub fn install_using_url(url: &str, verify_signature: bool) -> Result<(String, S
     Ok(info)
 }
 
-pub fn install_using_parsed(
+fn install_each(
     parsed: Vec<ParseInputResEnum>,
     verify_signature: bool,
+    reason: InstallReason,
+    journal: &mut Vec<HistoryPackage>,
 ) -> Result<Vec<(String, String)>> {
     let mut arr = Vec::new();
     for parsed in parsed {
         log!("Info:Start installing {}", parsed.preview());
+        // 先使用输入记录，安装成功后替换为实际的包名和版本
+        journal.push(match &parsed {
+            ParseInputResEnum::LocalPath(p) | ParseInputResEnum::Url(p) => HistoryPackage {
+                name: p.clone(),
+                from: None,
+                to: None,
+            },
+            ParseInputResEnum::PackageMatcher(p) => HistoryPackage::new(
+                &p.scope,
+                &p.name,
+                p.current_version.clone(),
+                Some(p.target_version.clone()),
+            ),
+        });
         let (scope, name) = match parsed {
-            ParseInputResEnum::LocalPath(p) => install_using_package(&p, false)?,
-            ParseInputResEnum::Url(u) => install_using_url(&u, false)?,
-            ParseInputResEnum::PackageMatcher(p) => {
-                install_using_url(&p.download_url, verify_signature)?
+            ParseInputResEnum::LocalPath(p) => {
+                install_using_package(&p, false, reason.clone(), None)?
             }
+            ParseInputResEnum::Url(u) => install_using_url(
+                &u,
+                false,
+                reason.clone(),
+                InstallSource::Url { url: u.clone() },
+            )?,
+            ParseInputResEnum::PackageMatcher(p) => install_using_url(
+                &p.download_url,
+                verify_signature,
+                reason.clone(),
+                resolve_url_source(&p.scope, &p.name, &p.download_url),
+            )?,
         };
+        let version = info_local(&scope, &name).ok().map(|(_, diff)| diff.version);
+        if let Some(node) = journal.last_mut() {
+            *node = HistoryPackage::new(&scope, &name, node.from.clone(), version);
+        }
         log!("Success:Package '{scope}/{name}' installed successfully");
         arr.push((scope, name));
     }
     Ok(arr)
 }
 
+// 命令行中给出的包都是用户显式请求的，仅在调用方指定时记录为批量或导入安装
+pub fn install_using_parsed(
+    parsed: Vec<ParseInputResEnum>,
+    verify_signature: bool,
+    reason: Option<InstallReason>,
+) -> Result<Vec<(String, String)>> {
+    let reason = reason.unwrap_or(InstallReason::Explicit);
+    let started = Instant::now();
+    let mut journal = Vec::new();
+    let res = install_each(parsed, verify_signature, reason, &mut journal);
+    record_history(HistoryOperation::Install, journal, started, &res);
+    res
+}
+
 // #[test]
 // fn test_install_using_url() {
 //     install_using_url(
@@ -218,7 +297,7 @@ fn test_install() {
 
     // 卸载
     if info_local(&"Microsoft".to_string(), &"VSCode".to_string()).is_ok() {
-        crate::uninstall(Some("Microsoft".to_string()), &"VSCode".to_string()).unwrap();
+        crate::entrances::uninstall(Some("Microsoft".to_string()), &"VSCode".to_string()).unwrap();
     }
 
     // 打包并安装
@@ -226,9 +305,16 @@ fn test_install() {
         &"./examples/VSCode".to_string(),
         Some("./test/VSCode_1.75.0.0_Cno (1).nep".to_string()),
         true,
+        &crate::types::pack::PackOptions::default(),
+    )
+    .unwrap();
+    install_using_package(
+        &"./test/VSCode_1.75.0.0_Cno (1).nep".to_string(),
+        true,
+        InstallReason::Explicit,
+        None,
     )
     .unwrap();
-    install_using_package(&"./test/VSCode_1.75.0.0_Cno (1).nep".to_string(), true).unwrap();
 
     assert!(shortcut_path.exists());
     assert!(entry1_path.exists() || entry2_path.exists());
@@ -236,11 +322,15 @@ fn test_install() {
     assert!(cx_path.exists());
 
     // 重复安装，会被要求使用升级，但是会由于同版本导致升级失败
-    assert!(
-        install_using_package(&"./test/VSCode_1.75.0.0_Cno (1).nep".to_string(), true).is_err()
-    );
+    assert!(install_using_package(
+        &"./test/VSCode_1.75.0.0_Cno (1).nep".to_string(),
+        true,
+        InstallReason::Explicit,
+        None
+    )
+    .is_err());
 
-    crate::uninstall(None, &"VSCode".to_string()).unwrap();
+    crate::entrances::uninstall(None, &"VSCode".to_string()).unwrap();
 
     assert!(!shortcut_path.exists());
     assert!(!entry1_path.exists() || entry2_path.exists());
@@ -258,18 +348,54 @@ fn test_install() {
     // 安装 CallInstaller，预期会因为不存在主程序 ${Desktop}/Call.exe 而安装失败
     copy_dir("examples/CallInstaller", "test/CallInstaller1").unwrap();
 
-    assert!(install_using_package(&"test/CallInstaller1".to_string(), false).is_err());
+    assert!(install_using_package(
+        &"test/CallInstaller1".to_string(),
+        false,
+        InstallReason::Explicit,
+        None
+    )
+    .is_err());
     crate::clean().unwrap();
 
     // 提供指定的主程序后安装成功
     std::fs::write(desktop_call_path, "114514").unwrap();
-    crate::uninstall(None, &"CallInstaller".to_string()).unwrap();
+    crate::entrances::uninstall(None, &"CallInstaller".to_string()).unwrap();
     copy_dir("examples/CallInstaller", "test/CallInstaller2").unwrap();
-    install_using_package(&"test/CallInstaller2".to_string(), false).unwrap();
+    install_using_package(
+        &"test/CallInstaller2".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
 
     // 清理
     remove_file(desktop_call_path).unwrap();
-    crate::uninstall(None, &"CallInstaller".to_string()).unwrap();
+    crate::entrances::uninstall(None, &"CallInstaller".to_string()).unwrap();
+}
+
+#[test]
+fn test_install_with_sensitive_permission() {
+    use crate::utils::flags::{set_flag, Flag};
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    set_flag(Flag::Confirm, true);
+
+    // 添加需要敏感权限的自定义命令
+    let pkg_path = crate::utils::test::_fork_example_with_version("examples/VSCode", "1.75.4.0");
+    let mut setup = OpenOptions::new()
+        .append(true)
+        .open(Path::new(&pkg_path).join("workflows/setup.toml"))
+        .unwrap();
+    setup
+        .write_all(b"\n\n[run]\nstep = \"Execute\"\ncommand = \"Code.exe --version\"\n")
+        .unwrap();
+
+    // 确认模式下不会自动授予敏感权限，缺省策略直接拒绝，策略为询问时同样报错
+    let err = install_using_package(&pkg_path, false, InstallReason::Explicit, None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Error:Package 'Microsoft/VSCode' requires permissions"));
 }
 
 #[test]
@@ -280,7 +406,13 @@ fn test_install_dism() {
 
     crate::utils::fs::copy_dir("examples/Dism++", "test/Dism++").unwrap();
 
-    install_using_package(&"test/Dism++".to_string(), false).unwrap();
+    install_using_package(
+        &"test/Dism++".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
     let stem_name = match get_arch().unwrap() {
         SysArch::X64 => "Dism++x64",
         SysArch::X86 => "Dism++x86",
@@ -293,6 +425,7 @@ fn test_install_dism() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_reg_entry() {
     use crate::types::{steps::TStep, workflow::WorkflowContext};
     use crate::utils::flags::{set_flag, Flag};
@@ -389,6 +522,7 @@ fn test_install_with_matcher() {
                 .to_string(),
         ),
         true,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
 
@@ -396,7 +530,7 @@ fn test_install_with_matcher() {
     crate::utils::test::_ensure_testing_vscode_uninstalled();
     let parsed =
         crate::utils::parse_inputs::parse_install_inputs(vec!["vscode".to_string()]).unwrap();
-    install_using_parsed(parsed, false).unwrap();
+    install_using_parsed(parsed, false, None).unwrap();
     assert!(
         info_local(&"Microsoft".to_string(), &"VSCode".to_string())
             .unwrap()
@@ -418,6 +552,7 @@ fn test_install_with_matcher() {
                 .to_string(),
         ),
         true,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
 
@@ -440,6 +575,7 @@ fn test_install_expandable() {
     set_flag(Flag::Confirm, true);
     crate::utils::test::_ensure_clear_test_dir();
     crate::utils::test::_ensure_testing_uninstalled("Microsoft", "VSCodeE");
+    crate::utils::test::_allow_testing_permission("download_file");
 
     // 断言原来的包中不包含这个二进制文件
     assert!(!Path::new("examples/VSCodeE/VSCodeE/Code.exe").exists());
@@ -450,7 +586,13 @@ fn test_install_expandable() {
 
     // 安装
     crate::utils::fs::copy_dir("examples/VSCodeE", "test/VSCodeE").unwrap();
-    install_using_package(&"test/VSCodeE".to_string(), false).unwrap();
+    install_using_package(
+        &"test/VSCodeE".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
 
     // 断言安装成功
     assert!(info_local(&"Microsoft".to_string(), &"VSCodeE".to_string()).is_ok());
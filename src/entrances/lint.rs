@@ -0,0 +1,694 @@
+use anyhow::{anyhow, Result};
+use inflector::cases::sentencecase::to_sentence_case;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fs::{read_to_string, write},
+    ops::Range,
+    path::Path,
+};
+use toml::{Table, Value};
+
+use crate::{
+    executor::{collect_unknown_values, correct_value_case},
+    parsers::{collect_author_problems, is_nep_version_compatible},
+    types::{
+        lint::{LintDiagnostic, LintEdit, LintReport},
+        mixed_fs::MixedFS,
+        package::GlobalPackage,
+        source_map::SourceMap,
+        steps::{Step, VerifyStepCtx},
+        verifiable::Verifiable,
+        workflow::{WorkflowHeader, WorkflowNode},
+    },
+};
+
+use super::{
+    utils::validator::{
+        collect_call_installer_problems, collect_main_program_problems, collect_manifest_problems,
+        collect_source_dir_problems, has_call_installer,
+    },
+    verify::get_manifest,
+};
+
+// 展开工作流需要最先检查，以便装箱单检查能感知其产生的文件
+const WORKFLOWS: [&str; 4] = ["expand.toml", "setup.toml", "update.toml", "remove.toml"];
+
+// 收集 TOML 值中的全部字符串及其键路径
+fn collect_strings(prefix: &str, val: &Value, res: &mut Vec<(String, String)>) {
+    match val {
+        Value::String(s) => res.push((prefix.to_string(), s.to_owned())),
+        Value::Array(arr) => {
+            for (i, v) in arr.iter().enumerate() {
+                collect_strings(&format!("{prefix}[{i}]"), v, res);
+            }
+        }
+        Value::Table(table) => {
+            for (k, v) in table {
+                collect_strings(&format!("{prefix}.{k}"), v, res);
+            }
+        }
+        _ => {}
+    }
+}
+
+// 检查字符串中的内置变量拼写，返回修正后的值
+fn lint_values(file: &str, key_path: &str, raw: &str, res: &mut Vec<LintDiagnostic>) -> String {
+    let mut fixed = raw.to_string();
+    for unknown in collect_unknown_values(raw) {
+        let d = LintDiagnostic::warning(
+            "unknown-value",
+            file,
+            Some(key_path.to_string()),
+            format!("Unknown value '{unknown}' in '{raw}', check if it's a spelling mistake"),
+        );
+        if let Some(correct) = correct_value_case(&unknown) {
+            fixed = fixed.replace(&unknown, &correct);
+            res.push(d.with_suggestion(format!("use '{correct}' instead")));
+        } else {
+            res.push(d);
+        }
+    }
+    fixed
+}
+
+// 为同一字段的全部诊断附加修复编辑
+fn attach_edit(res: &mut [LintDiagnostic], key_path: &str, from: &str, to: &str) {
+    if from == to {
+        return;
+    }
+    for d in res.iter_mut() {
+        if d.key_path.as_deref() == Some(key_path) && d.suggestion.is_some() {
+            *d = d.clone().with_edit(from.to_string(), to.to_string());
+        }
+    }
+}
+
+fn syntax_diagnostic(file: &str, text: &str, e: &toml::de::Error) -> LintDiagnostic {
+    let d = LintDiagnostic::error(
+        "toml-syntax",
+        file,
+        None,
+        format!("Invalid toml file : {}", e.message().trim_end()),
+    );
+    if let Some(span) = e.span() {
+        let (line, column) = SourceMap::new(file, text).line_col(span.start);
+        d.with_position(line, column)
+    } else {
+        d
+    }
+}
+
+// 根据键路径为诊断补充行列号
+fn locate_diagnostics(source_dir: &str, res: &mut [LintDiagnostic]) {
+    let mut maps: HashMap<String, SourceMap> = HashMap::new();
+    for d in res.iter_mut() {
+        let key_path = if let (Some(key_path), None) = (&d.key_path, d.line) {
+            key_path
+        } else {
+            continue;
+        };
+        let map = maps.entry(d.file.clone()).or_insert_with(|| {
+            let text = read_to_string(Path::new(source_dir).join(&d.file)).unwrap_or_default();
+            SourceMap::new(&d.file, &text)
+        });
+        if let Some(span) = map.span(key_path) {
+            let (line, column) = map.line_col(span.start);
+            d.line = Some(line);
+            d.column = Some(column);
+        }
+    }
+}
+
+fn lint_package(source_dir: &str, res: &mut Vec<LintDiagnostic>) -> Option<GlobalPackage> {
+    let file = "package.toml";
+    let text = read_to_string(Path::new(source_dir).join(file)).ok()?;
+    let value: Value = match toml::from_str(&text) {
+        Ok(v) => v,
+        Err(e) => {
+            res.push(syntax_diagnostic(file, &text, &e));
+            return None;
+        }
+    };
+
+    if let Some(nep) = value.get("nep").and_then(|v| v.as_str()) {
+        if let Err(e) = is_nep_version_compatible(&nep.to_string(), env!("CARGO_PKG_VERSION")) {
+            res.push(LintDiagnostic::error(
+                "nep-version",
+                file,
+                Some("nep".to_string()),
+                e.to_string(),
+            ));
+        }
+    } else {
+        res.push(
+            LintDiagnostic::error(
+                "package-schema",
+                file,
+                Some("nep".to_string()),
+                "Field 'nep' undefined".to_string(),
+            )
+            .with_suggestion(format!(
+                "add 'nep = \"{}\"' at the top of the file",
+                env!("CARGO_PKG_VERSION").split('.').next().unwrap()
+            )),
+        );
+    }
+
+    // 内置变量拼写
+    let mut strings = Vec::new();
+    for table in ["package", "software"] {
+        if let Some(v) = value.get(table) {
+            collect_strings(table, v, &mut strings);
+        }
+    }
+    for (key_path, raw) in strings {
+        let fixed = lint_values(file, &key_path, &raw, res);
+        attach_edit(res, &key_path, &raw, &fixed);
+    }
+
+    // 缺少 nep 字段的问题已经报告，补齐后继续检查其它字段
+    let mut value = value;
+    if let Some(table) = value.as_table_mut() {
+        table.entry("nep").or_insert(Value::String("0".to_string()));
+    }
+    let global: GlobalPackage = match value.try_into() {
+        Ok(g) => g,
+        Err(e) => {
+            res.push(LintDiagnostic::error(
+                "package-schema",
+                file,
+                None,
+                format!("Can't validate package.toml : {e}"),
+            ));
+            return None;
+        }
+    };
+
+    for (key_path, problem) in collect_author_problems(&global.package.authors) {
+        res.push(LintDiagnostic::error(
+            "package-authors",
+            file,
+            Some(key_path),
+            problem,
+        ));
+    }
+
+    let mixed_fs = MixedFS::new(&format!("{source_dir}/{}", global.package.name));
+    if let Err(e) = global.package.verify_self(&mixed_fs) {
+        res.push(LintDiagnostic::error(
+            "package",
+            file,
+            Some("package".to_string()),
+            e.to_string(),
+        ));
+    }
+    if let Some(software) = &global.software {
+        if let Err(e) = software.verify_self(&mixed_fs) {
+            res.push(LintDiagnostic::error(
+                "software",
+                file,
+                Some("software".to_string()),
+                e.to_string(),
+            ));
+        }
+    } else {
+        res.push(LintDiagnostic::error(
+            "package-schema",
+            file,
+            Some("software".to_string()),
+            "Table 'software' undefined".to_string(),
+        ));
+    }
+
+    Some(global)
+}
+
+fn lint_workflow(
+    file: &str,
+    text: &str,
+    ctx: &VerifyStepCtx,
+    res: &mut Vec<LintDiagnostic>,
+) -> Vec<WorkflowNode> {
+    let table: Table = match toml::from_str(text) {
+        Ok(t) => t,
+        Err(e) => {
+            res.push(syntax_diagnostic(file, text, &e));
+            return Vec::new();
+        }
+    };
+
+    let mut nodes = Vec::new();
+    for (key, val) in table {
+        // 先检查可以机械修复的问题，再使用修复后的值进行步骤校验以避免重复报告
+        let mut fixed_val = val.clone();
+        let mut strings = Vec::new();
+        collect_strings(&key, &val, &mut strings);
+        for (key_path, raw) in strings {
+            if key_path == format!("{key}.step") {
+                continue;
+            }
+            let mut fixed = lint_values(file, &key_path, &raw, res);
+            let is_command = val.get("step").and_then(|s| s.as_str()) == Some("Execute")
+                && key_path == format!("{key}.command");
+            if is_command && fixed.contains('\\') {
+                fixed = fixed.replace('\\', "/");
+                res.push(
+                    LintDiagnostic::error(
+                        "execute-backslash",
+                        file,
+                        Some(key_path.clone()),
+                        format!("Backslash (\\) in '{raw}' is not allowed"),
+                    )
+                    .with_suggestion(format!("use forward slash (/) instead : '{fixed}'")),
+                );
+            }
+            attach_edit(res, &key_path, &raw, &fixed);
+            if fixed != raw {
+                if let Some(field) = key_path.strip_prefix(&format!("{key}.")) {
+                    if let Some(t) = fixed_val.as_table_mut() {
+                        if t.contains_key(field) {
+                            t.insert(field.to_string(), Value::String(fixed));
+                        }
+                    }
+                }
+            }
+        }
+
+        // 解析步骤
+        let mut header: WorkflowHeader = match fixed_val.clone().try_into() {
+            Ok(h) => h,
+            Err(e) => {
+                res.push(LintDiagnostic::error(
+                    "workflow-schema",
+                    file,
+                    Some(key.clone()),
+                    format!("Illegal workflow node : {e}"),
+                ));
+                continue;
+            }
+        };
+        if header.name.is_none() {
+            header.name = Some(to_sentence_case(&key));
+        }
+        // 位置由诊断中的键路径给出，不需要在信息中附加代码片段
+        let body = match Step::try_from_kv(key.clone(), fixed_val, &SourceMap::default()) {
+            Ok(b) => b,
+            Err(e) => {
+                res.push(LintDiagnostic::error(
+                    "workflow-schema",
+                    file,
+                    Some(key.clone()),
+                    e.to_string(),
+                ));
+                continue;
+            }
+        };
+        let node = WorkflowNode { header, body };
+        if let Err(e) = node.verify_step(ctx) {
+            res.push(LintDiagnostic::error(
+                "step",
+                file,
+                Some(key.clone()),
+                e.to_string(),
+            ));
+        }
+        nodes.push(node);
+    }
+    nodes
+}
+
+// 检查装箱单，展开工作流产生的文件视为存在
+fn lint_manifest(
+    pkg_content_path: &str,
+    expand: Vec<WorkflowNode>,
+    flows: Vec<WorkflowNode>,
+    res: &mut Vec<LintDiagnostic>,
+) {
+    let file = "workflows/setup.toml";
+    let mut fs = MixedFS::new(pkg_content_path);
+    let _expand_manifest = get_manifest(expand, &mut fs);
+    let manifest = get_manifest(flows, &mut fs);
+    let (invalid, missing) = collect_manifest_problems(manifest, &mut fs);
+    for e in invalid {
+        res.push(LintDiagnostic::error("manifest", file, None, e));
+    }
+    for path in missing {
+        let message = format!("Missing flow item '{path}' in '{pkg_content_path}'");
+        let d = if fs.var_warn_manifest {
+            LintDiagnostic::warning("manifest", file, None, message)
+        } else {
+            LintDiagnostic::error("manifest", file, None, message)
+        };
+        res.push(d.with_suggestion(format!(
+            "put '{path}' into the app directory or fix the step referencing it"
+        )));
+    }
+}
+
+// 字符串字面量是否表示给定的值，仅支持单行的基本字符串与字面量字符串
+fn is_string_literal_of(literal: &str, value: &str) -> bool {
+    let basic = format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    literal == basic || literal == format!("'{value}'")
+}
+
+// 按照键路径定位被诊断的字符串字面量并替换为修复后的值，保留文件中的注释和格式
+// 返回实际应用的修复数量
+fn apply_edits(source_dir: &str, diagnostics: &[LintDiagnostic]) -> Result<usize> {
+    let mut fixed = 0;
+    let files: HashSet<&String> = diagnostics
+        .iter()
+        .filter(|d| d.edit.is_some())
+        .map(|d| &d.file)
+        .collect();
+    for file in files {
+        let p = Path::new(source_dir).join(file);
+        let mut text = read_to_string(&p)?;
+        let map = SourceMap::new(file, &text);
+
+        // 同一字段的多个诊断共享同一个修复
+        let mut edits: Vec<(Range<usize>, &LintEdit)> = Vec::new();
+        for d in diagnostics.iter().filter(|d| &d.file == file) {
+            if let (Some(edit), Some(key_path)) = (&d.edit, &d.key_path) {
+                if let Some(span) = map.span(key_path) {
+                    if !edits.iter().any(|(s, _)| s == &span) {
+                        edits.push((span, edit));
+                    }
+                }
+            }
+        }
+
+        // 从后向前替换，避免前面的替换改变后面的位置
+        edits.sort_by_key(|(span, _)| Reverse(span.start));
+        for (span, edit) in edits {
+            if is_string_literal_of(&text[span.clone()], &edit.from) {
+                text.replace_range(span, &Value::String(edit.to.clone()).to_string());
+                fixed += 1;
+            }
+        }
+        write(&p, text)?;
+    }
+    Ok(fixed)
+}
+
+// 收集包源目录中的全部问题，fix 为 true 时自动修复机械性问题
+pub fn lint(source_dir: &String, fix: bool) -> Result<LintReport> {
+    let dir = Path::new(source_dir);
+    if !dir.is_dir() {
+        return Err(anyhow!(
+            "Error:Source '{source_dir}' is not an existing directory"
+        ));
+    }
+    let mut res = Vec::new();
+
+    // 目录结构
+    for problem in collect_source_dir_problems(source_dir)? {
+        res.push(
+            LintDiagnostic::error("structure", ".", None, problem).with_suggestion(
+                "the directory should contain only 'package.toml', 'workflows' and the app directory"
+                    .to_string(),
+            ),
+        );
+    }
+
+    // 包信息
+    let global = lint_package(source_dir, &mut res);
+    let pkg_content_path = global
+        .as_ref()
+        .map(|g| format!("{source_dir}/{}", g.package.name))
+        .unwrap_or(source_dir.to_owned());
+    if let Some(global) = &global {
+        for problem in collect_main_program_problems(source_dir, global) {
+            res.push(LintDiagnostic::error(
+                "main-program",
+                "package.toml",
+                Some("software.main_program".to_string()),
+                problem,
+            ));
+        }
+    }
+
+    // 工作流
+    let mut expand_nodes = Vec::new();
+    let mut manifest_nodes = Vec::new();
+    let mut have_setup = false;
+    let mut call_installer = HashMap::new();
+    for name in WORKFLOWS {
+        let file = format!("workflows/{name}");
+        let text = if let Ok(text) = read_to_string(dir.join(&file)) {
+            text
+        } else {
+            continue;
+        };
+        let ctx = VerifyStepCtx {
+            mixed_fs: MixedFS::new(if name == "setup.toml" {
+                &pkg_content_path
+            } else {
+                source_dir
+            }),
+            is_expand_flow: name == "expand.toml",
+        };
+        let mut nodes = lint_workflow(&file, &text, &ctx, &mut res);
+        call_installer.insert(name, has_call_installer(&nodes));
+        match name {
+            "expand.toml" => expand_nodes = nodes,
+            "setup.toml" => {
+                have_setup = true;
+                manifest_nodes.append(&mut nodes);
+            }
+            "update.toml" => manifest_nodes.append(&mut nodes),
+            _ => {}
+        }
+    }
+    if have_setup {
+        lint_manifest(&pkg_content_path, expand_nodes, manifest_nodes, &mut res);
+    }
+    if let Some(software) = global.and_then(|g| g.software) {
+        for (file, problem) in collect_call_installer_problems(
+            &software,
+            call_installer.get("setup.toml") == Some(&true),
+            call_installer.get("update.toml").copied(),
+            call_installer.get("remove.toml").copied(),
+        ) {
+            res.push(LintDiagnostic::error(
+                "call-installer",
+                &file,
+                None,
+                problem,
+            ));
+        }
+    }
+
+    locate_diagnostics(source_dir, &mut res);
+
+    // 修复后重新检查以报告剩余的问题
+    if fix && res.iter().any(|d| d.edit.is_some()) {
+        let fixed = apply_edits(source_dir, &res)?;
+        let report = lint(source_dir, false)?;
+        return Ok(LintReport { fixed, ..report });
+    }
+
+    Ok(LintReport {
+        diagnostics: res,
+        fixed: 0,
+    })
+}
+
+#[test]
+fn test_lint() {
+    use crate::types::lint::LintSeverity;
+    use crate::utils::fs::copy_dir;
+    crate::utils::test::_ensure_clear_test_dir();
+
+    // 示例包没有错误
+    let report = lint(&"examples/VSCode".to_string(), false).unwrap();
+    assert_eq!(report.count(LintSeverity::Error), 0);
+
+    // 构造多个问题
+    copy_dir("examples/VSCode", "test/VSCode").unwrap();
+    write(
+        "test/VSCode/workflows/setup.toml",
+        r#"[create_shortcut]
+step = "Link"
+source_file = "Code.exe"
+target_name = "Visual Studio Code"
+
+[log]
+step = "Log"
+msg = "Installed to ${appdata}"
+
+[run]
+step = "Execute"
+command = "Code.exe --dir .\\data"
+
+[missing]
+step = "Path"
+record = "Missing.exe"
+
+[note]
+step = "Log"
+msg = "Code.exe --dir .\\data"
+"#,
+    )
+    .unwrap();
+    let report = lint(&"test/VSCode".to_string(), false).unwrap();
+    let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.rule.as_str()).collect();
+    assert!(rules.contains(&"unknown-value"));
+    assert!(rules.contains(&"execute-backslash"));
+    assert!(rules.contains(&"manifest"));
+    let backslash = report
+        .diagnostics
+        .iter()
+        .find(|d| d.rule == "execute-backslash")
+        .unwrap();
+    assert_eq!(backslash.key_path, Some("run.command".to_string()));
+    assert_eq!(backslash.location(), "workflows/setup.toml:12:11");
+
+    // 修复机械性问题
+    let report = lint(&"test/VSCode".to_string(), true).unwrap();
+    assert_eq!(report.fixed, 2);
+    assert!(report
+        .diagnostics
+        .iter()
+        .all(|d| d.rule != "execute-backslash" && d.rule != "unknown-value"));
+    let text = read_to_string("test/VSCode/workflows/setup.toml").unwrap();
+    assert!(text.contains("Code.exe --dir ./data"));
+    assert!(text.contains("Installed to ${AppData}"));
+    // 只修复被诊断的字段，相同的值出现在其它字段时保持不变
+    assert!(text.contains(r#"msg = "Code.exe --dir .\\data""#));
+}
+
+#[test]
+fn test_lint_verify_rules() {
+    use crate::entrances::verify::verify;
+    use crate::types::lint::LintSeverity;
+    use crate::utils::{fs::copy_dir, random::random_short_string};
+    use std::fs::{create_dir_all, remove_file};
+
+    // 每种 verify 会拒绝的问题，lint 都应该报告为对应规则的错误
+    let check = |origin: &str, rule: &str, modify: &dyn Fn(&Path)| {
+        let dir = Path::new("test").join(random_short_string());
+        create_dir_all(&dir).unwrap();
+        copy_dir(origin, &dir).unwrap();
+        modify(&dir);
+        let dir_str = dir.to_string_lossy().to_string();
+        assert!(
+            verify(&dir_str).is_err(),
+            "verify should reject rule '{rule}'"
+        );
+        let report = lint(&dir_str, false).unwrap();
+        assert!(
+            report
+                .diagnostics
+                .iter()
+                .any(|d| d.rule == rule && d.severity == LintSeverity::Error),
+            "lint should report rule '{rule}', got {:?}",
+            report.diagnostics
+        );
+    };
+    let replace = |dir: &Path, file: &str, from: &str, to: &str| {
+        let p = dir.join(file);
+        let text = read_to_string(&p).unwrap();
+        assert!(text.contains(from));
+        write(p, text.replace(from, to)).unwrap();
+    };
+    let append_setup = |dir: &Path, text: &str| {
+        let p = dir.join("workflows/setup.toml");
+        let origin = read_to_string(&p).unwrap();
+        write(p, origin + "\n\n" + text).unwrap();
+    };
+
+    // 目录结构
+    check("examples/VSCode", "structure", &|dir| {
+        write(dir.join("extra.txt"), "").unwrap()
+    });
+
+    // 包信息
+    check("examples/VSCode", "toml-syntax", &|dir| {
+        replace(dir, "package.toml", "nep = \"0\"", "nep = ")
+    });
+    check("examples/VSCode", "package-schema", &|dir| {
+        replace(dir, "package.toml", "nep = \"0\"", "")
+    });
+    check("examples/VSCode", "nep-version", &|dir| {
+        replace(dir, "package.toml", "nep = \"0\"", "nep = \"99.0\"")
+    });
+    check("examples/VSCode", "package-authors", &|dir| {
+        replace(dir, "package.toml", "\"Cno <dsyourshy@qq.com>\"", "\"Cno\"")
+    });
+    check("examples/VSCode", "package", &|dir| {
+        replace(
+            dir,
+            "package.toml",
+            "version = \"1.75.4.0\"",
+            "version = \"abc\"",
+        )
+    });
+    check("examples/VSCode", "software", &|dir| {
+        replace(
+            dir,
+            "package.toml",
+            "upstream = \"https://code.visualstudio.com/\"",
+            "upstream = \"code\"",
+        )
+    });
+
+    // 工作流
+    check("examples/VSCode", "workflow-schema", &|dir| {
+        append_setup(dir, "[unknown]\nstep = \"Unknown\"\n")
+    });
+    check("examples/VSCode", "step", &|dir| {
+        append_setup(
+            dir,
+            "[link]\nstep = \"Link\"\nsource_file = \"Code.exe\"\ntarget_name = \"../VSCode\"\n",
+        )
+    });
+    check("examples/VSCode", "manifest", &|dir| {
+        append_setup(
+            dir,
+            "[missing]\nstep = \"Path\"\nrecord = \"Missing.exe\"\n",
+        )
+    });
+
+    // 调用安装器
+    check("examples/CallInstaller", "call-installer", &|dir| {
+        std::fs::copy(
+            "examples/VSCode/workflows/setup.toml",
+            dir.join("workflows/update.toml"),
+        )
+        .unwrap();
+    });
+    check("examples/CallInstaller", "call-installer", &|dir| {
+        remove_file(dir.join("workflows/remove.toml")).unwrap()
+    });
+    check("examples/CallInstaller", "call-installer", &|dir| {
+        replace(
+            dir,
+            "package.toml",
+            "main_program = \"${Desktop}/Call.exe\"",
+            "main_program = \"Installer.exe\"",
+        )
+    });
+    check("examples/CallInstaller", "call-installer", &|dir| {
+        replace(
+            dir,
+            "package.toml",
+            "main_program = \"${Desktop}/Call.exe\"",
+            "",
+        )
+    });
+
+    // 主程序版本号
+    check("examples/VSCode", "main-program", &|dir| {
+        replace(dir, "package.toml", "1.75.4.0", "1.76.0.0")
+    });
+    check("examples/VSCode", "main-program", &|dir| {
+        write(dir.join("VSCode/fake.exe"), "").unwrap();
+        replace(
+            dir,
+            "package.toml",
+            "main_program = \"Code.exe\"",
+            "main_program = \"fake.exe\"",
+        )
+    });
+}
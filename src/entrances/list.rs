@@ -1,12 +1,26 @@
 use anyhow::Result;
+use std::{cmp::Reverse, path::Path};
 
 use crate::{
-    log,
-    types::info::Info,
-    utils::{fs::read_sub_dir, get_bare_apps},
+    log, p2s,
+    types::{
+        extended_semver::ExSemVer,
+        info::Info,
+        list::{InstallKind, ListEntry, ListFilter, ListSortBy},
+        mirror::MirrorPkgSoftwareRelease,
+        policy::UpdateDecision,
+    },
+    utils::{
+        fs::read_sub_dir, get_bare_apps, install_record::read_install_record, pin::get_pin,
+        policy::decide_update,
+    },
 };
 
-use super::info::info;
+use super::{
+    du::get_package_usage,
+    expand::is_workshop_expandable,
+    info::{get_installed_at, info},
+};
 
 pub fn list() -> Result<Vec<Info>> {
     let app_dir = get_bare_apps()?;
@@ -31,8 +45,137 @@ pub fn list() -> Result<Vec<Info>> {
     Ok(res)
 }
 
+// 仅返回锁定规则和更新策略允许更新到的版本，使用 info 中已经读取的发行版本
+fn get_upgradable_version(scope: &str, info: &Info) -> Option<String> {
+    let local_instance = ExSemVer::parse(&info.local.as_ref()?.version).ok()?;
+    let online_instance = ExSemVer::parse(&info.online.as_ref()?.version).ok()?;
+    if online_instance <= local_instance {
+        return None;
+    }
+    let releases = info
+        .releases
+        .iter()
+        .filter_map(|node| {
+            Some(MirrorPkgSoftwareRelease {
+                file_name: node.file_name.to_owned(),
+                version: ExSemVer::parse(&node.version).ok()?,
+                size: node.size,
+                timestamp: node.timestamp,
+                integrity: None,
+            })
+        })
+        .collect();
+    match decide_update(scope, &info.name, releases, &local_instance) {
+        Ok(UpdateDecision::Update(release)) => Some(release.version.to_string()),
+        _ => None,
+    }
+}
+
+fn get_install_kind(install_path: &Path, info: &Info) -> InstallKind {
+    let ctx_str = p2s!(install_path.join(".nep_context"));
+    if is_workshop_expandable(&ctx_str) {
+        InstallKind::Expandable
+    } else if info
+        .software
+        .as_ref()
+        .and_then(|s| s.registry_entry.as_ref())
+        .is_some()
+    {
+        InstallKind::Installer
+    } else {
+        InstallKind::Portable
+    }
+}
+
+// 列出经过筛选和排序的已安装包，long 为 true 时读取详细信息
+pub fn list_entries(filter: &ListFilter, sort: ListSortBy, long: bool) -> Result<Vec<ListEntry>> {
+    let app_dir = get_bare_apps()?;
+    let need_date = long || sort == ListSortBy::Date;
+    let need_size = long || sort == ListSortBy::Size;
+    let mut res = vec![];
+    for scope in read_sub_dir(app_dir.clone())? {
+        for name in read_sub_dir(app_dir.join(&scope))? {
+            let install_path = app_dir.join(&scope).join(&name);
+            let mut entry = ListEntry {
+                scope: scope.clone(),
+                name: name.clone(),
+                info: None,
+                broken_reason: None,
+                install_path: install_path.clone(),
+                pin: None,
+                upgradable: None,
+                installed_at: None,
+                usage: None,
+                kind: None,
+                record: None,
+            };
+            match info(Some(scope.clone()), &name) {
+                Ok(info) => {
+                    entry.pin = get_pin(&scope, &info.name)?;
+                    entry.upgradable = get_upgradable_version(&scope, &info);
+                    if long {
+                        entry.kind = Some(get_install_kind(&install_path, &info));
+                    }
+                    if long || filter.from_mirror.is_some() {
+                        entry.record =
+                            read_install_record(&install_path.join(".nep_context")).unwrap_or(None);
+                    }
+                    entry.info = Some(info);
+                }
+                Err(e) => {
+                    if !filter.broken {
+                        log!("Warning:Skip invalid folder '{scope}/{name}' : {e}");
+                    }
+                    entry.broken_reason = Some(e.to_string());
+                }
+            }
+            if !filter.matches(&entry) {
+                continue;
+            }
+
+            // 仅对筛选后的包读取耗时的信息
+            if need_date && entry.info.is_some() {
+                entry.installed_at = get_installed_at(&scope, &name).ok();
+            }
+            if need_size {
+                entry.usage = Some(get_package_usage(&scope, &name)?);
+            }
+            res.push(entry);
+        }
+    }
+
+    match sort {
+        ListSortBy::Name => {
+            res.sort_by_key(|entry| (entry.scope.to_lowercase(), entry.name.to_lowercase()))
+        }
+        ListSortBy::Date => res.sort_by_key(|entry| Reverse(entry.installed_at.unwrap_or(0))),
+        ListSortBy::Size => {
+            res.sort_by_key(|entry| Reverse(entry.usage.as_ref().map(|u| u.total()).unwrap_or(0)))
+        }
+    }
+
+    Ok(res)
+}
+
 #[test]
 fn test_list() {
     let res = list().unwrap();
     println!("{res:#?}");
 }
+
+#[test]
+fn test_list_entries() {
+    crate::utils::test::_ensure_testing_vscode();
+    let filter = ListFilter {
+        scope: Some("microsoft".to_string()),
+        ..Default::default()
+    };
+    let res = list_entries(&filter, ListSortBy::Size, true).unwrap();
+    let node = res.iter().find(|node| node.name == "VSCode").unwrap();
+    assert!(node.usage.as_ref().unwrap().app > 0);
+    assert!(node.installed_at.is_some());
+    assert_eq!(node.kind, Some(InstallKind::Portable));
+    assert!(node.record.is_some());
+    assert!(res.iter().all(|node| node.scope == "Microsoft"));
+    print!("{}", node.format(true, true));
+}
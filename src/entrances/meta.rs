@@ -31,7 +31,7 @@ fn find_meta_target(
             // 作为路径使用，可以是一个包或者已经解包的目录
             let p = Path::new(&local_path);
             if p.exists() {
-                let (path, pkg) = unpack_nep(&local_path, verify_signature)?;
+                let (path, pkg, _, _) = unpack_nep(&local_path, verify_signature)?;
                 // verify(&p2s!(path))?;
                 return Ok((path.clone(), path.join("workflows"), pkg));
             }
@@ -55,34 +55,28 @@ fn find_meta_target(
     ))
 }
 
-pub fn meta(input: PackageInputEnum, verify_signature: bool) -> Result<MetaResult> {
-    // 解包
-    let (temp_dir_inner_path, workflow_path, global) = find_meta_target(input, verify_signature)?;
-    let temp_dir = p2s!(temp_dir_inner_path);
-
-    // 检查工作流存在
-    let exists_workflows: Vec<(String, String)> =
-        vec!["setup.toml", "update.toml", "remove.toml", "expand.toml"]
-            .into_iter()
-            .filter_map(|name| {
-                let p = workflow_path.join(name);
-                if p.exists() {
-                    Some((name.to_string(), p2s!(p)))
-                } else {
-                    None
-                }
-            })
-            .collect();
+// 返回存在的工作流 (文件名，路径)
+pub fn find_workflows(workflow_path: &Path) -> Vec<(String, String)> {
+    vec!["setup.toml", "update.toml", "remove.toml", "expand.toml"]
+        .into_iter()
+        .filter_map(|name| {
+            let p = workflow_path.join(name);
+            if p.exists() {
+                Some((name.to_string(), p2s!(p)))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
 
+// 收集工作流目录中所有工作流需要的权限，并合并同类权限
+pub fn generalize_workflows_permissions(workflow_path: &Path) -> Result<Vec<Permission>> {
     // 收集所有工作流
-    let total_workflow = exists_workflows
-        .clone()
-        .into_iter()
-        .map(|(_, p)| parse_workflow(&p).unwrap())
-        .fold(Vec::new(), |mut acc, mut x| {
-            acc.append(&mut x);
-            acc
-        });
+    let mut total_workflow = Vec::new();
+    for (_, p) in find_workflows(workflow_path) {
+        total_workflow.append(&mut parse_workflow(&p)?);
+    }
 
     // 收集并合并同类权限
     let mut map: HashMap<(PermissionLevel, PermissionKey), HashSet<String>> = HashMap::new();
@@ -113,6 +107,18 @@ pub fn meta(input: PackageInputEnum, verify_signature: bool) -> Result<MetaResul
         }
     });
 
+    Ok(permissions)
+}
+
+pub fn meta(input: PackageInputEnum, verify_signature: bool) -> Result<MetaResult> {
+    // 解包
+    let (temp_dir_inner_path, workflow_path, global) = find_meta_target(input, verify_signature)?;
+    let temp_dir = p2s!(temp_dir_inner_path);
+
+    // 检查工作流存在
+    let exists_workflows = find_workflows(&workflow_path);
+    let permissions = generalize_workflows_permissions(&workflow_path)?;
+
     Ok(MetaResult {
         temp_dir,
         permissions,
@@ -190,6 +196,7 @@ fn test_meta() {
                 level: PermissionLevel::Sensitive,
                 targets: vec![
                     "${AppData}/pwsh.exe".to_string(),
+                    "${ProgramFiles_X64}/Microsoft/128杜拉拉.dll".to_string(),
                     "${ProgramFiles_X64}/Microsoft/64.dll".to_string(),
                     "${SystemDrive}/system32/Windows/".to_string(),
                 ],
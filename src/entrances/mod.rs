@@ -1,12 +1,23 @@
+mod autoremove;
 mod clean;
 pub mod config;
+mod diff;
+mod doctor;
+mod du;
 mod expand;
+mod history;
 mod info;
+mod inspect;
 mod install;
+mod lint;
 mod list;
 mod meta;
 mod mirror;
+mod new;
 mod pack;
+mod pin;
+mod policy;
+mod sandbox;
 mod search;
 mod uninstall;
 mod update;
@@ -14,18 +25,29 @@ mod upgrade;
 mod utils;
 mod verify;
 
+pub use self::autoremove::autoremove;
 pub use self::clean::clean;
+pub use self::diff::diff;
+pub use self::doctor::doctor;
+pub use self::du::du;
 pub use self::expand::{expand_workshop, is_workshop_expandable};
-pub use self::info::{info, info_local, info_online};
+pub use self::history::history;
+pub use self::info::{info, info_detailed, info_local, info_online};
+pub use self::inspect::{cat, ls_package};
 pub use self::install::{install_using_package, install_using_parsed};
-pub use self::list::list;
+pub use self::lint::lint;
+pub use self::list::{list, list_entries};
 pub use self::meta::meta;
 pub use self::mirror::{
     auto_mirror_update_all, mirror_add, mirror_list, mirror_remove, mirror_update,
     mirror_update_all,
 };
+pub use self::new::new;
 pub use self::pack::pack;
+pub use self::pin::{pin, unpin};
+pub use self::policy::{policy_list, policy_set, policy_unset};
+pub use self::sandbox::sandbox_test;
 pub use self::search::search;
-pub use self::uninstall::uninstall;
-pub use self::update::{update_all, update_using_package, update_using_parsed};
+pub use self::uninstall::{uninstall, uninstall_using_parsed};
+pub use self::update::{update_all, update_using_parsed};
 pub use self::upgrade::upgrade;
@@ -0,0 +1,349 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{create_dir_all, read_dir, write},
+    path::Path,
+};
+use toml::{Table, Value};
+
+use crate::{
+    log, log_ok_last, p2s,
+    types::{
+        package::{GlobalPackage, Package},
+        scaffold::{ScaffoldDraft, ScaffoldOptions, ScaffoldWorkflow},
+        software::Software,
+    },
+    utils::{
+        exe_version::get_exe_version,
+        fs::copy_dir,
+        term::{ask_input, ask_multi_select, ask_select},
+    },
+};
+
+use super::verify::verify;
+
+const LANGUAGES: [&str; 3] = ["Multi", "zh-CN", "en-US"];
+
+// 列出程序目录顶层的可执行文件
+fn find_programs(source: &Path) -> Result<Vec<String>> {
+    let mut res = Vec::new();
+    for entry in read_dir(source).map_err(|e| {
+        anyhow!(
+            "Error:Failed to read source directory '{}' : {e}",
+            p2s!(source)
+        )
+    })? {
+        let path = entry?.path();
+        let is_exe = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("exe"))
+            .unwrap_or(false);
+        if path.is_file() && is_exe {
+            res.push(p2s!(path.file_name().unwrap()));
+        }
+    }
+    res.sort();
+    Ok(res)
+}
+
+fn split_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+// 使用命令行提供的字段，缺失的字段询问用户，确认模式下使用缺省值
+fn complete_draft(draft: ScaffoldDraft) -> Result<ScaffoldOptions> {
+    let source = if let Some(source) = draft.source {
+        source
+    } else {
+        ask_input("Directory of the program files", None)?
+    };
+    let source_path = Path::new(&source);
+    if !source_path.is_dir() {
+        return Err(anyhow!(
+            "Error:Source '{source}' is not an existing directory"
+        ));
+    }
+    let default_name = source_path
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|name| p2s!(name)));
+
+    // 主程序优先选择与目录同名的可执行文件
+    let main_program = if let Some(main_program) = draft.main_program {
+        Some(main_program)
+    } else {
+        let programs = find_programs(source_path)?;
+        if programs.is_empty() {
+            log!("Warning:No executable file found in '{source}', skip creating shortcut and PATH entrance");
+            None
+        } else {
+            let default_index = programs
+                .iter()
+                .position(|p| {
+                    default_name
+                        .as_ref()
+                        .map(|name| p.to_lowercase().starts_with(&name.to_lowercase()))
+                        .unwrap_or(false)
+                })
+                .unwrap_or(0);
+            let index = ask_select("Main program", &programs, default_index)?;
+            Some(programs[index].clone())
+        }
+    };
+
+    let name = if let Some(name) = draft.name {
+        name
+    } else {
+        ask_input("Package name", default_name)?
+    };
+    let description = if let Some(description) = draft.description {
+        description
+    } else {
+        ask_input("Description", Some(name.clone()))?
+    };
+    // 能读取主程序版本号时将其作为缺省版本号
+    let version = if let Some(version) = draft.version {
+        version
+    } else {
+        let exe_version = main_program
+            .as_ref()
+            .and_then(|mp| get_exe_version(source_path.join(mp)).ok());
+        ask_input(
+            "Version",
+            Some(exe_version.unwrap_or("1.0.0.0".to_string())),
+        )?
+    };
+    let authors = if let Some(authors) = draft.authors {
+        authors
+    } else {
+        split_list(&ask_input("Authors (separated by comma)", None)?)
+    };
+    if authors.is_empty() {
+        return Err(anyhow!("Error:At least one author should be provided"));
+    }
+    let license = if let Some(license) = draft.license {
+        Some(license)
+    } else {
+        Some(ask_input(
+            "License (leave blank to skip)",
+            Some(String::new()),
+        )?)
+        .filter(|license| !license.is_empty())
+    };
+    let scope = if let Some(scope) = draft.scope {
+        scope
+    } else {
+        ask_input("Scope", None)?
+    };
+    let upstream = if let Some(upstream) = draft.upstream {
+        upstream
+    } else {
+        ask_input("Upstream url", None)?
+    };
+    let category = if let Some(category) = draft.category {
+        category
+    } else {
+        ask_input("Category", Some("实用工具".to_string()))?
+    };
+    let language = if let Some(language) = draft.language {
+        language
+    } else {
+        let items: Vec<String> = LANGUAGES.iter().map(|l| l.to_string()).collect();
+        items[ask_select("Language", &items, 0)?].clone()
+    };
+    let workflows = if let Some(workflows) = draft.workflows {
+        workflows
+    } else {
+        let all = ScaffoldWorkflow::all();
+        let items: Vec<String> = all.iter().map(|w| w.file_name()).collect();
+        ask_multi_select("Optional workflows", &items, &[false, false, false])?
+            .into_iter()
+            .map(|i| all[i].clone())
+            .collect()
+    };
+
+    Ok(ScaffoldOptions {
+        source,
+        name,
+        description,
+        version,
+        authors,
+        license,
+        scope,
+        upstream,
+        category,
+        language,
+        main_program,
+        workflows,
+    })
+}
+
+fn step_node(name: &str, step: &str, fields: Vec<(&str, &str)>) -> Value {
+    let mut node = Table::new();
+    node.insert("name".to_string(), Value::String(name.to_string()));
+    node.insert("step".to_string(), Value::String(step.to_string()));
+    for (key, value) in fields {
+        node.insert(key.to_string(), Value::String(value.to_string()));
+    }
+    Value::Table(node)
+}
+
+// 为主程序创建快捷方式和 PATH 入口
+fn gen_entrance_workflow(options: &ScaffoldOptions) -> Result<String> {
+    let mut flow = Table::new();
+    if let Some(main_program) = &options.main_program {
+        flow.insert(
+            "create_shortcut".to_string(),
+            step_node(
+                "Create shortcut",
+                "Link",
+                vec![
+                    ("source_file", main_program),
+                    ("target_name", &options.name),
+                ],
+            ),
+        );
+        flow.insert(
+            "add_path".to_string(),
+            step_node("Add PATH", "Path", vec![("record", main_program)]),
+        );
+    } else {
+        flow.insert(
+            "log".to_string(),
+            step_node(
+                "Log",
+                "Log",
+                vec![("msg", &format!("{} installed", options.name))],
+            ),
+        );
+    }
+    Ok(toml::to_string(&flow)?)
+}
+
+fn gen_skeleton_workflow(workflow: &ScaffoldWorkflow, options: &ScaffoldOptions) -> Result<String> {
+    // 更新工作流存在时不会执行安装工作流，因此需要重新创建入口
+    if workflow == &ScaffoldWorkflow::Update {
+        return gen_entrance_workflow(options);
+    }
+    let msg = match workflow {
+        ScaffoldWorkflow::Remove => "TODO: clean up files created outside the app directory",
+        _ => "TODO: download or generate the files required by the package",
+    };
+    let mut flow = Table::new();
+    flow.insert(
+        "todo".to_string(),
+        step_node("Todo", "Log", vec![("msg", msg)]),
+    );
+    Ok(toml::to_string(&flow)?)
+}
+
+// 生成符合规范的包源目录并进行校验
+pub fn new(dir: &String, draft: ScaffoldDraft) -> Result<GlobalPackage> {
+    let dir_path = Path::new(dir);
+    if dir_path.exists() && read_dir(dir_path)?.next().is_some() {
+        return Err(anyhow!("Error:Target directory '{dir}' is not empty"));
+    }
+    let options = complete_draft(draft)?;
+
+    // nep 字段仅使用 ept 的主版本号
+    let nep = env!("CARGO_PKG_VERSION")
+        .split('.')
+        .next()
+        .unwrap()
+        .to_string();
+    // 无法读取版本号的主程序会导致校验失败，因此不写入 main_program 字段
+    let readable_main_program = options
+        .main_program
+        .clone()
+        .filter(|mp| get_exe_version(Path::new(&options.source).join(mp)).is_ok());
+    let global = GlobalPackage {
+        nep,
+        package: Package {
+            name: options.name.clone(),
+            description: options.description.clone(),
+            template: "Software".to_string(),
+            version: options.version.clone(),
+            authors: options.authors.clone(),
+            license: options.license.clone(),
+            icon: None,
+            strict: None,
+        },
+        software: Some(Software {
+            scope: options.scope.clone(),
+            upstream: options.upstream.clone(),
+            category: options.category.clone(),
+            arch: None,
+            language: options.language.clone(),
+            main_program: readable_main_program,
+            tags: None,
+            alias: None,
+            registry_entry: None,
+        }),
+    };
+
+    // 写入文件
+    log!("Info:Generating package source...");
+    let workflows_path = dir_path.join("workflows");
+    create_dir_all(&workflows_path)
+        .map_err(|e| anyhow!("Error:Failed to create directory '{dir}' : {e}"))?;
+    copy_dir(&options.source, dir_path.join(&options.name))?;
+    write(
+        dir_path.join("package.toml"),
+        toml::to_string_pretty(&global)?,
+    )?;
+    write(
+        workflows_path.join("setup.toml"),
+        gen_entrance_workflow(&options)?,
+    )?;
+    for workflow in &options.workflows {
+        write(
+            workflows_path.join(workflow.file_name()),
+            gen_skeleton_workflow(workflow, &options)?,
+        )?;
+    }
+    log_ok_last!("Info:Generating package source...");
+
+    // 确保生成的目录可以直接打包
+    verify(dir)
+}
+
+#[test]
+fn test_new() {
+    use crate::utils::flags::{set_flag, Flag};
+    use std::fs::remove_dir_all;
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_clear_test_dir();
+
+    let draft = ScaffoldDraft {
+        source: Some("examples/Dism++/Dism++".to_string()),
+        main_program: Some("Dism++x64.exe".to_string()),
+        authors: Some(vec![
+            "Cno <dsyourshy@qq.com>".to_string(),
+            "Chuyu".to_string(),
+        ]),
+        scope: Some("Chuyu".to_string()),
+        upstream: Some("https://github.com/Chuyu-Team/Dism-Multi-language".to_string()),
+        workflows: Some(ScaffoldWorkflow::all()),
+        ..Default::default()
+    };
+    let global = new(&"test/Dism++".to_string(), draft.clone()).unwrap();
+    assert_eq!(global.package.name, "Dism++");
+    assert_eq!(global.package.version, "10.1.1002.1");
+    for file in ["setup.toml", "update.toml", "remove.toml", "expand.toml"] {
+        assert!(Path::new("test/Dism++/workflows").join(file).exists());
+    }
+
+    // 目标目录非空
+    assert!(new(&"test/Dism++".to_string(), draft.clone()).is_err());
+    remove_dir_all("test/Dism++").unwrap();
+
+    // 确认模式下缺失必填字段
+    let draft = ScaffoldDraft {
+        authors: None,
+        ..draft
+    };
+    assert!(new(&"test/Dism++".to_string(), draft).is_err());
+    set_flag(Flag::Confirm, false);
+}
@@ -1,15 +1,24 @@
+use crate::compression::pack_tar_reproducible;
 use crate::compression::{compress, pack_tar};
-use crate::entrances::verify::verify;
+use crate::entrances::{
+    utils::{delta::gen_delta_source, package::read_nep_hash},
+    verify::verify,
+};
 use crate::parsers::parse_author;
 use crate::signature::sign;
-use crate::types::{signature::Signature, signature::SignatureNode};
+use crate::types::{pack::PackOptions, signature::Signature, signature::SignatureNode};
 use crate::utils::{allocate_path_temp, is_debug_mode, term::ask_yn};
 use crate::{log, log_ok_last, p2s};
 use anyhow::{anyhow, Result};
 use std::fs::{remove_dir_all, write};
 use std::path::Path;
 
-pub fn pack(source_dir: &String, into_file: Option<String>, need_sign: bool) -> Result<String> {
+pub fn pack(
+    source_dir: &String,
+    into_file: Option<String>,
+    need_sign: bool,
+    options: &PackOptions,
+) -> Result<String> {
     log!("Info:Preparing to pack '{source_dir}'");
 
     // 通用校验
@@ -22,8 +31,49 @@ pub fn pack(source_dir: &String, into_file: Option<String>, need_sign: bool) ->
         fa = first_author.name
     );
 
+    // 增量包仅打包相对基础版本变化的文件
+    // 同时在输出目录生成完整包，以其内包摘要作为应用增量包后的安装记录摘要
+    let (pack_source, delta) = if let Some(base_file) = &options.delta_from {
+        let full_file = p2s!(Path::new(into_file.as_deref().unwrap_or("./"))
+            .with_file_name(file_stem.clone() + ".nep"));
+        if into_file.as_ref() == Some(&full_file) {
+            return Err(anyhow!(
+                "Error:Target '{full_file}' is reserved for the full package of delta package"
+            ));
+        }
+        let full_options = PackOptions {
+            delta_from: None,
+            ..options.clone()
+        };
+        pack(
+            source_dir,
+            Some(full_file.clone()),
+            need_sign,
+            &full_options,
+        )?;
+        log!("Info:Full package stored at '{full_file}'");
+        let full_hash = read_nep_hash(&full_file, need_sign)?;
+
+        let delta_source_path = allocate_path_temp(&(file_stem.clone() + "_Delta"), false)?;
+        let delta = gen_delta_source(
+            source_dir,
+            &global,
+            base_file,
+            full_hash,
+            &delta_source_path,
+        )?;
+        (p2s!(delta_source_path), Some(delta))
+    } else {
+        (source_dir.to_owned(), None)
+    };
+
     // 校验 into_file 是否存在
-    let into_file = into_file.unwrap_or(String::from("./") + &file_stem + ".nep");
+    let default_into_file = if let Some(delta) = &delta {
+        format!("./{file_stem}_from_{}.nep", delta.base_version)
+    } else {
+        String::from("./") + &file_stem + ".nep"
+    };
+    let into_file = into_file.unwrap_or(default_into_file);
     let into_file_path = Path::new(&into_file);
     if into_file_path.exists() {
         if into_file_path.is_dir() {
@@ -41,13 +91,14 @@ pub fn pack(source_dir: &String, into_file: Option<String>, need_sign: bool) ->
     // 生成内包
     log!("Info:Compressing inner package...");
     let inner_path_str = p2s!(temp_dir_path.join(file_stem.clone() + ".tar.zst"));
-    compress(source_dir, &inner_path_str)?;
+    compress(&pack_source, &inner_path_str, options)?;
     log_ok_last!("Info:Compressing inner package...");
 
     // 对内包进行签名
     let signature = if need_sign {
         log!("Info:Signing inner package...");
-        let signature = sign(&inner_path_str)?;
+        // 可复现打包使用确定性签名，使外包同样字节一致
+        let signature = sign(&inner_path_str, options.is_reproducible())?;
         Some(signature)
     } else {
         None
@@ -70,13 +121,20 @@ pub fn pack(source_dir: &String, into_file: Option<String>, need_sign: bool) ->
 
     // 生成外包
     log!("Info:Packing outer package...");
-    pack_tar(&p2s!(temp_dir_path), &into_file)?;
+    if let Some(mtime) = options.reproducible_mtime {
+        pack_tar_reproducible(&p2s!(temp_dir_path), &into_file, mtime)?;
+    } else {
+        pack_tar(&p2s!(temp_dir_path), &into_file)?;
+    }
     log_ok_last!("Info:Packing outer package...");
 
     // 清理临时文件夹
     if !is_debug_mode() {
         log!("Info:Cleaning...");
-        let clean_res = remove_dir_all(&temp_dir_path);
+        let mut clean_res = remove_dir_all(&temp_dir_path);
+        if pack_source != *source_dir {
+            clean_res = clean_res.and(remove_dir_all(&pack_source));
+        }
         if clean_res.is_ok() {
             log_ok_last!("Info:Cleaning...");
         } else {
@@ -104,6 +162,7 @@ fn test_pack() {
         &"./examples/ComplexFS".to_string(),
         Some("./test/ComplexFS_1.75.0.0_Cno.nep".to_string()),
         true,
+        &PackOptions::default(),
     )
     .unwrap();
     set_flag(Flag::Debug, true);
@@ -111,6 +170,30 @@ fn test_pack() {
         &"./examples/ComplexFS".to_string(),
         Some("./test/ComplexFS_1.75.0.0_Cno.nep".to_string()),
         false,
+        &PackOptions::default(),
     )
     .unwrap();
+
+    // 可复现打包
+    set_flag(Flag::Debug, false);
+    let options = PackOptions::new(true, None, None).unwrap();
+    crate::utils::fs::copy_dir("examples/ComplexFS", "test/ComplexFS_Copy").unwrap();
+    pack(
+        &"./examples/ComplexFS".to_string(),
+        Some("./test/ComplexFS_a.nep".to_string()),
+        true,
+        &options,
+    )
+    .unwrap();
+    pack(
+        &"./test/ComplexFS_Copy".to_string(),
+        Some("./test/ComplexFS_b.nep".to_string()),
+        true,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read("test/ComplexFS_a.nep").unwrap(),
+        std::fs::read("test/ComplexFS_b.nep").unwrap()
+    );
 }
@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+
+use crate::{
+    log,
+    types::{extended_semver::ExSemVer, matcher::PackageMatcher, pin::PinNode},
+    utils::{
+        path::find_scope_with_name,
+        pin::{remove_pin, set_pin},
+    },
+};
+
+use super::info_local;
+
+// 返回 (scope, name, 锁定描述)
+pub fn pin(package_matcher: &String) -> Result<(String, String, String)> {
+    let matcher = PackageMatcher::parse(package_matcher, true, false)?;
+
+    // 查找 scope 并使用 scope 更新纠正大小写
+    let (scope, name) = find_scope_with_name(&matcher.name, matcher.scope)?;
+
+    // 仅允许锁定已安装的包
+    let (_, diff) = info_local(&scope, &name)
+        .map_err(|_| anyhow!("Error:Package '{scope}/{name}' hasn't been installed"))?;
+    let node = PinNode {
+        version_req: matcher.version_req.map(|req| req.to_string()),
+    };
+    if let Some(req) = node.get_version_req()? {
+        if !req.matches(&ExSemVer::parse(&diff.version)?.semver_instance) {
+            log!(
+                "Warning:Installed version '{ver}' of '{scope}/{name}' doesn't match the pinned req '{req}'",
+                ver = diff.version
+            );
+        }
+    }
+    let desc = node.describe();
+    set_pin(&scope, &name, node)?;
+
+    Ok((scope, name, desc))
+}
+
+pub fn unpin(package_matcher: &String) -> Result<(String, String)> {
+    let matcher = PackageMatcher::parse(package_matcher, true, true)?;
+
+    // 查找 scope 并使用 scope 更新纠正大小写
+    let (scope, name) = find_scope_with_name(&matcher.name, matcher.scope)?;
+    if !remove_pin(&scope, &name)? {
+        return Err(anyhow!("Error:Package '{scope}/{name}' hasn't been pinned"));
+    }
+
+    Ok((scope, name))
+}
+
+#[test]
+fn test_pin() {
+    use crate::utils::flags::{set_flag, Flag};
+    use crate::utils::pin::get_pin;
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_testing_vscode();
+
+    // 锁定版本范围
+    let (scope, name, _) = pin(&"vscode@~1.75".to_string()).unwrap();
+    assert_eq!(
+        get_pin(&scope, &name).unwrap().unwrap().version_req,
+        Some("~1.75".to_string())
+    );
+
+    // 完全冻结
+    pin(&"Microsoft/VSCode".to_string()).unwrap();
+    assert!(get_pin(&scope, &name)
+        .unwrap()
+        .unwrap()
+        .version_req
+        .is_none());
+
+    // 冻结后拒绝更新
+    let err = crate::utils::parse_inputs::parse_update_inputs(vec!["vscode".to_string()])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("held"));
+
+    // 解除锁定
+    unpin(&"vscode".to_string()).unwrap();
+    assert!(get_pin(&scope, &name).unwrap().is_none());
+    assert!(unpin(&"vscode".to_string()).is_err());
+
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+}
@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use toml::Value;
+
+use crate::{
+    types::{
+        matcher::PackageMatcher,
+        policy::{PolicyNode, UpdatePolicy},
+    },
+    utils::{
+        cfg::get_config,
+        path::find_scope_with_name,
+        policy::{read_policies, remove_package_policy, set_package_policy},
+    },
+};
+
+use super::info_local;
+
+// 返回 (scope, name, 策略描述)
+pub fn policy_set(
+    package_matcher: &String,
+    policy: &String,
+    allow_prerelease: bool,
+) -> Result<(String, String, String)> {
+    let matcher = PackageMatcher::parse(package_matcher, true, true)?;
+    let policy: UpdatePolicy = Value::String(policy.to_owned()).try_into().map_err(|_| {
+        anyhow!("Error:Invalid policy '{policy}', expect 'major', 'minor', 'patch' or 'reserved'")
+    })?;
+
+    // 查找 scope 并使用 scope 更新纠正大小写
+    let (scope, name) = find_scope_with_name(&matcher.name, matcher.scope)?;
+    info_local(&scope, &name)
+        .map_err(|_| anyhow!("Error:Package '{scope}/{name}' hasn't been installed"))?;
+
+    let node = PolicyNode {
+        policy,
+        allow_prerelease,
+    };
+    let desc = node.describe();
+    set_package_policy(&scope, &name, node)?;
+
+    Ok((scope, name, desc))
+}
+
+pub fn policy_unset(package_matcher: &String) -> Result<(String, String)> {
+    let matcher = PackageMatcher::parse(package_matcher, true, true)?;
+
+    // 查找 scope 并使用 scope 更新纠正大小写
+    let (scope, name) = find_scope_with_name(&matcher.name, matcher.scope)?;
+    if !remove_package_policy(&scope, &name)? {
+        return Err(anyhow!(
+            "Error:Package '{scope}/{name}' doesn't have its own update policy"
+        ));
+    }
+
+    Ok((scope, name))
+}
+
+// 返回全局策略描述和各个包的策略描述
+pub fn policy_list() -> Result<(String, Vec<(String, String)>)> {
+    let cfg = get_config();
+    let global = PolicyNode {
+        policy: cfg.update.policy,
+        allow_prerelease: cfg.update.allow_prerelease,
+    };
+    let list = read_policies()?
+        .policies
+        .into_iter()
+        .map(|(key, node)| (key, node.describe()))
+        .collect();
+
+    Ok((global.describe(), list))
+}
+
+#[test]
+fn test_policy() {
+    crate::utils::test::_ensure_testing_vscode();
+
+    assert!(policy_set(&"vscode".to_string(), &"unknown".to_string(), false).is_err());
+    let (scope, name, desc) =
+        policy_set(&"vscode".to_string(), &"patch".to_string(), true).unwrap();
+    assert_eq!(desc, "policy 'patch' with prerelease".to_string());
+    let (_, list) = policy_list().unwrap();
+    assert!(list.contains(&(format!("{scope}/{name}"), desc)));
+
+    policy_unset(&"vscode".to_string()).unwrap();
+    assert!(policy_unset(&"vscode".to_string()).is_err());
+
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+}
@@ -0,0 +1,221 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{create_dir_all, remove_dir_all},
+    path::Path,
+};
+
+use super::{
+    expand::{expand_workshop, is_workshop_expandable},
+    meta::generalize_workflows_permissions,
+    utils::delta::collect_relative_paths,
+    verify::{get_manifest, verify},
+};
+use crate::{
+    executor::{workflow_executor, workflow_reverse_executor},
+    log, log_ok_last, p2s,
+    parsers::parse_workflow,
+    types::{mixed_fs::MixedFS, package::GlobalPackage, steps::Step, workflow::WorkflowNode},
+    utils::{
+        allocate_path_temp,
+        env::enter_sandbox,
+        fs::{copy_dir, move_or_copy},
+        is_debug_mode,
+    },
+};
+
+fn read_workflow(workflows: &Path, file_name: &str) -> Result<Option<Vec<WorkflowNode>>> {
+    let p = workflows.join(file_name);
+    if p.exists() {
+        Ok(Some(parse_workflow(&p2s!(p))?))
+    } else {
+        Ok(None)
+    }
+}
+
+// 检查工作流的装箱单在程序目录中是否存在
+fn check_manifest(flow: Vec<WorkflowNode>, located: &Path, file_name: &str) -> Vec<String> {
+    let mut fs = MixedFS::new(&p2s!(located));
+    get_manifest(flow, &mut fs)
+        .into_iter()
+        .filter(|item| !located.join(item).exists())
+        .map(|item| format!("  Manifest item '{item}' not found after running '{file_name}'"))
+        .collect()
+}
+
+// 执行步骤直接在宿主系统中运行命令，其读写不会被重定向到沙箱
+fn check_execute_steps(workflows: &Path) -> Result<()> {
+    let mut found = Vec::new();
+    for file_name in ["setup.toml", "update.toml", "remove.toml"] {
+        for node in read_workflow(workflows, file_name)?.unwrap_or_default() {
+            if let Step::StepExecute(_) = node.body {
+                found.push(format!(
+                    "  Step '{name}' in '{file_name}'",
+                    name = node.header.name.unwrap_or(node.header.step)
+                ));
+            }
+        }
+    }
+    if found.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Error:Execute steps run commands on the host outside the sandbox, add '--allow-execute' to run them anyway :\n{}",
+            found.join("\n")
+        ))
+    }
+}
+
+fn run_in_sandbox(
+    source_dir: &String,
+    root: &Path,
+    global: &GlobalPackage,
+    allow_execute: bool,
+) -> Result<()> {
+    let name = &global.package.name;
+    let scope = &global.software.as_ref().unwrap().scope;
+
+    // 准备工作目录，可展开的包需要先执行展开工作流
+    let workshop = root.join("Workshop");
+    copy_dir(source_dir, &workshop)?;
+    let workshop_str = p2s!(workshop);
+    if is_workshop_expandable(&workshop_str) {
+        expand_workshop(&workshop_str)?;
+    }
+
+    // 部署程序目录
+    let apps = root.join("apps");
+    let located = apps.join(scope).join(name);
+    create_dir_all(apps.join(scope))?;
+    move_or_copy(workshop.join(name), located.clone())?;
+    let located_str = p2s!(located);
+    let workflows = workshop.join("workflows");
+    if allow_execute {
+        log!("Warning:Execute steps will run on the host outside the sandbox");
+    } else {
+        check_execute_steps(&workflows)?;
+    }
+    let permissions = generalize_workflows_permissions(&workflows)?;
+    let mut problems = Vec::new();
+
+    // 执行安装工作流
+    let setup_flow = read_workflow(&workflows, "setup.toml")?
+        .ok_or(anyhow!("Error:Can't find workflow 'setup.toml'"))?;
+    log!("Info:Running setup workflow...");
+    workflow_executor(
+        setup_flow.clone(),
+        located_str.clone(),
+        global.clone(),
+        permissions.clone(),
+    )?;
+    log_ok_last!("Info:Running setup workflow...");
+    problems.append(&mut check_manifest(
+        setup_flow.clone(),
+        &located,
+        "setup.toml",
+    ));
+
+    // 在安装后的目录中执行更新工作流
+    if let Some(update_flow) = read_workflow(&workflows, "update.toml")? {
+        log!("Info:Running update workflow...");
+        workflow_executor(
+            update_flow.clone(),
+            located_str.clone(),
+            global.clone(),
+            permissions.clone(),
+        )?;
+        log_ok_last!("Info:Running update workflow...");
+        problems.append(&mut check_manifest(update_flow, &located, "update.toml"));
+    }
+
+    // 按照卸载的顺序执行卸载工作流与逆向安装工作流，然后删除程序目录
+    if let Some(remove_flow) = read_workflow(&workflows, "remove.toml")? {
+        log!("Info:Running remove workflow...");
+        workflow_executor(
+            remove_flow,
+            located_str.clone(),
+            global.clone(),
+            permissions,
+        )?;
+        log_ok_last!("Info:Running remove workflow...");
+    }
+    log!("Info:Running reverse setup workflow...");
+    workflow_reverse_executor(setup_flow, located_str, global.clone())?;
+    log_ok_last!("Info:Running reverse setup workflow...");
+    remove_dir_all(&apps)?;
+    remove_dir_all(&workshop)?;
+
+    // 沙箱中不应留下任何文件
+    let (mut files, mut dirs) = (Vec::new(), Vec::new());
+    collect_relative_paths(root, "", &mut files, &mut dirs)?;
+    files.sort();
+    for file in files {
+        problems.push(format!("  File '{file}' left behind after removal"));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Error:Sandbox test of '{source_dir}' failed :\n{}",
+            problems.join("\n")
+        ))
+    }
+}
+
+// 在沙箱中依次执行安装、更新与卸载工作流，内置变量指向的系统目录与 bin 目录会被重定向到沙箱根目录
+// 执行步骤无法被沙箱隔离，需要显式允许
+pub fn sandbox_test(source_dir: &String, allow_execute: bool) -> Result<()> {
+    let root = allocate_path_temp("Sandbox", false)?;
+    log!("Debug:Sandbox root : '{}'", p2s!(root));
+    // 预先创建真实系统中总是存在的目录
+    for dir in [
+        "Home/Desktop",
+        "Home/AppData",
+        "SystemDrive/Users/Public/Desktop",
+    ] {
+        create_dir_all(root.join(dir))?;
+    }
+
+    // 校验同样在沙箱中进行，使内置变量解析到沙箱内
+    let guard = enter_sandbox(p2s!(root));
+    let res = verify(source_dir)
+        .and_then(|global| run_in_sandbox(source_dir, &root, &global, allow_execute));
+    drop(guard);
+
+    if is_debug_mode() {
+        log!("Debug:Leaving sandbox directory '{}'", p2s!(root));
+    } else if remove_dir_all(&root).is_err() {
+        log!(
+            "Warning:Failed to remove sandbox directory '{}'",
+            p2s!(root)
+        );
+    }
+    res
+}
+
+#[test]
+fn test_sandbox_test() {
+    use std::fs::{read_to_string, write};
+    crate::utils::test::_ensure_clear_test_dir();
+
+    sandbox_test(&"./examples/VSCode".to_string(), false).unwrap();
+
+    // 新建在桌面上的文件不会被逆向安装工作流删除
+    copy_dir("examples/VSCode", "test/VSCode").unwrap();
+    let workflow_text = read_to_string("test/VSCode/workflows/setup.toml").unwrap()
+        + "\n\n[create_note]\nstep = \"New\"\nat = \"${Desktop}/note.txt\"\n";
+    write("test/VSCode/workflows/setup.toml", workflow_text).unwrap();
+    let err = sandbox_test(&"test/VSCode".to_string(), false).unwrap_err();
+    assert!(err.to_string().contains("Home/Desktop/note.txt"));
+
+    // 未显式允许时拒绝运行执行步骤
+    let workflow_text = read_to_string("examples/VSCode/workflows/setup.toml").unwrap()
+        + "\n\n[run_echo]\nstep = \"Execute\"\ncommand = \"echo hello\"\n";
+    write("test/VSCode/workflows/setup.toml", workflow_text).unwrap();
+    let err = sandbox_test(&"test/VSCode".to_string(), false).unwrap_err();
+    assert!(err.to_string().contains("--allow-execute"));
+    sandbox_test(&"test/VSCode".to_string(), true).unwrap();
+
+    // 沙箱结束后内置变量恢复正常
+    assert!(crate::utils::env::get_sandbox_root().is_none());
+}
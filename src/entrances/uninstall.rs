@@ -4,7 +4,7 @@ use std::{
     fs::{read_dir, remove_dir, remove_dir_all},
     path::Path,
     thread::sleep,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -12,17 +12,18 @@ use crate::{
     log, log_ok_last, p2s,
     parsers::{parse_package, parse_workflow},
     types::{
+        history::{HistoryOperation, HistoryPackage},
         mixed_fs::MixedFS,
         steps::{StepExecute, TStep},
         workflow::{WorkflowContext, WorkflowNode},
     },
     utils::{
-        get_bare_apps, get_path_apps, path::find_scope_with_name, process::kill_with_name,
-        reg_entry::get_reg_entry, term::ask_yn,
+        get_bare_apps, get_path_apps, history::record_history, path::find_scope_with_name,
+        process::kill_with_name, reg_entry::get_reg_entry, term::ask_yn,
     },
 };
 
-use super::utils::validator::installed_validator;
+use super::{meta::generalize_workflows_permissions, utils::validator::installed_validator};
 
 fn get_manifest(flow: Vec<WorkflowNode>) -> Vec<String> {
     let mut manifest = Vec::new();
@@ -92,7 +93,9 @@ pub fn uninstall(scope: Option<String>, package_name: &String) -> Result<(String
 
         // 执行卸载工作流
         log!("Info:Running remove workflow...");
-        workflow_executor(remove_flow, app_str.clone(), global.clone())?;
+        let permissions =
+            generalize_workflows_permissions(&app_path.join(".nep_context/workflows"))?;
+        workflow_executor(remove_flow, app_str.clone(), global.clone(), permissions)?;
         log_ok_last!("Info:Running remove workflow...");
     }
 
@@ -159,6 +162,32 @@ pub fn uninstall(scope: Option<String>, package_name: &String) -> Result<(String
     Ok((scope, package_name))
 }
 
+// 依次卸载，第一个失败的包会中止后续卸载；每个尝试过的包单独记录历史，以区分已卸载、失败与未尝试的包
+pub fn uninstall_using_parsed(parsed: Vec<(String, String, String)>) -> Result<usize> {
+    for (scope, name, version) in &parsed {
+        let started = Instant::now();
+        let res = uninstall(Some(scope.clone()), name).map_err(|e| {
+            // 卸载失败时提示用户如何手动解决坏包
+            let app_path = get_path_apps(scope, name, false).unwrap();
+            anyhow!("Error:Failed to uninstall package '{scope}/{name}' : '{e}', try to manually delete '{}' if this package is broken", p2s!(app_path))
+        });
+        record_history(
+            HistoryOperation::Uninstall,
+            vec![HistoryPackage::new(
+                scope,
+                name,
+                Some(version.clone()),
+                None,
+            )],
+            started,
+            &res,
+        );
+        let (scope, name) = res?;
+        log!("Success:Package '{scope}/{name}' uninstalled successfully");
+    }
+    Ok(parsed.len())
+}
+
 #[test]
 fn test_uninstall() {
     use crate::utils::flags::{set_flag, Flag};
@@ -188,3 +217,50 @@ fn test_uninstall() {
     uninstall(None, &"Notepad".to_string()).unwrap();
     assert!(!Path::new(&pwd).exists());
 }
+
+#[test]
+fn test_uninstall_using_parsed() {
+    use crate::utils::flags::{set_flag, Flag};
+    use crate::utils::history::read_history;
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_testing_vscode();
+
+    // 第二个包卸载失败时，第三个包不会被尝试
+    let parsed = vec![
+        (
+            "Microsoft".to_string(),
+            "VSCode".to_string(),
+            "1.75.4.0".to_string(),
+        ),
+        (
+            "FakeScope".to_string(),
+            "Missing".to_string(),
+            "1.0.0.0".to_string(),
+        ),
+        (
+            "FakeScope".to_string(),
+            "Untouched".to_string(),
+            "1.0.0.0".to_string(),
+        ),
+    ];
+    assert!(uninstall_using_parsed(parsed).is_err());
+
+    let entries = read_history().unwrap();
+    let vscode = entries
+        .iter()
+        .rev()
+        .find(|entry| entry.involves("Microsoft/VSCode"))
+        .unwrap();
+    assert!(vscode.success);
+    assert_eq!(vscode.packages.len(), 1);
+    let missing = entries
+        .iter()
+        .rev()
+        .find(|entry| entry.involves("FakeScope/Missing"))
+        .unwrap();
+    assert!(!missing.success);
+    assert_eq!(missing.packages.len(), 1);
+    assert!(!entries
+        .iter()
+        .any(|entry| entry.involves("FakeScope/Untouched")));
+}
@@ -1,31 +1,48 @@
 use super::{
-    info_local, install_using_package, list, uninstall,
+    info_local, info_online,
+    install::install_using_package,
+    list,
+    meta::generalize_workflows_permissions,
+    uninstall,
     utils::{
-        package::{clean_temp, unpack_nep},
+        delta::{apply_delta, is_delta_applicable, read_delta},
+        package::{clean_temp, parse_inner_package, unpack_nep, unpack_nep_inner},
         validator::installed_validator,
     },
 };
-use crate::utils::flags::{set_flag, Flag};
+use crate::utils::flags::{get_flag, set_flag, Flag};
 use crate::{
     entrances::{expand_workshop, is_workshop_expandable},
     executor::workflow_executor,
     p2s,
     parsers::{parse_author, parse_workflow},
     signature::blake3::compute_hash_blake3_from_string,
-    types::{author::Author, extended_semver::ExSemVer},
+    types::{
+        author::Author,
+        delta::DeltaNode,
+        extended_semver::ExSemVer,
+        history::{HistoryOperation, HistoryPackage},
+        install_record::{InstallReason, InstallRecord, InstallSource},
+        policy::UpdateDecision,
+    },
     utils::{
         cache::spawn_cache,
-        download::download_nep,
+        download::{download_nep, fill_url_template},
+        fmt_print::fmt_package_line,
         fs::move_or_copy,
         get_path_apps, get_path_cache,
+        history::record_history,
+        install_record::{read_install_record, resolve_url_source, write_install_record},
         parse_inputs::{parse_update_inputs, ParseInputResEnum},
-        term::ask_yn,
+        permissions::check_permissions_diff,
+        policy::decide_update,
+        term::{ask_yn, ask_yn_strict},
     },
 };
 use crate::{executor::workflow_reverse_executor, types::info::UpdateInfo};
 use crate::{log, log_ok_last};
 use anyhow::{anyhow, Result};
-use std::{fs::remove_dir_all, str::FromStr};
+use std::{fs::remove_dir_all, path::Path, str::FromStr, time::Instant};
 
 fn same_authors(a: &[String], b: &[String]) -> bool {
     let ai: Vec<Author> = a.iter().map(|raw| parse_author(raw).unwrap()).collect();
@@ -34,14 +51,58 @@ fn same_authors(a: &[String], b: &[String]) -> bool {
     ai.eq(&bi)
 }
 
-pub fn update_using_package(source_file: &String, verify_signature: bool) -> Result<UpdateInfo> {
+// 更新并刷新安装记录中的来源，未提供来源时视为本地文件
+pub fn update_using_package(
+    source_file: &String,
+    verify_signature: bool,
+    source: Option<InstallSource>,
+) -> Result<UpdateInfo> {
     log!("Info:Preparing to update with package '{source_file}'");
+    let source = source.unwrap_or_else(|| InstallSource::Local {
+        path: source_file.to_owned(),
+    });
 
-    // 解包
-    let (temp_dir_inner_path, fresh_package) = unpack_nep(source_file, verify_signature)?;
+    // 解包，增量包在签名校验通过后使用已安装的基础版本补全程序目录
+    let (temp_dir_inner_path, fresh_package, package_hash, signer, delta) = if Path::new(
+        source_file,
+    )
+    .is_file()
+    {
+        let (inner, signature_struct, digest) = unpack_nep_inner(source_file, verify_signature)?;
+        let delta = read_delta(&inner)?.map(|manifest| manifest.delta);
+        if let Some(delta) = &delta {
+            // 基础版本未安装时，改用镜像源中的完整包
+            if !is_delta_applicable(delta)? {
+                log!(
+                        "Warning:Installed '{scope}/{name}' is not the base version ({base_version}) of the delta package, falling back to the full package",
+                        scope = delta.scope,
+                        name = delta.name,
+                        base_version = delta.base_version
+                    );
+                clean_temp(source_file)?;
+                return update_using_full_release(delta, verify_signature);
+            }
+            apply_delta(&inner, delta)?;
+        }
+        let (package, signer) = parse_inner_package(&inner, signature_struct, verify_signature)?;
+        (inner, package, Some(digest), signer, delta)
+    } else {
+        let (inner, package, hash, signer) = unpack_nep(source_file, verify_signature)?;
+        (inner, package, hash, signer, None)
+    };
     let fresh_software = fresh_package.software.clone().unwrap();
     let name = fresh_package.package.name.clone();
     let fresh_scope = fresh_software.scope;
+    if let Some(delta) = &delta {
+        if delta.scope != fresh_scope
+            || delta.name != name
+            || delta.version != fresh_package.package.version
+        {
+            return Err(anyhow!(
+                "Error:Invalid delta package : delta.toml doesn't match package.toml"
+            ));
+        }
+    }
 
     // 确认包是否已安装
     log!("Info:Resolving package...");
@@ -54,23 +115,44 @@ pub fn update_using_package(source_file: &String, verify_signature: bool) -> Res
     let local_version = ExSemVer::from_str(&local_diff.version)?;
     let fresh_version_str = fresh_package.package.version.clone();
     let fresh_version = ExSemVer::from_str(&fresh_version_str)?;
-    if local_version >= fresh_version {
+    if local_version == fresh_version {
         return Err(anyhow!("Error:Package '{name}' has been up to date ({local_version}), can't update to the version of given package ({fresh_version})"));
     }
+    if local_version > fresh_version {
+        if !get_flag(Flag::AllowDowngrade, false) {
+            return Err(anyhow!("Error:Package '{name}' has been up to date ({local_version}), can't update to the version of given package ({fresh_version}), add '--allow-downgrade' to downgrade it"));
+        }
+        if !ask_yn_strict(
+            format!("Downgrade package '{name}' from '{local_version}' to '{fresh_version}'?"),
+            false,
+        ) {
+            return Err(anyhow!("Error:Downgrade canceled by user"));
+        }
+    }
 
     // 确认作者是否一致
     if !same_authors(
         &local_package.package.authors,
         &fresh_package.package.authors,
     ) {
+        // 增量包依赖已安装的版本，改用完整包重新安装
+        if let Some(delta) = &delta {
+            clean_temp(source_file)?;
+            return update_using_full_release(delta, verify_signature);
+        }
         // 需要卸载然后重新安装
         if !ask_yn(format!("The given package is not the same as the author of the installed package (local:{:?}, given:{:?}), uninstall the installed package first?",local_package.package.authors,fresh_package.package.authors),true) {
             return Err(anyhow!("Error:Update canceled by user"));
         }
-        // 卸载
+        // 卸载，保留原有的安装原因
+        let reason = read_install_record(
+            &get_path_apps(&local_software.scope, &name, false)?.join(".nep_context"),
+        )?
+        .map(|record| record.reason)
+        .unwrap_or(InstallReason::Explicit);
         uninstall(Some(local_software.scope), &local_package.package.name)?;
         // 安装
-        install_using_package(source_file, verify_signature)?;
+        install_using_package(source_file, verify_signature, reason, Some(source))?;
         return Ok(UpdateInfo {
             name,
             scope: fresh_scope,
@@ -81,8 +163,22 @@ pub fn update_using_package(source_file: &String, verify_signature: bool) -> Res
 
     let located = get_path_apps(&local_software.scope, &name, true)?;
     let located_str = p2s!(located);
+    let old_record = read_install_record(&located.join(".nep_context"))?;
     log_ok_last!("Info:Resolving package...");
 
+    // 展示新旧版本的权限变化
+    let old_permissions =
+        generalize_workflows_permissions(&located.join(".nep_context").join("workflows"))?;
+    let fresh_permissions =
+        generalize_workflows_permissions(&temp_dir_inner_path.join("workflows"))?;
+    check_permissions_diff(
+        &fresh_scope,
+        &name,
+        signer,
+        &old_permissions,
+        &fresh_permissions,
+    )?;
+
     // 如果旧包有 remove 且新包没有 update 则执行旧包的 remove
     let remove_path = located
         .join(".nep_context")
@@ -92,7 +188,12 @@ pub fn update_using_package(source_file: &String, verify_signature: bool) -> Res
     if remove_path.exists() && !update_path.exists() {
         log!("Info:Running remove workflow...");
         let remove_workflow = parse_workflow(&p2s!(remove_path))?;
-        workflow_executor(remove_workflow, located_str.clone(), local_package.clone())?;
+        workflow_executor(
+            remove_workflow,
+            located_str.clone(),
+            local_package.clone(),
+            old_permissions,
+        )?;
         log_ok_last!("Info:Running remove workflow...");
     };
 
@@ -128,19 +229,34 @@ pub fn update_using_package(source_file: &String, verify_signature: bool) -> Res
         // 执行 update 工作流
         log!("Info:Running update workflow...");
         let update_workflow = parse_workflow(&p2s!(update_path))?;
-        workflow_executor(update_workflow, located_str.clone(), fresh_package)?;
+        workflow_executor(
+            update_workflow,
+            located_str.clone(),
+            fresh_package,
+            fresh_permissions,
+        )?;
         log_ok_last!("Info:Running update workflow...");
     } else {
         // 执行 setup 工作流
         log!("Info:Running setup workflow...");
         let setup_workflow = parse_workflow(&p2s!(update_path.with_file_name("setup.toml")))?;
-        workflow_executor(setup_workflow, located_str.clone(), fresh_package)?;
+        workflow_executor(
+            setup_workflow,
+            located_str.clone(),
+            fresh_package,
+            fresh_permissions,
+        )?;
         log_ok_last!("Info:Running setup workflow...");
     }
 
-    // 保存上下文
+    // 保存上下文，没有安装记录的旧包视为用户显式安装
     let ctx_path = located.join(".nep_context");
-    move_or_copy(temp_dir_inner_path, ctx_path)?;
+    move_or_copy(temp_dir_inner_path, ctx_path.clone())?;
+    let record = old_record
+        .map(|record| record.refresh(source.clone()))
+        .unwrap_or_else(|| InstallRecord::new(InstallReason::Explicit, source))
+        .with_package_hash(delta.map(|delta| delta.full_hash).or(package_hash));
+    write_install_record(&ctx_path, &record)?;
 
     // 检查更新是否完整
     log!("Info:Validating update...");
@@ -158,14 +274,35 @@ pub fn update_using_package(source_file: &String, verify_signature: bool) -> Res
     })
 }
 
-pub fn update_using_url(url: &str, verify_signature: bool) -> Result<UpdateInfo> {
+// 在镜像源中查找增量包目标版本对应的完整包并使用其更新
+fn update_using_full_release(delta: &DeltaNode, verify_signature: bool) -> Result<UpdateInfo> {
+    let (scope, name) = (&delta.scope, &delta.name);
+    let (item, url_template) = info_online(scope, name, None)?;
+    let version = ExSemVer::from_str(&delta.version)?;
+    let release = item
+        .releases
+        .into_iter()
+        .find(|release| release.version == version)
+        .ok_or(anyhow!(
+            "Error:Can't find full package of '{scope}/{name}' ({version}) in mirrors"
+        ))?;
+    let url = fill_url_template(&url_template, scope, &item.name, &release.file_name)?;
+    let source = resolve_url_source(scope, name, &url);
+    update_using_url(&url, verify_signature, source)
+}
+
+pub fn update_using_url(
+    url: &str,
+    verify_signature: bool,
+    source: InstallSource,
+) -> Result<UpdateInfo> {
     // 下载文件到临时目录
     let cache_path = get_path_cache()?;
     let url_hash = compute_hash_blake3_from_string(url)?;
     let (p, cache_ctx) = download_nep(url, Some((cache_path, url_hash)))?;
 
     // 更新
-    let info = update_using_package(&p2s!(p), verify_signature)?;
+    let info = update_using_package(&p2s!(p), verify_signature, Some(source))?;
 
     // 缓存下载的包
     spawn_cache(cache_ctx)?;
@@ -178,7 +315,8 @@ pub fn update_using_package_matcher(matcher: String, verify_signature: bool) ->
     let parsed = parse_update_inputs(vec![matcher])?;
     // 执行更新
     if let ParseInputResEnum::PackageMatcher(p) = parsed.first().unwrap() {
-        update_using_url(&p.download_url, verify_signature)
+        let source = resolve_url_source(&p.scope, &p.name, &p.download_url);
+        update_using_url(&p.download_url, verify_signature, source)
     } else {
         Err(anyhow!(
             "Error:Fatal:Input matcher can't be parsed as package matcher"
@@ -186,29 +324,62 @@ pub fn update_using_package_matcher(matcher: String, verify_signature: bool) ->
     }
 }
 
-pub fn update_using_parsed(
+fn update_each(
     parsed: Vec<ParseInputResEnum>,
     verify_signature: bool,
+    journal: &mut Vec<HistoryPackage>,
 ) -> Result<Vec<UpdateInfo>> {
     let mut arr = Vec::new();
     for parsed in parsed {
         log!("Info:Start updating with {}", parsed.preview());
+        // 先使用输入记录，更新成功后替换为实际的包名和版本
+        journal.push(match &parsed {
+            ParseInputResEnum::LocalPath(p) | ParseInputResEnum::Url(p) => HistoryPackage {
+                name: p.clone(),
+                from: None,
+                to: None,
+            },
+            ParseInputResEnum::PackageMatcher(p) => HistoryPackage::new(
+                &p.scope,
+                &p.name,
+                p.current_version.clone(),
+                Some(p.target_version.clone()),
+            ),
+        });
         let res = match parsed {
-            ParseInputResEnum::LocalPath(p) => update_using_package(&p, false)?,
-            ParseInputResEnum::Url(u) => update_using_url(&u, false)?,
+            ParseInputResEnum::LocalPath(p) => update_using_package(&p, false, None)?,
+            ParseInputResEnum::Url(u) => {
+                update_using_url(&u, false, InstallSource::Url { url: u.clone() })?
+            }
             ParseInputResEnum::PackageMatcher(p) => {
-                update_using_url(&p.download_url, verify_signature)?
+                let source = resolve_url_source(&p.scope, &p.name, &p.download_url);
+                update_using_url(&p.download_url, verify_signature, source)?
             }
         };
+        if let Some(node) = journal.last_mut() {
+            *node = res.to_history_package();
+        }
         log!("{}", res.format_success());
         arr.push(res);
     }
     Ok(arr)
 }
 
+pub fn update_using_parsed(
+    parsed: Vec<ParseInputResEnum>,
+    verify_signature: bool,
+) -> Result<Vec<UpdateInfo>> {
+    let started = Instant::now();
+    let mut journal = Vec::new();
+    let res = update_each(parsed, verify_signature, &mut journal);
+    record_history(HistoryOperation::Update, journal, started, &res);
+    res
+}
+
 pub fn update_all(verify_signature: bool) -> Result<(i32, i32)> {
     // 遍历 list 结果，生成更新列表
     let list_res = list()?;
+    let mut held_list: Vec<(UpdateInfo, String)> = Vec::new();
     let update_list: Vec<UpdateInfo> = list_res
         .iter()
         .filter_map(|node| {
@@ -221,17 +392,52 @@ pub fn update_all(verify_signature: bool) -> Result<(i32, i32)> {
             if local_instance >= online_instance {
                 return None;
             }
-
-            Some(UpdateInfo {
+            let mut info = UpdateInfo {
                 name: node.name.to_owned(),
                 scope: node.software?.scope,
                 from_version: local_version,
                 to_version: online_version,
-            })
+            };
+
+            // 检查锁定规则和更新策略
+            let decision = info_online(&info.scope, &info.name, None).and_then(|(item, _)| {
+                decide_update(&info.scope, &info.name, item.releases, &local_instance)
+            });
+            match decision {
+                Ok(UpdateDecision::Update(release)) => {
+                    info.to_version = release.version.to_string();
+                    Some(info)
+                }
+                Ok(UpdateDecision::Held(reason)) => {
+                    held_list.push((info, reason));
+                    None
+                }
+                Ok(UpdateDecision::UpToDate) => None,
+                Err(e) => {
+                    held_list.push((info, e.to_string()));
+                    None
+                }
+            }
         })
         .collect();
     let count = update_list.len();
 
+    // 打印被锁定或被更新策略拦截的包
+    if !held_list.is_empty() {
+        let tip = held_list.iter().fold(
+            "\nHeld back packages:\n".to_string(),
+            |acc, (node, reason)| {
+                acc + &fmt_package_line(
+                    &node.scope,
+                    &node.name,
+                    &format!("{} → {}", node.from_version, node.to_version),
+                    Some(reason.to_owned()),
+                )
+            },
+        );
+        println!("{tip}");
+    }
+
     // 打印并确认更新
     if update_list.is_empty() {
         return Ok((0, 0));
@@ -253,19 +459,36 @@ pub fn update_all(verify_signature: bool) -> Result<(i32, i32)> {
     // 依次更新
     let mut success_count = 0;
     let mut failure_count = 0;
+    // 隐式开启确认模式，但重要的问题仍需用户确认
+    let implicit = !get_flag(Flag::Confirm, false);
     set_flag(Flag::Confirm, true);
+    set_flag(Flag::ImplicitConfirm, implicit);
+    let started = Instant::now();
+    let mut journal = Vec::new();
+    let mut errors = Vec::new();
     for info in update_list {
         let res =
             update_using_package_matcher(format!("{}/{}", info.scope, info.name), verify_signature);
+        journal.push(info.to_history_package());
         if let Err(e) = res {
             failure_count += 1;
+            errors.push(format!("{}/{} : {e}", info.scope, info.name));
             log!("{}", info.format_failure(e));
         } else {
             success_count += 1;
             log!("{}", info.format_success());
         }
     }
-    set_flag(Flag::Confirm, false);
+    set_flag(Flag::Confirm, !implicit);
+    set_flag(Flag::ImplicitConfirm, false);
+
+    // 部分包更新失败时整体记录为失败
+    let res = if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(errors.join("; ")))
+    };
+    record_history(HistoryOperation::Update, journal, started, &res);
 
     Ok((success_count, failure_count))
 }
@@ -299,9 +522,16 @@ fn test_update_using_package() {
         &"./examples/VSCode".to_string(),
         Some("./test/VSCode_1.75.0.0_Cno.nep".to_string()),
         true,
+        &crate::types::pack::PackOptions::default(),
+    )
+    .unwrap();
+    install_using_package(
+        &"./test/VSCode_1.75.0.0_Cno.nep".to_string(),
+        true,
+        InstallReason::Explicit,
+        None,
     )
     .unwrap();
-    install_using_package(&"./test/VSCode_1.75.0.0_Cno.nep".to_string(), true).unwrap();
 
     // 手动更新版本号
     crate::utils::fs::copy_dir("examples/VSCode", "test/VSCode").unwrap();
@@ -322,12 +552,12 @@ fn test_update_using_package() {
     .unwrap();
 
     // 安装新版本
-    update_using_package(&"test/VSCode".to_string(), false).unwrap();
+    update_using_package(&"test/VSCode".to_string(), false, None).unwrap();
     assert!(!old_ico.exists());
     assert!(new_ico.exists());
 
     // 卸载
-    crate::uninstall(None, &"VSCode".to_string()).unwrap();
+    crate::entrances::uninstall(None, &"VSCode".to_string()).unwrap();
 }
 
 #[test]
@@ -347,8 +577,20 @@ fn test_update_all() {
     crate::utils::test::_modify_package_dir_version("test/Notepad", "22.0.0.0");
 
     // 安装旧版本
-    install_using_package(&"examples/VSCode".to_string(), false).unwrap();
-    install_using_package(&"test/Notepad".to_string(), false).unwrap();
+    install_using_package(
+        &"examples/VSCode".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
+    install_using_package(
+        &"test/Notepad".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
 
     // 生成新包
     let source_dir = crate::utils::test::_fork_example_with_version("examples/VSCode", "1.75.4.2");
@@ -357,12 +599,14 @@ fn test_update_all() {
         &source_dir,
         Some("./test/static/VSCode_1.75.4.2_Cno.nep".to_string()),
         false,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
     crate::pack(
         &"./examples/Notepad".to_string(),
         Some("./test/static/Notepad_22.1.0.0_Cno.nep".to_string()),
         false,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
 
@@ -429,6 +673,8 @@ fn test_update_workflow_executions() {
         crate::entrances::install_using_package(
             &format!("examples/UpdateSuit/VSCode{old_type}"),
             false,
+            InstallReason::Explicit,
+            None,
         )
         .unwrap();
         assert!(Path::new(&desktop)
@@ -440,7 +686,7 @@ fn test_update_workflow_executions() {
             &format!("examples/UpdateSuit/VSCode{new_type}"),
             "1.75.4.1",
         );
-        crate::entrances::update_using_package(&source_file, false).unwrap();
+        update_using_package(&source_file, false, None).unwrap();
 
         // 断言仅存在指定文件
         for file in assert_files {
@@ -472,8 +718,13 @@ fn test_update_with_different_author() {
     crate::utils::test::_ensure_testing_vscode_uninstalled();
 
     // 安装旧版本
-    crate::entrances::install_using_package(&"examples/UpdateSuit/VSCode3".to_string(), false)
-        .unwrap();
+    crate::entrances::install_using_package(
+        &"examples/UpdateSuit/VSCode3".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
     assert!(desktop_path.join("vsc3-setup-1.75.4.0.lnk").exists());
 
     // 安装新版本
@@ -483,7 +734,7 @@ fn test_update_with_different_author() {
         std::path::Path::new(&source_file).join("workflows/update.toml"),
     )
     .unwrap();
-    update_using_package(&source_file, false).unwrap();
+    update_using_package(&source_file, false, None).unwrap();
 
     // 断言是先卸载再安装的
     assert!(!desktop_path.join("vsc3-setup-1.75.4.0.lnk").exists());
@@ -503,6 +754,7 @@ fn test_update_expandable() {
     set_flag(Flag::Confirm, true);
     crate::utils::test::_ensure_clear_test_dir();
     crate::utils::test::_ensure_testing_uninstalled("Microsoft", "VSCodeE");
+    crate::utils::test::_allow_testing_permission("download_file");
 
     // 断言原来的包中不包含这个二进制文件
     assert!(!Path::new("examples/VSCodeE/VSCodeE/Code.exe").exists());
@@ -513,7 +765,13 @@ fn test_update_expandable() {
 
     // 安装
     crate::utils::fs::copy_dir("examples/VSCodeE", "test/VSCodeE").unwrap();
-    install_using_package(&"test/VSCodeE".to_string(), false).unwrap();
+    install_using_package(
+        &"test/VSCodeE".to_string(),
+        false,
+        InstallReason::Explicit,
+        None,
+    )
+    .unwrap();
 
     // 断言安装成功
     assert!(info_local(&"Microsoft".to_string(), &"VSCodeE".to_string()).is_ok());
@@ -533,7 +791,7 @@ fn test_update_expandable() {
     assert!(!Path::new(&pkg_path).join("VSCodeE/Code.exe").exists());
 
     // 安装更新包
-    update_using_package(&pkg_path, false).unwrap();
+    update_using_package(&pkg_path, false, None).unwrap();
 
     // 断言安装成功
     assert!(
@@ -548,3 +806,115 @@ fn test_update_expandable() {
     crate::utils::test::_ensure_testing_uninstalled("Microsoft", "VSCodeE");
     handler.kill().unwrap();
 }
+
+#[test]
+fn test_downgrade() {
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_clear_test_dir();
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+
+    // 安装新版本
+    let source_dir = crate::utils::test::_fork_example_with_version("examples/VSCode", "1.75.4.1");
+    install_using_package(&source_dir, false, InstallReason::Explicit, None).unwrap();
+
+    // 未显式允许时拒绝降级
+    assert!(update_using_package(&"examples/VSCode".to_string(), false, None).is_err());
+
+    // 允许降级
+    set_flag(Flag::AllowDowngrade, true);
+    let res = update_using_package(&"examples/VSCode".to_string(), false, None).unwrap();
+    assert_eq!(res.to_version, "1.75.4.0".to_string());
+    assert!(
+        info_local(&"Microsoft".to_string(), &"VSCode".to_string())
+            .unwrap()
+            .1
+            .version
+            == *"1.75.4.0"
+    );
+
+    // 相同版本仍然拒绝
+    assert!(update_using_package(&"examples/VSCode".to_string(), false, None).is_err());
+    set_flag(Flag::AllowDowngrade, false);
+
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+}
+
+#[test]
+fn test_update_with_delta_chain() {
+    use crate::entrances::utils::package::read_nep_hash;
+    use crate::types::pack::PackOptions;
+    set_flag(Flag::Confirm, true);
+    crate::utils::test::_ensure_clear_test_dir();
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+    let (scope, name) = ("Microsoft".to_string(), "VSCode".to_string());
+    let read_hash = || {
+        read_install_record(
+            &get_path_apps(&scope, &name, false)
+                .unwrap()
+                .join(".nep_context"),
+        )
+        .unwrap()
+        .unwrap()
+        .package_hash
+    };
+
+    // 基础版本与第一个增量包：新增文件
+    let base_file = "./test/VSCode_1.75.4.0_Cno.nep".to_string();
+    crate::pack(
+        &"./examples/VSCode".to_string(),
+        Some(base_file.clone()),
+        true,
+        &PackOptions::default(),
+    )
+    .unwrap();
+    let source_dir = crate::utils::test::_fork_example_with_version("examples/VSCode", "1.75.4.1");
+    std::fs::write(format!("{source_dir}/VSCode/new.txt"), "new").unwrap();
+    let first_delta = crate::pack(
+        &source_dir,
+        Some("./test/VSCode_delta_1.nep".to_string()),
+        true,
+        &PackOptions {
+            delta_from: Some(base_file.clone()),
+            ..PackOptions::default()
+        },
+    )
+    .unwrap();
+    let first_full = "./test/VSCode_1.75.4.1_Cno.nep".to_string();
+
+    // 增量包不能直接安装
+    assert!(install_using_package(&first_delta, true, InstallReason::Explicit, None).is_err());
+    install_using_package(&base_file, true, InstallReason::Explicit, None).unwrap();
+
+    // 应用后安装记录中为完整包的摘要
+    update_using_package(&first_delta, true, None).unwrap();
+    let located = get_path_apps(&scope, &name, false).unwrap();
+    assert!(located.join("new.txt").exists());
+    assert!(located.join("Code.exe").exists());
+    assert_eq!(read_hash(), Some(read_nep_hash(&first_full, true).unwrap()));
+
+    // 基于完整包生成的第二个增量包：删除文件
+    let source_dir = crate::utils::test::_fork_example_with_version("examples/VSCode", "1.75.4.2");
+    std::fs::write(format!("{source_dir}/VSCode/new.txt"), "new").unwrap();
+    std::fs::remove_file(format!("{source_dir}/VSCode/favicon.ico")).unwrap();
+    let second_delta = crate::pack(
+        &source_dir,
+        Some("./test/VSCode_delta_2.nep".to_string()),
+        true,
+        &PackOptions {
+            delta_from: Some(first_full),
+            ..PackOptions::default()
+        },
+    )
+    .unwrap();
+    let res = update_using_package(&second_delta, true, None).unwrap();
+    assert_eq!(res.to_version, "1.75.4.2".to_string());
+    assert!(located.join("new.txt").exists());
+    assert!(located.join("Code.exe").exists());
+    assert!(!located.join("favicon.ico").exists());
+    assert_eq!(
+        read_hash(),
+        Some(read_nep_hash(&"./test/VSCode_1.75.4.2_Cno.nep".to_string(), true).unwrap())
+    );
+
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+}
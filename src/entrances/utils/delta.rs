@@ -0,0 +1,291 @@
+use anyhow::{anyhow, Result};
+use std::{
+    collections::HashSet,
+    fs::{copy, create_dir_all, read_dir, read_to_string, remove_file, write},
+    path::Path,
+};
+
+use super::package::{clean_temp, unpack_nep};
+use crate::{
+    executor::values_validator_path,
+    log, log_ok_last, p2s,
+    signature::blake3::compute_hash_blake3,
+    types::{
+        delta::{Delta, DeltaNode},
+        extended_semver::ExSemVer,
+        package::GlobalPackage,
+    },
+    utils::{fs::copy_dir, get_path_apps, install_record::read_install_record},
+};
+
+// 递归列出目录中的文件与子目录，返回以 '/' 分隔的相对路径
+pub fn collect_relative_paths(
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<String>,
+    dirs: &mut Vec<String>,
+) -> Result<()> {
+    for entry in
+        read_dir(dir).map_err(|e| anyhow!("Error:Can't read '{}' as directory : {e}", p2s!(dir)))?
+    {
+        let path = entry?.path();
+        let relative = prefix.to_string() + &p2s!(path.file_name().unwrap());
+        if path.is_dir() {
+            // 已安装目录中的上下文不属于程序文件
+            if relative == ".nep_context" {
+                continue;
+            }
+            collect_relative_paths(&path, &(relative.clone() + "/"), files, dirs)?;
+            dirs.push(relative);
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+fn copy_file_with_parent(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        create_dir_all(parent)?;
+    }
+    copy(from, to).map_err(|e| {
+        anyhow!(
+            "Error:Failed to copy '{from}' to '{to}' : {e}",
+            from = p2s!(from),
+            to = p2s!(to)
+        )
+    })?;
+    Ok(())
+}
+
+// 在 into 目录中生成增量包的源目录，程序目录仅保留相对基础版本新增或修改的文件
+// full_hash 为同一源目录打包得到的完整包内包摘要
+pub fn gen_delta_source(
+    source_dir: &String,
+    global: &GlobalPackage,
+    base_file: &String,
+    full_hash: String,
+    into: &Path,
+) -> Result<DeltaNode> {
+    let name = &global.package.name;
+    let scope = &global.software.as_ref().unwrap().scope;
+
+    // 解包基础版本
+    let (base_inner, base_global, base_hash, _) = unpack_nep(base_file, false)?;
+    let base_hash = base_hash.ok_or(anyhow!(
+        "Error:Base of delta package should be a nep file, got directory '{base_file}'"
+    ))?;
+    let base_scope = &base_global.software.as_ref().unwrap().scope;
+    if &base_global.package.name != name || base_scope != scope {
+        return Err(anyhow!(
+            "Error:Base package '{base_scope}/{base_name}' is not the same package as '{scope}/{name}'",
+            base_name = base_global.package.name
+        ));
+    }
+    let base_version = base_global.package.version.clone();
+    if ExSemVer::parse(&base_version)? >= ExSemVer::parse(&global.package.version)? {
+        return Err(anyhow!(
+            "Error:Base version '{base_version}' should be lower than '{version}'",
+            version = global.package.version
+        ));
+    }
+
+    // 程序目录以外的文件全部保留
+    log!("Info:Generating delta from '{base_version}'...");
+    for entry in read_dir(source_dir)? {
+        let path = entry?.path();
+        let file_name = p2s!(path.file_name().unwrap());
+        if &file_name == name {
+            continue;
+        }
+        if path.is_dir() {
+            copy_dir(&path, into.join(&file_name))?;
+        } else {
+            copy(&path, into.join(&file_name))?;
+        }
+    }
+
+    // 对比程序目录
+    let fresh_app = Path::new(source_dir).join(name);
+    let base_app = base_inner.join(name);
+    let (mut fresh_files, mut fresh_dirs) = (Vec::new(), Vec::new());
+    collect_relative_paths(&fresh_app, "", &mut fresh_files, &mut fresh_dirs)?;
+    let (mut base_files, mut base_dirs) = (Vec::new(), Vec::new());
+    collect_relative_paths(&base_app, "", &mut base_files, &mut base_dirs)?;
+    let base_file_set: HashSet<&String> = base_files.iter().collect();
+    let base_dir_set: HashSet<&String> = base_dirs.iter().collect();
+
+    let into_app = into.join(name);
+    create_dir_all(&into_app)?;
+    let mut changed = 0;
+    for file in &fresh_files {
+        let fresh_path = fresh_app.join(file);
+        if base_file_set.contains(file)
+            && compute_hash_blake3(&p2s!(fresh_path))?
+                == compute_hash_blake3(&p2s!(base_app.join(file)))?
+        {
+            continue;
+        }
+        copy_file_with_parent(&fresh_path, &into_app.join(file))?;
+        changed += 1;
+    }
+    // 保留新增的空目录
+    for dir in &fresh_dirs {
+        if !base_dir_set.contains(dir) {
+            create_dir_all(into_app.join(dir))?;
+        }
+    }
+
+    // 基础版本中存在而新版本中不存在的文件与目录需要删除
+    let fresh_set: HashSet<&String> = fresh_files.iter().chain(fresh_dirs.iter()).collect();
+    let mut deleted: Vec<String> = base_files
+        .iter()
+        .chain(base_dirs.iter())
+        .filter(|p| !fresh_set.contains(p))
+        .cloned()
+        .collect();
+    deleted.sort();
+    let manifest = Delta {
+        delta: DeltaNode {
+            scope: scope.to_owned(),
+            name: name.to_owned(),
+            version: global.package.version.clone(),
+            base_version: base_version.clone(),
+            base_hash,
+            full_hash,
+            deleted,
+        },
+    };
+    write(into.join("delta.toml"), toml::to_string_pretty(&manifest)?)?;
+    log_ok_last!("Info:Generating delta from '{base_version}'...");
+    log!(
+        "Info:Delta contains {changed} added or modified files and {deleted} deletions",
+        deleted = manifest.delta.deleted.len()
+    );
+    clean_temp(base_file)?;
+
+    Ok(manifest.delta)
+}
+
+// 读取内包中的 delta.toml，完整包返回 None
+pub fn read_delta(inner: &Path) -> Result<Option<Delta>> {
+    let manifest_path = inner.join("delta.toml");
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+    let manifest = toml::from_str(&read_to_string(&manifest_path)?)
+        .map_err(|e| anyhow!("Error:Can't parse delta.toml : {e}"))?;
+    Ok(Some(manifest))
+}
+
+// 检查已安装的版本是否为增量包的基础版本
+pub fn is_delta_applicable(delta: &DeltaNode) -> Result<bool> {
+    let ctx_path = get_path_apps(&delta.scope, &delta.name, false)?.join(".nep_context");
+    let installed_hash = read_install_record(&ctx_path)?.and_then(|record| record.package_hash);
+    Ok(installed_hash.as_ref() == Some(&delta.base_hash))
+}
+
+// 使用已安装的基础版本补全增量包的程序目录，使 inner 成为完整的内包
+pub fn apply_delta(inner: &Path, delta: &DeltaNode) -> Result<()> {
+    if !is_delta_applicable(delta)? {
+        return Err(anyhow!(
+            "Error:Delta package requires '{scope}/{name}' ({base_version}) to be installed, use the full package instead",
+            scope = delta.scope,
+            name = delta.name,
+            base_version = delta.base_version
+        ));
+    }
+    for path in &delta.deleted {
+        values_validator_path(path)?;
+    }
+    let deleted: HashSet<&String> = delta.deleted.iter().collect();
+
+    log!("Info:Applying delta package...");
+    let installed = get_path_apps(&delta.scope, &delta.name, false)?;
+    let (mut files, mut dirs) = (Vec::new(), Vec::new());
+    collect_relative_paths(&installed, "", &mut files, &mut dirs)?;
+    let app = inner.join(&delta.name);
+    for dir in dirs {
+        if !deleted.contains(&dir) {
+            create_dir_all(app.join(dir))?;
+        }
+    }
+    // 增量包中的文件优先
+    for file in files {
+        let target = app.join(&file);
+        if deleted.contains(&file) || target.exists() {
+            continue;
+        }
+        copy_file_with_parent(&installed.join(&file), &target)?;
+    }
+    remove_file(inner.join("delta.toml"))?;
+    log_ok_last!("Info:Applying delta package...");
+
+    Ok(())
+}
+
+#[test]
+fn test_gen_delta_source() {
+    use crate::utils::{allocate_path_temp, fs::try_recycle};
+    crate::utils::test::_ensure_clear_test_dir();
+    crate::utils::test::_ensure_testing_vscode_uninstalled();
+
+    crate::pack(
+        &"./examples/VSCode".to_string(),
+        Some("./test/VSCode_1.75.0.0_Cno.nep".to_string()),
+        false,
+        &crate::types::pack::PackOptions::default(),
+    )
+    .unwrap();
+
+    // 修改一个文件、新增一个文件并删除一个文件
+    copy_dir("examples/VSCode", "test/VSCode").unwrap();
+    let package_text = read_to_string("test/VSCode/package.toml")
+        .unwrap()
+        .replace("1.75.4.0", "1.75.4.1");
+    write("test/VSCode/package.toml", package_text).unwrap();
+    write("test/VSCode/VSCode/new.txt", "new").unwrap();
+    try_recycle("test/VSCode/VSCode/favicon.ico").unwrap();
+    let global = crate::entrances::verify::verify(&"test/VSCode".to_string()).unwrap();
+
+    let into = allocate_path_temp("VSCode_Delta", false).unwrap();
+    let delta = gen_delta_source(
+        &"test/VSCode".to_string(),
+        &global,
+        &"./test/VSCode_1.75.0.0_Cno.nep".to_string(),
+        "full".to_string(),
+        &into,
+    )
+    .unwrap();
+    assert_eq!(delta.base_version, "1.75.4.0");
+    assert_eq!(delta.version, "1.75.4.1");
+    assert_eq!(delta.full_hash, "full");
+    assert!(into.join("package.toml").exists());
+    assert!(into.join("workflows/setup.toml").exists());
+    assert!(into.join("VSCode/new.txt").exists());
+
+    let manifest = read_delta(&into).unwrap().unwrap();
+    assert_eq!(manifest.delta, delta);
+    assert_eq!(manifest.delta.deleted, vec!["favicon.ico".to_string()]);
+
+    // 未安装基础版本时不能应用
+    assert_eq!(delta.scope, "Microsoft");
+    assert!(!is_delta_applicable(&delta).unwrap());
+    assert!(apply_delta(&into, &delta).is_err());
+    assert!(read_delta(Path::new("examples/VSCode")).unwrap().is_none());
+    std::fs::remove_dir_all(into).unwrap();
+
+    // 基础版本不能高于目标版本
+    let into = allocate_path_temp("VSCode_Delta", false).unwrap();
+    let mut global = global;
+    global.package.version = "1.74.0.0".to_string();
+    assert!(gen_delta_source(
+        &"test/VSCode".to_string(),
+        &global,
+        &"./test/VSCode_1.75.0.0_Cno.nep".to_string(),
+        "full".to_string(),
+        &into,
+    )
+    .is_err());
+    std::fs::remove_dir_all(into).unwrap();
+}
@@ -1,2 +1,3 @@
+pub mod delta;
 pub mod package;
 pub mod validator;
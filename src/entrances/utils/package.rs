@@ -1,38 +1,50 @@
 use std::{
-    cmp::min,
     collections::HashMap,
     fs::{remove_dir_all, File},
-    io::{Cursor, Read},
+    io::{copy, sink, BufReader, Read},
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 
 use anyhow::{anyhow, Result};
-use sysinfo::System;
-use tar::Archive;
+use tar::{Archive, Header};
 
 use crate::{
-    compression::{decompress, fast_decompress_zstd, release_tar},
+    compression::{decompress, walk_compressed},
     entrances,
-    entrances::utils::validator::{inner_validator, outer_hashmap_validator, outer_validator},
+    entrances::utils::delta::read_delta,
+    entrances::utils::validator::{inner_validator, outer_validator},
     p2s,
-    parsers::{fast_parse_signature, parse_author, parse_package, parse_signature},
-    signature::{fast_verify, verify},
-    types::package::GlobalPackage,
+    parsers::{fast_parse_signature, parse_author, parse_package},
+    signature::{blake3::Blake3Reader, verify_digest},
+    types::{package::GlobalPackage, signature::SignatureNode},
     utils::{allocate_path_temp, fs::copy_dir, is_debug_mode},
 };
 use crate::{log, log_ok_last};
 
+// 记录为每个源文件分配的临时目录，清理时才能找到带随机后缀的目录
+lazy_static! {
+    static ref TEMP_DIRS: Mutex<HashMap<String, PathBuf>> = Mutex::new(HashMap::new());
+}
+
 /// 根据源文件路径创建临时目录
 fn get_temp_dir_path(source_file: &String) -> Result<PathBuf> {
     let file_stem = p2s!(Path::new(source_file).file_stem().unwrap());
-    let temp_dir_path = allocate_path_temp(&file_stem, true)?;
+    let temp_dir_path = allocate_path_temp(&file_stem, false)?;
+    TEMP_DIRS
+        .lock()
+        .unwrap()
+        .insert(source_file.to_owned(), temp_dir_path.clone());
 
     Ok(temp_dir_path)
 }
 
 /// 清理临时目录(会判断 debug)
 pub fn clean_temp(source_file: &String) -> Result<()> {
-    let temp_dir_path = get_temp_dir_path(source_file)?;
+    let temp_dir_path = match TEMP_DIRS.lock().unwrap().remove(source_file) {
+        Some(p) => p,
+        None => return Ok(()),
+    };
     if !is_debug_mode() {
         log!("Info:Cleaning...");
         let clean_res = remove_dir_all(&temp_dir_path);
@@ -54,8 +66,12 @@ pub fn clean_temp(source_file: &String) -> Result<()> {
     Ok(())
 }
 
-/// 返回 (Inner 临时目录,package 结构体)
-pub fn unpack_nep(source: &String, verify_signature: bool) -> Result<(PathBuf, GlobalPackage)> {
+/// 返回 (Inner 临时目录,package 结构体,内包 blake3 摘要,通过校验的签名者)
+/// 输入目录时摘要为 None，未校验签名时签名者为 None
+pub fn unpack_nep(
+    source: &String,
+    verify_signature: bool,
+) -> Result<(PathBuf, GlobalPackage, Option<String>, Option<String>)> {
     // 处理输入目录的情况
     let source_path = Path::new(source);
     if source_path.is_dir() {
@@ -74,25 +90,12 @@ pub fn unpack_nep(source: &String, verify_signature: bool) -> Result<(PathBuf, G
             let temp_path = allocate_path_temp(&global.package.name, false)?;
             copy_dir(source_path, &temp_path)?;
 
-            Ok((temp_path, global))
+            Ok((temp_path, global, None, None))
         };
     }
 
-    // 检查文件大小
-    let file = File::open(source).map_err(|e| anyhow!("Error:Can't open file '{source}' : {e}"))?;
-    let meta = file.metadata()?;
-    let size = meta.len();
-    // 获取 fast 处理方法的文件大小上限，确保最大占用内存不超过 4GB
-    let s = System::new_all();
-    let size_limit = min(s.available_memory() / 3, 4 * 1024 * 1024 * 1024);
-
-    let res = if size <= size_limit {
-        log!("Debug:Use fast unpack method ({size}/{size_limit})");
-        fast_unpack_nep(source, verify_signature)?
-    } else {
-        log!("Debug:Use normal unpack method ({size}/{size_limit})");
-        normal_unpack_nep(source, verify_signature)?
-    };
+    let (temp_path, global, digest, signer) = stream_unpack_nep(source, verify_signature)?;
+    let res = (temp_path, global, Some(digest), signer);
 
     // 离线模式下强制执行一次检查
     // if !verify_signature {
@@ -102,123 +105,220 @@ pub fn unpack_nep(source: &String, verify_signature: bool) -> Result<(PathBuf, G
     Ok(res)
 }
 
-fn normal_unpack_nep(
+// 增量包需要使用已安装的基础版本补全后才能解析，仅能用于更新
+fn stream_unpack_nep(
     source_file: &String,
     verify_signature: bool,
-) -> Result<(PathBuf, GlobalPackage)> {
+) -> Result<(PathBuf, GlobalPackage, String, Option<String>)> {
+    let (temp_dir_inner_path, signature_struct, digest) =
+        unpack_nep_inner(source_file, verify_signature)?;
+    if read_delta(&temp_dir_inner_path)?.is_some() {
+        clean_temp(source_file)?;
+        return Err(anyhow!(
+            "Error:'{source_file}' is a delta package, use 'ept update' with its base version installed"
+        ));
+    }
+    let (package_struct, signer) =
+        parse_inner_package(&temp_dir_inner_path, signature_struct, verify_signature)?;
+    Ok((temp_dir_inner_path, package_struct, digest, signer))
+}
+
+// 流式读取外包，内包在读取时同步计算摘要并解压到 Inner 目录，不生成中间文件
+// 返回 (Inner 临时目录,通过校验的签名信息,内包 blake3 摘要)
+pub fn unpack_nep_inner(
+    source_file: &String,
+    verify_signature: bool,
+) -> Result<(PathBuf, SignatureNode, String)> {
     // 创建临时目录
     let temp_dir_path = get_temp_dir_path(source_file)?;
-    let temp_dir_outer_path = temp_dir_path.join("Outer");
     let temp_dir_inner_path = temp_dir_path.join("Inner");
+    let temp_dir_inner_str = p2s!(temp_dir_inner_path);
 
-    // 解压外包
-    log!("Info:Unpacking outer package...");
-    let temp_dir_outer_str = p2s!(temp_dir_outer_path);
-    release_tar(source_file, &temp_dir_outer_str)
-        .map_err(|e| anyhow!("Error:Invalid nep package : {e}"))?;
-    let signature_path = temp_dir_outer_path.join("signature.toml");
-    log_ok_last!("Info:Unpacking outer package...");
-
-    // 签名文件加载与校验
-    let signature_struct = parse_signature(&p2s!(signature_path))?.package;
-    let inner_pkg_str = outer_validator(&temp_dir_outer_str, &signature_struct.raw_name_stem)?;
-    if verify_signature {
-        log!("Info:Verifying package signature...");
-        if let Some(sign) = signature_struct.signature {
-            let check_res = verify(&inner_pkg_str, &signature_struct.signer, &sign)?;
-            if !check_res {
-                return Err(anyhow!(
-                    "Error:Failed to verify package signature, this package may have been hacked"
-                ));
-            }
-            log_ok_last!("Info:Verifying package signature...");
-        } else {
-            return Err(anyhow!(
-                "Error:This package doesn't contain signature, use offline mode to install"
-            ));
+    // 遍历外包，读取签名文件并解压内包
+    log!("Info:Decompressing inner package...");
+    let stream_res = stream_outer(source_file, verify_signature, |reader| {
+        decompress(reader, &temp_dir_inner_str)
+            .map_err(|e| anyhow!("Error:Invalid nep package : {e}"))?;
+        log_ok_last!("Info:Decompressing inner package...");
+        Ok(())
+    });
+    // 校验失败时清理整个临时目录
+    let (signature_struct, digest) = match stream_res {
+        Ok(res) => res,
+        Err(e) => {
+            clean_temp(source_file)?;
+            return Err(e);
         }
-    } else {
-        log!("Warning:Signature verification has been disabled!");
-    }
+    };
 
-    // 解压内包
-    log!("Info:Decompressing inner package...");
-    let temp_dir_inner_str = p2s!(temp_dir_inner_path);
-    decompress(&inner_pkg_str, &temp_dir_inner_str)
-        .map_err(|e| anyhow!("Error:Invalid nep package : {e}"))?;
     inner_validator(&temp_dir_inner_str)?;
-    log_ok_last!("Info:Decompressing inner package...");
-
-    // 读取 package.toml
-    let package_struct = parse_package(
-        &p2s!(temp_dir_inner_path.join("package.toml")),
-        &temp_dir_inner_str,
-        false,
-    )?;
+    Ok((temp_dir_inner_path, signature_struct, digest))
+}
 
-    // 检查签名者与第一作者是否一致
-    let author = parse_author(&package_struct.package.authors[0])?;
-    if signature_struct.signer != author.email.unwrap() {
+// 读取内包中的 package.toml，返回 (package 结构体,通过校验的签名者)
+// 检查签名者与第一作者是否一致
+fn check_signer(authors: &[String], signer: &String, verify_signature: bool) -> Result<()> {
+    let first_author = authors.first().ok_or(anyhow!(
+        "Error:Invalid package : field 'package.authors' is empty"
+    ))?;
+    let author = parse_author(first_author)?;
+    if author.email.as_ref() != Some(signer) {
         if verify_signature {
             return Err(anyhow!(
-                "Error:Invalid package : expect first author '{fa}' to be the package signer '{ps}'",
-                fa=package_struct.package.authors[0],
-                ps=signature_struct.signer
+                "Error:Invalid package : expect first author '{first_author}' to be the package signer '{signer}'"
             ));
         } else {
-            log!("Warning:Invalid package : expect first author '{fa}' to be the package signer '{ps}', ignoring this error due to signature verification has been disabled",fa=package_struct.package.authors[0],ps=signature_struct.signer);
+            log!("Warning:Invalid package : expect first author '{first_author}' to be the package signer '{signer}', ignoring this error due to signature verification has been disabled");
         }
     }
+    Ok(())
+}
+
+pub fn parse_inner_package(
+    temp_dir_inner_path: &Path,
+    signature_struct: SignatureNode,
+    verify_signature: bool,
+) -> Result<(GlobalPackage, Option<String>)> {
+    let package_struct = parse_package(
+        &p2s!(temp_dir_inner_path.join("package.toml")),
+        &p2s!(temp_dir_inner_path),
+        false,
+    )?;
+
+    check_signer(
+        &package_struct.package.authors,
+        &signature_struct.signer,
+        verify_signature,
+    )?;
 
-    Ok((temp_dir_inner_path, package_struct))
+    // 仅在签名通过校验时信任签名者
+    let signer = if verify_signature {
+        Some(signature_struct.signer)
+    } else {
+        None
+    };
+    Ok((package_struct, signer))
 }
-fn fast_unpack_nep(
+
+// 遍历外包，签名文件读入内存，内包交给 handle_inner 处理并在读取时计算摘要
+// 遍历完成后校验外包结构与签名，返回 (签名信息,内包摘要)
+fn stream_outer<F>(
     source_file: &String,
     verify_signature: bool,
-) -> Result<(PathBuf, GlobalPackage)> {
-    // 创建临时目录
-    let temp_dir_path = get_temp_dir_path(source_file)?;
-    let temp_dir_inner_path = temp_dir_path.join("Inner");
-
-    // 读取外包，生成 hashmap
-    log!("Info:Reading outer package...");
+    mut handle_inner: F,
+) -> Result<(SignatureNode, String)>
+where
+    F: FnMut(&mut dyn Read) -> Result<()>,
+{
     let outer_file =
         File::open(source_file).map_err(|e| anyhow!("Error:Can't open '{source_file}' : {e}"))?;
-    let mut outer_tar = Archive::new(outer_file);
-    let mut outer_map = HashMap::new();
+    let mut outer_tar = Archive::new(BufReader::new(outer_file));
+    let mut signature_raw = None;
+    let mut inner_pkg_name = None;
+    let mut digest = None;
     for entry in outer_tar
         .entries()
         .map_err(|e| anyhow!("Error:Failed to traverse file as tar : {e}"))?
     {
         let mut entry = entry.map_err(|e| anyhow!("Error:Failed to get tar file entry : {e}"))?;
+        // 外包中的目录条目（如根目录 './'）不携带内容
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
         let name = p2s!(entry
             .path()
             .map_err(|e| anyhow!("Error:Failed to get tar file path : {e}"))?);
-        let mut buffer = Vec::with_capacity(
-            entry
-                .header()
-                .size()
-                .map_err(|e| anyhow!("Error:Failed to get tar file size : {e}"))?
-                as usize,
-        );
-        entry.read_to_end(&mut buffer)?;
-        outer_map.insert(name, buffer);
+        if name == "signature.toml" {
+            let mut buffer = Vec::new();
+            entry.read_to_end(&mut buffer)?;
+            signature_raw = Some(buffer);
+        } else if name.ends_with(".tar.zst") && inner_pkg_name.is_none() {
+            let mut reader = Blake3Reader::new(entry);
+            handle_inner(&mut reader)?;
+            // 摘要需要覆盖整个内包，包括 zstd 帧之后的剩余字节
+            digest = Some(reader.finalize()?);
+            inner_pkg_name = Some(name);
+        } else {
+            return Err(anyhow!(
+                "Error:Invalid nep outer package : unexpected file '{name}'"
+            ));
+        }
+    }
+    let signature_struct = check_outer_signature(
+        signature_raw,
+        inner_pkg_name,
+        digest.clone(),
+        verify_signature,
+    )?;
+    Ok((signature_struct, digest.unwrap()))
+}
+
+// 流式遍历内包中的条目而不解压到磁盘，遍历完成后才会校验签名
+// 因此 visit 中读取的内容需要在函数返回 Ok 后才能使用
+pub fn inspect_nep<F>(source_file: &String, verify_signature: bool, mut visit: F) -> Result<()>
+where
+    F: FnMut(&Header, String, &mut dyn Read) -> Result<()>,
+{
+    if Path::new(source_file).is_dir() {
+        return Err(anyhow!(
+            "Error:Given path '{source_file}' refers to a directory, expect a nep package"
+        ));
     }
-    log_ok_last!("Info:Reading outer package...");
+    let mut package_raw = None;
+    let (signature_struct, _) = stream_outer(source_file, verify_signature, |reader| {
+        walk_compressed(reader, |header, name, entry_reader| {
+            // 保留 package.toml 的内容，以便在签名校验通过后检查签名者
+            if name.trim_start_matches("./") == "package.toml" {
+                let mut buffer = Vec::new();
+                entry_reader.read_to_end(&mut buffer)?;
+                let res = visit(header, name, &mut buffer.as_slice());
+                package_raw = Some(buffer);
+                res
+            } else {
+                visit(header, name, entry_reader)
+            }
+        })
+        .map_err(|e| anyhow!("Error:Invalid nep package : {e}"))
+    })?;
 
-    // 签名文件加载与校验
-    let signature_raw = outer_map.get_mut("signature.toml").ok_or(anyhow!(
+    // 与解包时一致，签名者需要是第一作者
+    let package_raw = package_raw.ok_or(anyhow!(
+        "Error:Invalid nep inner package : missing 'package.toml'"
+    ))?;
+    let package_struct: GlobalPackage = toml::from_str(&String::from_utf8_lossy(&package_raw))
+        .map_err(|e| anyhow!("Error:Invalid package.toml in '{source_file}' : {e}"))?;
+    check_signer(
+        &package_struct.package.authors,
+        &signature_struct.signer,
+        verify_signature,
+    )
+}
+
+// 计算外包中内包的 blake3 摘要，不解压内包
+pub fn read_nep_hash(source_file: &String, verify_signature: bool) -> Result<String> {
+    let (_, digest) = stream_outer(source_file, verify_signature, |reader| {
+        copy(reader, &mut sink())?;
+        Ok(())
+    })?;
+    Ok(digest)
+}
+
+// 校验外包结构，并使用读取内包时计算的摘要校验签名
+fn check_outer_signature(
+    signature_raw: Option<Vec<u8>>,
+    inner_pkg_name: Option<String>,
+    digest: Option<String>,
+    verify_signature: bool,
+) -> Result<SignatureNode> {
+    let mut signature_raw = signature_raw.ok_or(anyhow!(
         "Error:Invalid nep outer package : missing 'signature.toml'"
     ))?;
-    let signature_struct = fast_parse_signature(signature_raw)?.package;
-    outer_hashmap_validator(&outer_map, &signature_struct.raw_name_stem)?;
-    let inner_pkg_raw = outer_map
-        .get(&(signature_struct.raw_name_stem + ".tar.zst"))
-        .unwrap();
+    let signature_struct = fast_parse_signature(&mut signature_raw)?.package;
+    outer_validator(inner_pkg_name.as_ref(), &signature_struct.raw_name_stem)?;
     if verify_signature {
         log!("Info:Verifying package signature...");
-        if let Some(sign) = signature_struct.signature {
-            let check_res = fast_verify(inner_pkg_raw, &signature_struct.signer, &sign)?;
+        if let Some(sign) = &signature_struct.signature {
+            let check_res = verify_digest(&digest.unwrap(), &signature_struct.signer, sign)?;
             if !check_res {
                 return Err(anyhow!(
                     "Error:Failed to verify package signature, this package may have been hacked"
@@ -234,38 +334,7 @@ fn fast_unpack_nep(
         log!("Warning:Signature verification has been disabled!");
     }
 
-    // 解压内包到临时目录
-    log!("Info:Decompressing inner package...");
-    let temp_dir_inner_str = p2s!(temp_dir_inner_path);
-    let inner_tar_raw = fast_decompress_zstd(inner_pkg_raw)?;
-    let inner_tar_file = Cursor::new(inner_tar_raw);
-    let mut inner_archive = Archive::new(inner_tar_file);
-    inner_archive.unpack(&temp_dir_inner_str)?;
-    inner_validator(&temp_dir_inner_str)?;
-    log_ok_last!("Info:Decompressing inner package...");
-
-    // 读取 package.toml
-    let package_struct = parse_package(
-        &p2s!(temp_dir_inner_path.join("package.toml")),
-        &temp_dir_inner_str,
-        false,
-    )?;
-
-    // 检查签名者与第一作者是否一致
-    let author = parse_author(&package_struct.package.authors[0])?;
-    if signature_struct.signer != author.email.unwrap() {
-        if verify_signature {
-            return Err(anyhow!(
-                "Error:Invalid package : expect first author '{fa}' to be the package signer '{ps}'",
-                fa=package_struct.package.authors[0],
-                ps=signature_struct.signer
-            ));
-        } else {
-            log!("Warning:Invalid package : expect first author '{fa}' to be the package signer '{ps}', ignoring this error due to signature verification has been disabled",fa=package_struct.package.authors[0],ps=signature_struct.signer);
-        }
-    }
-
-    Ok((temp_dir_inner_path, package_struct))
+    Ok(signature_struct)
 }
 
 #[test]
@@ -281,15 +350,20 @@ fn test_unpack_nep() {
         &"./examples/VSCode".to_string(),
         Some("./test/VSCode_1.75.0.0_Cno.nep".to_string()),
         true,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
 
     let res = unpack_nep(&"./test/VSCode_1.75.0.0_Cno.nep".to_string(), true).unwrap();
     println!("{res:#?}");
+    assert!(res.2.is_some());
+    assert_eq!(res.3, Some("dsyourshy@qq.com".to_string()));
+    let hash = read_nep_hash(&"./test/VSCode_1.75.0.0_Cno.nep".to_string(), true).unwrap();
+    assert_eq!(res.2, Some(hash));
 }
 
 #[test]
-fn test_normal_unpack_nep() {
+fn test_stream_unpack_nep() {
     use crate::utils::flags::{set_flag, Flag};
     if cfg!(debug_assertions) {
         log!("Warning:Debug mode enabled");
@@ -301,65 +375,24 @@ fn test_normal_unpack_nep() {
         &"./examples/VSCode".to_string(),
         Some("./test/VSCode_1.75.0.0_Cno.nep".to_string()),
         true,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
 
-    let res = normal_unpack_nep(&"./test/VSCode_1.75.0.0_Cno.nep".to_string(), true).unwrap();
+    let res = stream_unpack_nep(&"./test/VSCode_1.75.0.0_Cno.nep".to_string(), true).unwrap();
+    assert!(res.0.join("package.toml").exists());
+    // 不再生成外包目录与中间 tar 文件
+    assert!(!res.0.with_file_name("Outer").exists());
     println!("{res:#?}");
 }
 
-#[test]
-fn test_fast_unpack_nep() {
-    use crate::utils::flags::{set_flag, Flag};
-    if cfg!(debug_assertions) {
-        log!("Warning:Debug mode enabled");
-        set_flag(Flag::Debug, true);
-    }
-    crate::utils::test::_ensure_clear_test_dir();
-
-    crate::pack(
-        &"./examples/VSCode".to_string(),
-        Some("./test/VSCode_1.75.0.0_Cno.nep".to_string()),
-        true,
-    )
-    .unwrap();
-
-    let res = fast_unpack_nep(&"./test/VSCode_1.75.0.0_Cno.nep".to_string(), true).unwrap();
-    println!("{res:#?}");
-}
-
-// #[test]
-// fn benchmark_fast_unpack_nep() {
-//     set_flag(Flag::DEBUG, true);
-//     // 准备带有一定体积的包
-//     crate::utils::test::_ensure_clear_test_dir();
-
-//     crate::pack(
-//         &"examples/Dism++".to_string(),
-//         Some("./test/Dism++_10.1.1002.1_Cno.nep".to_string()),
-//         true,
-//     )
-//     .unwrap();
-
-//     use std::time::Instant;
-//     let normal = Instant::now();
-//     for _ in 0..10 {
-//         normal_unpack_nep(&"./test/Dism++_10.1.1002.1_Cno.nep".to_string(), true).unwrap();
-//     }
-
-//     let fast = Instant::now();
-//     for _ in 0..10 {
-//         fast_unpack_nep(&"./test/Dism++_10.1.1002.1_Cno.nep".to_string(), true).unwrap();
-//     }
-//     println!(
-//         "Normal unpack cost {n}ms, fast unpack cost {f}ms",
-//         n = normal.elapsed().as_millis(),
-//         f = fast.elapsed().as_millis()
-//     );
-// }
-
 #[test]
 fn test_bad_package() {
+    use crate::compression::release_tar;
+    use crate::utils::{
+        flags::{set_flag, Flag},
+        path::parse_relative_path_with_base,
+    };
     crate::utils::test::_ensure_clear_test_dir();
 
     // 生成基础目录
@@ -367,10 +400,11 @@ fn test_bad_package() {
         &"./examples/Dism++".to_string(),
         Some("./test/Normal.nep".to_string()),
         true,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
     release_tar(
-        &"./test/Normal.nep".to_string(),
+        File::open("./test/Normal.nep").unwrap(),
         &"./test/Normal".to_string(),
     )
     .unwrap();
@@ -380,14 +414,15 @@ fn test_bad_package() {
         &"./examples/Dism++".to_string(),
         Some("./test/UnSig++_10.1.1002.1_Cno.nep".to_string()),
         false,
+        &crate::types::pack::PackOptions::default(),
     )
     .unwrap();
-    assert!(normal_unpack_nep(&"./test/UnSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
-    assert!(fast_unpack_nep(&"./test/UnSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
+    assert!(stream_unpack_nep(&"./test/UnSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
 
     // 被篡改的签名
     copy_dir("test/Normal", "test/BadSig").unwrap();
-    let mut signature_struct = parse_signature(&"test/BadSig/signature.toml".to_string()).unwrap();
+    let mut signature_struct =
+        fast_parse_signature(&mut std::fs::read("test/BadSig/signature.toml").unwrap()).unwrap();
     signature_struct.package.signature = signature_struct
         .package
         .signature
@@ -399,8 +434,24 @@ fn test_bad_package() {
         &"test/BadSig++_10.1.1002.1_Cno.nep".to_string(),
     )
     .unwrap();
-    assert!(normal_unpack_nep(&"test/BadSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
-    assert!(fast_unpack_nep(&"test/BadSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
+    // 校验失败时不会残留临时目录
+    set_flag(Flag::Debug, false);
+    let count_temp = |stem: &str| {
+        std::fs::read_dir(parse_relative_path_with_base("temp").unwrap())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&(stem.to_string() + "_"))
+            })
+            .count()
+    };
+    let temp_count = count_temp("BadSig++_10.1.1002.1_Cno");
+    assert!(stream_unpack_nep(&"test/BadSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
+    assert_eq!(count_temp("BadSig++_10.1.1002.1_Cno"), temp_count);
 
     // 缺失签名文件
     copy_dir("test/Normal", "test/NoSig").unwrap();
@@ -410,12 +461,12 @@ fn test_bad_package() {
         &"test/NoSig++_10.1.1002.1_Cno.nep".to_string(),
     )
     .unwrap();
-    assert!(normal_unpack_nep(&"test/NoSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
-    assert!(fast_unpack_nep(&"test/NoSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
+    assert!(stream_unpack_nep(&"test/NoSig++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
 
     // 错误的打包者
     copy_dir("test/Normal", "test/BadAuth").unwrap();
-    let mut signature_struct = parse_signature(&"test/BadAuth/signature.toml".to_string()).unwrap();
+    let mut signature_struct =
+        fast_parse_signature(&mut std::fs::read("test/BadAuth/signature.toml").unwrap()).unwrap();
     signature_struct.package.signer = "Jack".to_string();
     let text = toml::to_string_pretty(&signature_struct).unwrap();
     std::fs::write("test/BadAuth/signature.toml", text).unwrap();
@@ -424,6 +475,48 @@ fn test_bad_package() {
         &"test/BadAuth++_10.1.1002.1_Cno.nep".to_string(),
     )
     .unwrap();
-    assert!(normal_unpack_nep(&"test/BadAuth++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
-    assert!(fast_unpack_nep(&"test/BadAuth++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
+    assert!(stream_unpack_nep(&"test/BadAuth++_10.1.1002.1_Cno.nep".to_string(), true).is_err());
+
+    // 增量包不能直接解包，且不会残留临时目录
+    copy_dir("examples/Dism++", "test/Delta++").unwrap();
+    let delta = crate::types::delta::Delta {
+        delta: crate::types::delta::DeltaNode {
+            scope: "Chuyu".to_string(),
+            name: "Dism++".to_string(),
+            version: "10.1.1002.1".to_string(),
+            base_version: "10.1.1002.0".to_string(),
+            base_hash: "base".to_string(),
+            full_hash: "full".to_string(),
+            deleted: Vec::new(),
+        },
+    };
+    std::fs::write(
+        "test/Delta++/delta.toml",
+        toml::to_string_pretty(&delta).unwrap(),
+    )
+    .unwrap();
+    // 源目录校验不允许额外文件，因此手动生成外包
+    std::fs::create_dir_all("test/DeltaOuter").unwrap();
+    crate::compression::compress(
+        &"test/Delta++".to_string(),
+        &"test/DeltaOuter/Delta++_10.1.1002.1_Cno.tar.zst".to_string(),
+        &crate::types::pack::PackOptions::default(),
+    )
+    .unwrap();
+    let mut signature_struct =
+        fast_parse_signature(&mut std::fs::read("test/Normal/signature.toml").unwrap()).unwrap();
+    signature_struct.package.raw_name_stem = "Delta++_10.1.1002.1_Cno".to_string();
+    let text = toml::to_string_pretty(&signature_struct).unwrap();
+    std::fs::write("test/DeltaOuter/signature.toml", text).unwrap();
+    crate::compression::pack_tar(
+        &"test/DeltaOuter".to_string(),
+        &"test/Delta++_10.1.1002.1_Cno.nep".to_string(),
+    )
+    .unwrap();
+    let temp_count = count_temp("Delta++_10.1.1002.1_Cno");
+    let err = stream_unpack_nep(&"test/Delta++_10.1.1002.1_Cno.nep".to_string(), false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("is a delta package"));
+    assert_eq!(count_temp("Delta++_10.1.1002.1_Cno"), temp_count);
 }
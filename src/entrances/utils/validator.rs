@@ -1,44 +1,172 @@
 use anyhow::{anyhow, Result};
-use std::{
-    collections::{HashMap, HashSet},
-    path::Path,
-};
+use std::{fs::read_dir, path::Path};
 
 use crate::{
     executor::values_validator_path,
-    p2s,
-    types::mixed_fs::MixedFS,
-    utils::{term::ask_yn, wild_match::contains_wild_match},
+    log, p2s,
+    types::{
+        extended_semver::ExSemVer, mixed_fs::MixedFS, package::GlobalPackage, software::Software,
+        steps::Step, workflow::WorkflowNode,
+    },
+    utils::{
+        exe_version::get_exe_version, is_starts_with_inner_value,
+        path::parse_relative_path_with_located, term::ask_yn, wild_match::contains_wild_match,
+    },
 };
 
+// 收集的问题中第一个作为错误返回
+fn first_problem(problems: Vec<String>) -> Result<()> {
+    match problems.into_iter().next() {
+        Some(problem) => Err(anyhow!(problem)),
+        None => Ok(()),
+    }
+}
+
+fn collect_inner_problems(dir: &String) -> Vec<String> {
+    vec!["package.toml", "workflows/setup.toml"]
+        .into_iter()
+        .filter(|file_name| !Path::new(dir).join(file_name).exists())
+        .map(|file_name| {
+            format!("Error:Invalid nep inner package : missing '{file_name}' in '{dir}'")
+        })
+        .collect()
+}
+
 pub fn inner_validator(dir: &String) -> Result<()> {
-    let manifest = vec!["package.toml", "workflows/setup.toml"];
-    for file_name in manifest {
-        let p = Path::new(dir).join(file_name);
-        if !p.exists() {
-            return Err(anyhow!(
-                "Error:Invalid nep inner package : missing '{file_name}' in '{dir}'"
-            ));
-        }
+    first_problem(collect_inner_problems(dir))
+}
+
+// 收集源目录结构的全部问题，源目录中只能包含 package.toml、workflows 与程序目录
+pub fn collect_source_dir_problems(dir: &String) -> Result<Vec<String>> {
+    let mut problems = Vec::new();
+    let dir_count = read_dir(dir)?.count();
+    if dir_count != 3 {
+        problems.push(format!(
+            "Error:Expected 3 items in '{dir}', got {dir_count} items"
+        ));
     }
-    Ok(())
+    problems.append(&mut collect_inner_problems(dir));
+    Ok(problems)
 }
 
-pub fn manifest_validator(base: &String, manifest: Vec<String>, fs: &mut MixedFS) -> Result<()> {
-    let mut missing_list = HashSet::new();
+pub fn source_dir_validator(dir: &String) -> Result<()> {
+    first_problem(collect_source_dir_problems(dir)?)
+}
+
+// 收集装箱单的全部问题，返回 (不合法条目的错误信息，按出现顺序去重的缺失条目)
+pub fn collect_manifest_problems(
+    manifest: Vec<String>,
+    fs: &mut MixedFS,
+) -> (Vec<String>, Vec<String>) {
+    let mut invalid = Vec::new();
+    let mut missing = Vec::new();
     for path in manifest {
-        values_validator_path(&path)?;
-        if contains_wild_match(&path) {
-            return Err(anyhow!(
+        if let Err(e) = values_validator_path(&path) {
+            invalid.push(e.to_string());
+        } else if contains_wild_match(&path) {
+            invalid.push(format!(
                 "Error:Wild match shouldn't appear in manifest item '{path}'"
             ));
+        } else if !fs.exists(&path) && !missing.contains(&path) {
+            missing.push(path);
+        }
+    }
+    (invalid, missing)
+}
+
+// 工作流中是否有启用了 call_installer 的 Execute 步骤
+pub fn has_call_installer(flow: &[WorkflowNode]) -> bool {
+    flow.iter().any(|node| {
+        matches!(&node.body, Step::StepExecute(step) if step.call_installer.unwrap_or(false))
+    })
+}
+
+// 收集 call_installer 的全部问题，返回（问题所在的文件，错误信息）
+// 传入各工作流是否调用了 call_installer，工作流不存在时为 None
+pub fn collect_call_installer_problems(
+    software: &Software,
+    setup: bool,
+    update: Option<bool>,
+    remove: Option<bool>,
+) -> Vec<(String, String)> {
+    let mut problems = Vec::new();
+    if !setup {
+        return problems;
+    }
+
+    // 如果用到了 call_installer 则有一些特殊逻辑，除非提供了 registry_entry：
+    if software.registry_entry.is_none() {
+        // 必须有卸载流
+        if remove.is_none() {
+            problems.push(("workflows/remove.toml".to_string(), "Error:Workflow 'remove.toml' should include 'Execute' step with 'call_installer' field enabled when workflow 'setup.toml' includes such step".to_string()));
+        }
+
+        // 必须提供绝对路径的 main_program
+        if let Some(mp) = &software.main_program {
+            if !is_starts_with_inner_value(mp) && !Path::new(mp).is_absolute() {
+                problems.push(("package.toml".to_string(), format!("Error:Field 'main_program' in table 'software' should starts with inner value when workflow 'setup.toml' includes 'Execute' step with 'call_installer' field, got '{mp}'")));
+            }
+        } else {
+            problems.push(("package.toml".to_string(), "Error:Field 'main_program' or 'registry_entry' in table 'software' should be provided when workflow 'setup.toml' includes 'Execute' step with 'call_installer' field".to_string()));
+        }
+    }
+
+    // 更新、卸载工作流同样需要调用安装器
+    for (name, call_installer) in [("update.toml", update), ("remove.toml", remove)] {
+        if call_installer == Some(false) {
+            problems.push((format!("workflows/{name}"), format!("Error:Workflow '{name}' should include 'Execute' step with 'call_installer' field enabled when workflow 'setup.toml' includes such step")));
         }
-        if !fs.exists(&path) {
-            missing_list.insert(path);
+    }
+    problems
+}
+
+// 如果显式提供了相对路径的主程序，收集读取其版本号时的问题
+// 主程序不存在的问题由 software 表的校验报告
+pub fn collect_main_program_problems(source_dir: &String, global: &GlobalPackage) -> Vec<String> {
+    let mp = match global
+        .software
+        .as_ref()
+        .and_then(|s| s.main_program.clone())
+    {
+        Some(mp) if !mp.starts_with("${") && Path::new(&mp).is_relative() => mp,
+        _ => return Vec::new(),
+    };
+    let mp_path = parse_relative_path_with_located(
+        &format!("{name}/{mp}", name = global.package.name),
+        source_dir,
+    );
+    log!(
+        "Debug:Main program path : '{}',with source_dir = '{source_dir}'",
+        p2s!(mp_path)
+    );
+    if !mp_path.exists() {
+        return Vec::new();
+    }
+    let version = match get_exe_version(mp_path) {
+        Ok(version) => version,
+        Err(e) => return vec![format!("Error:Failed to read version of main program '{mp}', consider remove field 'software.main_program' : {e}")],
+    };
+
+    // 与申明的版本号进行比较，仅要求 semver 部分相等即可
+    match (
+        ExSemVer::parse(&global.package.version),
+        ExSemVer::parse(&version),
+    ) {
+        (Ok(d_ver), Ok(r_ver)) => {
+            if d_ver.semver_instance != r_ver.semver_instance {
+                vec![format!("Error:The version declared ({dv}) is inconsistent with the version obtained by the read main program ({version}), consider remove field 'software.main_program'",dv=&global.package.version)]
+            } else {
+                Vec::new()
+            }
         }
+        (Err(e), _) | (_, Err(e)) => vec![e.to_string()],
     }
-    if !missing_list.is_empty() {
-        let items: Vec<String> = missing_list.into_iter().collect();
+}
+
+pub fn manifest_validator(base: &String, manifest: Vec<String>, fs: &mut MixedFS) -> Result<()> {
+    let (invalid, items) = collect_manifest_problems(manifest, fs);
+    first_problem(invalid)?;
+    if !items.is_empty() {
         if fs.var_warn_manifest {
             if !ask_yn(
                 format!("May missing these flow items '{items:?}' in '{base}', continue?"),
@@ -56,42 +184,14 @@ pub fn manifest_validator(base: &String, manifest: Vec<String>, fs: &mut MixedFS
     Ok(())
 }
 
-// 定义外包的装箱单（内包名称需要传入）
-macro_rules! def_outer_manifest {
-    ($inner_pkg_name:expr) => {{
-        vec![&$inner_pkg_name]
-    }};
-}
-
-// 返回内包路径
-pub fn outer_validator(dir: &String, stem: &String) -> Result<String> {
-    let inner_pkg_name = stem.to_owned() + ".tar.zst";
-    let manifest = def_outer_manifest!(inner_pkg_name);
-    for file_name in manifest {
-        let p = Path::new(dir).join(file_name);
-        if !p.exists() {
-            return Err(anyhow!(
-                "Error:Invalid nep outer package : missing '{file_name}' in '{dir}'"
-            ));
-        }
-    }
-
-    let inner_path = Path::new(dir).join(&inner_pkg_name);
-    Ok(p2s!(inner_path))
-}
-
-pub fn outer_hashmap_validator(map: &HashMap<String, Vec<u8>>, stem: &String) -> Result<()> {
-    let inner_pkg_name = stem.to_owned() + ".tar.zst";
-    let manifest = def_outer_manifest!(inner_pkg_name);
-    for file_name in manifest {
-        let entry = map.get(file_name);
-        if entry.is_none() {
-            return Err(anyhow!(
-                "Error:Invalid nep outer package : missing '{file_name}'"
-            ));
-        }
+// 检查外包中读到的内包与签名文件中记录的名称一致
+pub fn outer_validator(inner_pkg_name: Option<&String>, stem: &String) -> Result<()> {
+    let expected = stem.to_owned() + ".tar.zst";
+    if inner_pkg_name != Some(&expected) {
+        return Err(anyhow!(
+            "Error:Invalid nep outer package : missing '{expected}'"
+        ));
     }
-
     Ok(())
 }
 
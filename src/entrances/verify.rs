@@ -1,21 +1,21 @@
-use crate::parsers::{parse_package, parse_workflow};
-use crate::types::extended_semver::ExSemVer;
+use crate::parsers::{parse_package, parse_workflow, parse_workflow_with_source};
 use crate::types::mixed_fs::MixedFS;
 use crate::types::package::GlobalPackage;
-use crate::types::steps::{Step, VerifyStepCtx};
+use crate::types::source_map::SourceMap;
+use crate::types::steps::VerifyStepCtx;
 use crate::types::workflow::WorkflowNode;
-use crate::utils::exe_version::get_exe_version;
 use crate::utils::is_starts_with_inner_value;
-use crate::utils::path::parse_relative_path_with_located;
 use crate::{log, log_ok_last, p2s};
 use anyhow::{anyhow, Result};
 use std::collections::HashSet;
-use std::fs::read_dir;
 use std::path::{Path, PathBuf};
 
-use super::utils::validator::{inner_validator, manifest_validator};
+use super::utils::validator::{
+    collect_call_installer_problems, collect_main_program_problems, has_call_installer,
+    manifest_validator, source_dir_validator,
+};
 
-fn get_manifest(flow: Vec<WorkflowNode>, fs: &mut MixedFS) -> Vec<String> {
+pub(super) fn get_manifest(flow: Vec<WorkflowNode>, fs: &mut MixedFS) -> Vec<String> {
     let mut manifest = Vec::new();
     let mut set = HashSet::new();
     for node in flow {
@@ -48,32 +48,25 @@ fn get_workflow_path(source_dir: &String, file_name: &str) -> PathBuf {
 }
 
 // 返回是否调用了 call_installer
-fn verify_workflow(flow: Vec<WorkflowNode>, ctx: &VerifyStepCtx) -> Result<bool> {
-    let mut have_call_installer = false;
-    for node in flow {
-        node.verify_step(ctx)?;
-        if let Step::StepExecute(step) = node.body {
-            if !have_call_installer {
-                have_call_installer = step.call_installer.unwrap_or(false);
-            }
-        }
+fn verify_workflow(
+    flow: Vec<(String, WorkflowNode)>,
+    source: &SourceMap,
+    ctx: &VerifyStepCtx,
+) -> Result<bool> {
+    let mut nodes = Vec::new();
+    for (key, node) in flow {
+        // 校验失败时指出步骤在工作流文件中的位置
+        node.verify_step(ctx)
+            .map_err(|e| anyhow!(source.annotate(e.to_string(), &key)))?;
+        nodes.push(node);
     }
-    Ok(have_call_installer)
+    Ok(has_call_installer(&nodes))
 }
 
 pub fn verify(source_dir: &String) -> Result<GlobalPackage> {
     // 打包检查
     log!("Info:Validating source directory...");
-    // 如果目录中文件数量超过 3 个则拒绝
-    let dir_list = read_dir(source_dir)?;
-    let dir_count = dir_list.into_iter().fold(0, |acc, _| acc + 1);
-    if dir_count != 3 {
-        return Err(anyhow!(
-            "Error:Expected 3 items in '{source_dir}', got {dir_count} items"
-        ));
-    }
-    // 运行内包检查器
-    inner_validator(source_dir)?;
+    source_dir_validator(source_dir)?;
     log_ok_last!("Info:Validating source directory...");
 
     // 读取包信息
@@ -87,49 +80,45 @@ pub fn verify(source_dir: &String) -> Result<GlobalPackage> {
     // 校验工作流
     log!("Info:Verifying workflows...");
     let setup_path = get_workflow_path(source_dir, "setup.toml");
-    let setup_flow = parse_workflow(&p2s!(setup_path))?;
+    let (setup_flow, setup_source) = parse_workflow_with_source(&p2s!(setup_path))?;
 
     // 记录 setup 中是否用到 call_installer
     let check_call_installer = verify_workflow(
         setup_flow.clone(),
+        &setup_source,
         &VerifyStepCtx {
             mixed_fs: MixedFS::new(&pkg_content_path),
             is_expand_flow: false,
         },
     )?;
 
-    // 如果用到了 call_installer 则有一些特殊逻辑，除非提供了 registry_entry：
-    if check_call_installer && software.registry_entry.is_none() {
-        // 必须有卸载流
-        if !get_workflow_path(source_dir, "remove.toml").exists() {
-            return Err(anyhow!("Error:Workflow 'remove.toml' should include 'Execute' step with 'call_installer' field enabled when workflow 'setup.toml' includes such step"));
-        }
-
-        // 必须提供绝对路径的 main_program
-        if let Some(mp) = software.main_program.clone() {
-            if !Path::new(&mp).is_absolute() {
-                return Err(anyhow!("Error:Field 'main_program' in table 'software' should starts with inner value when workflow 'setup.toml' includes 'Execute' step with 'call_installer' field, got '{mp}'"));
-            }
-        } else {
-            return Err(anyhow!("Error:Field 'main_program' or 'registry_entry' in table 'software' should be provided when workflow 'setup.toml' includes 'Execute' step with 'call_installer' field"));
-        }
-    }
-
     // 检查更新、卸载工作流
-    let optional_workflows = vec!["update.toml", "remove.toml"];
     let ctx = VerifyStepCtx {
         mixed_fs: MixedFS::new(source_dir),
         is_expand_flow: false,
     };
-    for opt_workflow in optional_workflows {
+    let mut optional_call_installer = Vec::new();
+    for opt_workflow in ["update.toml", "remove.toml"] {
         let opt_path = get_workflow_path(source_dir, opt_workflow);
-        if opt_path.exists() {
-            let flow = parse_workflow(&p2s!(opt_path))?;
-            let call_installer = verify_workflow(flow, &ctx)?;
-            if check_call_installer && !call_installer {
-                return Err(anyhow!("Error:Workflow '{opt_workflow}' should include 'Execute' step with 'call_installer' field enabled when workflow 'setup.toml' includes such step"));
-            }
-        }
+        optional_call_installer.push(if opt_path.exists() {
+            let (flow, source) = parse_workflow_with_source(&p2s!(opt_path))?;
+            Some(verify_workflow(flow, &source, &ctx)?)
+        } else {
+            None
+        });
+    }
+
+    // 如果用到了 call_installer 则有一些特殊逻辑
+    if let Some((_, problem)) = collect_call_installer_problems(
+        &software,
+        check_call_installer,
+        optional_call_installer[0],
+        optional_call_installer[1],
+    )
+    .into_iter()
+    .next()
+    {
+        return Err(anyhow!(problem));
     }
 
     // 检查展开工作流
@@ -139,8 +128,8 @@ pub fn verify(source_dir: &String) -> Result<GlobalPackage> {
     };
     let expand_path = get_workflow_path(source_dir, "expand.toml");
     if expand_path.exists() {
-        let flow = parse_workflow(&p2s!(expand_path))?;
-        verify_workflow(flow, &ctx)?;
+        let (flow, source) = parse_workflow_with_source(&p2s!(expand_path))?;
+        verify_workflow(flow, &source, &ctx)?;
     }
 
     log_ok_last!("Info:Verifying workflows...");
@@ -153,6 +142,7 @@ pub fn verify(source_dir: &String) -> Result<GlobalPackage> {
         let expand_flow = parse_workflow(&p2s!(expand_path))?;
         let _expand_manifest = get_manifest(expand_flow, &mut fs);
     }
+    let setup_flow = setup_flow.into_iter().map(|(_, node)| node).collect();
     let mut setup_manifest = get_manifest(setup_flow, &mut fs);
     // 加上 update 工作流的装箱单
     let update_path = get_workflow_path(source_dir, "update.toml");
@@ -165,31 +155,11 @@ pub fn verify(source_dir: &String) -> Result<GlobalPackage> {
     log_ok_last!("Info:Checking manifest...");
 
     // 如果显式提供了相对路径的主程序，检查该主程序是否可以正常读取版本号
-    if let Some(mp) = software.main_program {
-        if !mp.starts_with("${") && Path::new(&mp).is_relative() {
-            let mp_path = parse_relative_path_with_located(
-                &format!("{name}/{mp}", name = global.package.name),
-                source_dir,
-            );
-            log!(
-                "Debug:Main program path : '{}',with source_dir = '{source_dir}'",
-                p2s!(mp_path)
-            );
-            let read_res = get_exe_version(mp_path);
-            if let Ok(version) = read_res {
-                // 与申明的版本号进行比较，仅要求 semver 部分相等即可
-                let d_ver = ExSemVer::parse(&global.package.version)?;
-                let r_ver = ExSemVer::parse(&version)?;
-                if d_ver.semver_instance != r_ver.semver_instance {
-                    return Err(anyhow!("Error:The version declared ({dv}) is inconsistent with the version obtained by the read main program ({version}), consider remove field 'software.main_program'",dv=&global.package.version));
-                }
-            } else {
-                return Err(anyhow!(
-                    "Error:Failed to read version of main program '{mp}', consider remove field 'software.main_program' : {e}",
-                    e = read_res.unwrap_err()
-                ));
-            }
-        }
+    if let Some(problem) = collect_main_program_problems(source_dir, &global)
+        .into_iter()
+        .next()
+    {
+        return Err(anyhow!(problem));
     }
 
     Ok(global)
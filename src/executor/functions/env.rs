@@ -0,0 +1,49 @@
+use crate::{
+    types::permissions::{Permission, PermissionKey, PermissionLevel},
+    utils::conditions::ensure_args,
+};
+use anyhow::{anyhow, Result};
+use evalexpr::{Function, Value};
+use std::env::var;
+
+use super::{ArgType, EvalFunction};
+
+pub struct Env {
+    //- 读取某个环境变量的值，环境变量不存在时返回空字符串
+    //@ 需要输入不包含 `=` 的环境变量名称
+    //# `if = 'Env("PROCESSOR_ARCHITECTURE")=="AMD64"'`
+}
+
+impl EvalFunction for Env {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String]
+    }
+    fn get_closure(_: String) -> Function {
+        let signature = Self::get_signature();
+        Function::new(move |val| {
+            let args = ensure_args(val, &signature)?;
+            let arg = args[0].as_string()?;
+            Ok(Value::String(var(arg).unwrap_or_default()))
+        })
+    }
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
+            key: PermissionKey::env_read,
+            level: PermissionLevel::Normal,
+            targets: vec![arg],
+        }))
+    }
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        let arg = args[0].as_string()?;
+        if arg.is_empty() || arg.contains('=') {
+            return Err(anyhow!(
+                "Error:Argument of 'Env' should be a valid environment variable name, got '{arg}'"
+            ));
+        }
+        Ok(())
+    }
+    fn get_placeholder() -> Value {
+        Value::String(String::new())
+    }
+}
@@ -1,12 +1,12 @@
 use crate::{
     executor::{judge_perm_level, values_validator_path},
     types::permissions::{Permission, PermissionKey},
-    utils::{conditions::ensure_arg, path::parse_relative_path_with_located},
+    utils::{conditions::ensure_args, path::parse_relative_path_with_located},
 };
 use anyhow::Result;
 use evalexpr::{Function, Value};
 
-use super::EvalFunction;
+use super::{ArgType, EvalFunction};
 
 pub struct Exist {
     //- 检查某个路径指向的文件或目录是否存在
@@ -15,22 +15,28 @@ pub struct Exist {
 }
 
 impl EvalFunction for Exist {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String]
+    }
     fn get_closure(located: String) -> Function {
+        let signature = Self::get_signature();
         Function::new(move |val| {
-            let arg = ensure_arg(val)?;
+            let args = ensure_args(val, &signature)?;
+            let arg = args[0].as_string()?;
             let p = parse_relative_path_with_located(&arg, &located);
 
             Ok(Value::Boolean(p.exists()))
         })
     }
-    fn get_permission(arg: String) -> Result<Permission> {
-        Ok(Permission {
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
             key: PermissionKey::fs_read,
             level: judge_perm_level(&arg)?,
             targets: vec![arg],
-        })
+        }))
     }
-    fn verify_arg(arg: String) -> Result<()> {
-        values_validator_path(&arg)
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        values_validator_path(&args[0].as_string()?)
     }
 }
@@ -0,0 +1,60 @@
+use crate::{
+    executor::{judge_perm_level, values_validator_path},
+    types::permissions::{Permission, PermissionKey},
+    utils::{conditions::ensure_args, path::parse_relative_path_with_located},
+};
+use anyhow::{anyhow, Result};
+use evalexpr::{error, Function, Value};
+use regex::Regex;
+use std::fs::read_to_string;
+
+use super::{ArgType, EvalFunction};
+
+pub struct FileContains {
+    //- 检查某个文本文件的内容是否匹配正则表达式，文件不存在时返回 false
+    //@ 需要输入合法的文件路径和正则表达式
+    //# `if = 'FileContains("./config.ini", "^portable=1$")'`
+}
+
+impl EvalFunction for FileContains {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String, ArgType::String]
+    }
+    fn get_closure(located: String) -> Function {
+        let signature = Self::get_signature();
+        Function::new(move |val| {
+            let args = ensure_args(val, &signature)?;
+            let (path, pattern) = (args[0].as_string()?, args[1].as_string()?);
+            let regex = Regex::new(&pattern).map_err(|e| {
+                error::EvalexprError::CustomMessage(format!("Invalid regex '{pattern}' : {e}"))
+            })?;
+            let p = parse_relative_path_with_located(&path, &located);
+            if !p.is_file() {
+                return Ok(Value::Boolean(false));
+            }
+            let text = read_to_string(&p).map_err(|e| {
+                error::EvalexprError::CustomMessage(format!("Failed to read '{path}' : {e}"))
+            })?;
+
+            Ok(Value::Boolean(
+                text.lines().any(|line| regex.is_match(line)),
+            ))
+        })
+    }
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
+            key: PermissionKey::fs_read,
+            level: judge_perm_level(&arg)?,
+            targets: vec![arg],
+        }))
+    }
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        values_validator_path(&args[0].as_string()?)?;
+        let pattern = args[1].as_string()?;
+        Regex::new(&pattern).map_err(|e| {
+            anyhow!("Error:Second argument of 'FileContains' should be a valid regex, got '{pattern}' : {e}")
+        })?;
+        Ok(())
+    }
+}
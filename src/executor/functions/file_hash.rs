@@ -0,0 +1,55 @@
+use crate::{
+    executor::{judge_perm_level, values_validator_path},
+    p2s,
+    signature::blake3::compute_hash_blake3,
+    types::permissions::{Permission, PermissionKey},
+    utils::{conditions::ensure_args, path::parse_relative_path_with_located},
+};
+use anyhow::Result;
+use evalexpr::{error, Function, Value};
+
+use super::{ArgType, EvalFunction};
+
+pub struct FileHash {
+    //- 计算某个文件的 BLAKE3 哈希值，文件不存在时返回空字符串
+    //@ 需要输入合法的文件路径
+    //# `if = 'FileHash("./bin/core.dll")=="2646a295ef814f070b188278e11ee321d9c6fa18fb9e5bf4b11fcf05b6ef4ff6"'`
+}
+
+impl EvalFunction for FileHash {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String]
+    }
+    fn get_closure(located: String) -> Function {
+        let signature = Self::get_signature();
+        Function::new(move |val| {
+            let args = ensure_args(val, &signature)?;
+            let arg = args[0].as_string()?;
+            let p = parse_relative_path_with_located(&arg, &located);
+            if !p.is_file() {
+                return Ok(Value::String(String::new()));
+            }
+            let hash = compute_hash_blake3(&p2s!(p)).map_err(|e| {
+                error::EvalexprError::CustomMessage(format!(
+                    "Failed to compute hash of '{arg}' : {e}"
+                ))
+            })?;
+
+            Ok(Value::String(hash))
+        })
+    }
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
+            key: PermissionKey::fs_read,
+            level: judge_perm_level(&arg)?,
+            targets: vec![arg],
+        }))
+    }
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        values_validator_path(&args[0].as_string()?)
+    }
+    fn get_placeholder() -> Value {
+        Value::String(String::new())
+    }
+}
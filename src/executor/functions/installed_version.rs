@@ -0,0 +1,62 @@
+use crate::{
+    entrances::info_local,
+    types::permissions::{Permission, PermissionKey, PermissionLevel},
+    utils::{conditions::ensure_args, path::find_scope_with_name},
+};
+use anyhow::{anyhow, Result};
+use evalexpr::{error, Function, Value};
+use regex::Regex;
+
+use super::{ArgType, EvalFunction};
+
+lazy_static! {
+    static ref RESOURCE_REGEX: Regex = Regex::new(r"^[^/]+/[^/]+$").unwrap();
+}
+
+pub struct InstalledVersion {
+    //- 读取某个已被 ept 安装的包的版本号，未安装时返回空字符串，可配合 `VersionCompare` 使用
+    //@ 需要匹配模式 'SCOPE/NAME'
+    //# `if = 'VersionCompare(InstalledVersion("Microsoft/VSCode"), "1.75.0.0")>=0'`
+}
+
+impl EvalFunction for InstalledVersion {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String]
+    }
+    fn get_closure(_: String) -> Function {
+        let signature = Self::get_signature();
+        Function::new(move |val| {
+            let args = ensure_args(val, &signature)?;
+            let arg = args[0].as_string()?;
+            let sp: Vec<&str> = arg.split('/').collect();
+            if sp.len() != 2 {
+                return Err(error::EvalexprError::CustomMessage(format!(
+                    "Invalid argument '{arg}' : expect 'SCOPE/NAME', e.g. 'Microsoft/VSCode'"
+                )));
+            }
+            let version = find_scope_with_name(&sp[1].to_string(), Some(sp[0].to_string()))
+                .and_then(|(scope, name)| info_local(&scope, &name))
+                .map(|(global, _)| global.package.version)
+                .unwrap_or_default();
+
+            Ok(Value::String(version))
+        })
+    }
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
+            key: PermissionKey::nep_installed,
+            level: PermissionLevel::Normal,
+            targets: vec![arg],
+        }))
+    }
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        if !RESOURCE_REGEX.is_match(&args[0].as_string()?) {
+            return Err(anyhow!("Error:Argument of 'InstalledVersion' should match pattern 'SCOPE/NAME' (e.g. Microsoft/VSCode)"));
+        }
+        Ok(())
+    }
+    fn get_placeholder() -> Value {
+        Value::String(String::new())
+    }
+}
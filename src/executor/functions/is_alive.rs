@@ -1,11 +1,11 @@
 use crate::{
     types::permissions::{Permission, PermissionKey, PermissionLevel},
-    utils::{conditions::ensure_arg, process::is_alive_with_name},
+    utils::{conditions::ensure_args, process::is_alive_with_name},
 };
 use anyhow::{anyhow, Result};
 use evalexpr::{Function, Value};
 
-use super::EvalFunction;
+use super::{ArgType, EvalFunction};
 
 pub struct IsAlive {
     //- 检查某个进程是否正在运行
@@ -14,20 +14,27 @@ pub struct IsAlive {
 }
 
 impl EvalFunction for IsAlive {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String]
+    }
     fn get_closure(_: String) -> Function {
+        let signature = Self::get_signature();
         Function::new(move |val| {
-            let arg = ensure_arg(val)?;
+            let args = ensure_args(val, &signature)?;
+            let arg = args[0].as_string()?;
             Ok(Value::Boolean(is_alive_with_name(&arg)))
         })
     }
-    fn get_permission(arg: String) -> Result<Permission> {
-        Ok(Permission {
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
             key: PermissionKey::process_query,
             level: PermissionLevel::Normal,
             targets: vec![arg],
-        })
+        }))
     }
-    fn verify_arg(arg: String) -> Result<()> {
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        let arg = args[0].as_string()?;
         if !arg.to_ascii_lowercase().ends_with(".exe") {
             return Err(anyhow!(
                 "Error:Argument of 'IsAlive' should ends with '.exe', got '{arg}'"
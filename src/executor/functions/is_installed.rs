@@ -1,13 +1,13 @@
 use crate::{
     entrances::info,
     types::permissions::{Permission, PermissionKey, PermissionLevel},
-    utils::conditions::ensure_arg,
+    utils::conditions::ensure_args,
 };
 use anyhow::{anyhow, Result};
 use evalexpr::{error, Function, Value};
 use regex::Regex;
 
-use super::EvalFunction;
+use super::{ArgType, EvalFunction};
 
 lazy_static! {
     static ref RESOURCE_REGEX: Regex = Regex::new(r"^[^/]+/[^/]+$").unwrap();
@@ -20,9 +20,14 @@ pub struct IsInstalled {
 }
 
 impl EvalFunction for IsInstalled {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String]
+    }
     fn get_closure(_: String) -> Function {
+        let signature = Self::get_signature();
         Function::new(move |val| {
-            let arg = ensure_arg(val)?;
+            let args = ensure_args(val, &signature)?;
+            let arg = args[0].as_string()?;
             let sp: Vec<&str> = arg.split('/').collect();
             if sp.len() != 2 {
                 return Err(error::EvalexprError::CustomMessage(format!(
@@ -34,14 +39,16 @@ impl EvalFunction for IsInstalled {
             Ok(Value::Boolean(info.is_ok()))
         })
     }
-    fn get_permission(arg: String) -> Result<Permission> {
-        Ok(Permission {
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>> {
+        let arg = args[0].as_string()?;
+        Ok(Some(Permission {
             key: PermissionKey::nep_installed,
             level: PermissionLevel::Normal,
             targets: vec![arg],
-        })
+        }))
     }
-    fn verify_arg(arg: String) -> Result<()> {
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        let arg = args[0].as_string()?;
         if !RESOURCE_REGEX.is_match(&arg) {
             return Err(anyhow!("Error:Argument of 'IsAlive' should match pattern 'SCOPE/NAME' (e.g. Microsoft/VSCode)"));
         }
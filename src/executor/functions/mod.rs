@@ -1,12 +1,22 @@
+mod env;
 mod exist;
+mod file_contains;
+mod file_hash;
+mod installed_version;
 mod is_alive;
 mod is_directory;
 mod is_installed;
+mod version_compare;
 
-use self::{exist::Exist, is_alive::IsAlive, is_directory::IsDirectory, is_installed::IsInstalled};
+use self::{
+    env::Env, exist::Exist, file_contains::FileContains, file_hash::FileHash,
+    installed_version::InstalledVersion, is_alive::IsAlive, is_directory::IsDirectory,
+    is_installed::IsInstalled, version_compare::VersionCompare,
+};
 use crate::types::permissions::Permission;
 use anyhow::{anyhow, Result};
 use evalexpr::*;
+use std::fmt::{Display, Formatter};
 
 macro_rules! def_eval_functions {
     ($($x:ident),*) => {
@@ -23,26 +33,126 @@ macro_rules! def_eval_functions {
             vec![$( stringify!($x) ),*]
         }
 
-        pub fn get_eval_function_permission(name:String,arg:String)->Result<Permission>{
+        pub fn get_eval_function_signature(name:&str)->Result<Vec<ArgType>> {
+            match name {
+                $( stringify!($x) => Ok($x::get_signature()) ),* ,
+                _=>Err(anyhow!("Error:Unknown eval function name '{name}'"))
+            }
+        }
+
+        pub fn get_eval_function_permission(name:String,args:Vec<Value>)->Result<Option<Permission>>{
+            check_eval_function_args(&name,&args)?;
             match name.as_str() {
-                $( stringify!($x) => $x::get_permission(arg) ),* ,
+                $( stringify!($x) => $x::get_permission(args) ),* ,
                 _=>Err(anyhow!("Error:Unknown eval function name '{name}'"))
             }
         }
 
-        pub fn verify_eval_function_arg(name:String,arg:String)->Result<()> {
+        pub fn verify_eval_function_arg(name:String,args:Vec<Value>)->Result<()> {
+            check_eval_function_args(&name,&args)?;
             match name.as_str() {
-                $( stringify!($x) => $x::verify_arg(arg) ),* ,
+                $( stringify!($x) => $x::verify_arg(args) ),* ,
                 _=>Err(anyhow!("Error:Unknown eval function name '{name}'"))
             }
         }
+
+        pub fn get_eval_function_placeholder(name:&str)->Value {
+            match name {
+                $( stringify!($x) => $x::get_placeholder() ),* ,
+                _=>Value::Boolean(true)
+            }
+        }
     };
 }
 
+// 函数入参的类型
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArgType {
+    String,
+    Int,
+    Boolean,
+}
+
+impl ArgType {
+    // 不支持作为入参的类型返回 None
+    fn of(val: &Value) -> Option<Self> {
+        match val {
+            Value::String(_) => Some(ArgType::String),
+            Value::Int(_) => Some(ArgType::Int),
+            Value::Boolean(_) => Some(ArgType::Boolean),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ArgType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ArgType::String => "String",
+            ArgType::Int => "Int",
+            ArgType::Boolean => "Boolean",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// 检查入参个数和类型是否匹配签名，不匹配时返回原因
+pub fn match_signature(signature: &[ArgType], args: &[Value]) -> Option<String> {
+    if args.len() != signature.len() {
+        let expected: Vec<String> = signature.iter().map(|t| t.to_string()).collect();
+        return Some(format!(
+            "expect {count} argument(s) ({expected}), got {len}",
+            count = signature.len(),
+            expected = expected.join(", "),
+            len = args.len()
+        ));
+    }
+    for (index, (arg_type, arg)) in signature.iter().zip(args).enumerate() {
+        let got = ArgType::of(arg);
+        if got != Some(*arg_type) {
+            let got_type = got
+                .map(|t| t.to_string())
+                .unwrap_or("unsupported type".to_string());
+            return Some(format!(
+                "argument {pos} should be {arg_type}, got {got_type} '{arg}'",
+                pos = index + 1
+            ));
+        }
+    }
+    None
+}
+
+fn check_eval_function_args(name: &str, args: &[Value]) -> Result<()> {
+    let signature = get_eval_function_signature(name)?;
+    if let Some(reason) = match_signature(&signature, args) {
+        return Err(anyhow!(
+            "Error:Invalid arguments for function '{name}' : {reason}"
+        ));
+    }
+    Ok(())
+}
+
 trait EvalFunction {
+    // 入参签名，多个入参时以元组形式传入
+    fn get_signature() -> Vec<ArgType>;
     fn get_closure(located: String) -> Function;
-    fn get_permission(arg: String) -> Result<Permission>;
-    fn verify_arg(arg: String) -> Result<()>;
+    // 不需要权限的函数返回 None
+    fn get_permission(args: Vec<Value>) -> Result<Option<Permission>>;
+    fn verify_arg(args: Vec<Value>) -> Result<()>;
+    // 静态分析时代替真实返回值，需要与真实返回值类型一致
+    fn get_placeholder() -> Value {
+        Value::Boolean(true)
+    }
 }
 
-def_eval_functions!(Exist, IsDirectory, IsAlive, IsInstalled);
+def_eval_functions!(
+    Exist,
+    IsDirectory,
+    IsAlive,
+    IsInstalled,
+    FileHash,
+    InstalledVersion,
+    Env,
+    FileContains,
+    VersionCompare
+);
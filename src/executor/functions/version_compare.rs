@@ -0,0 +1,68 @@
+use crate::{
+    types::{extended_semver::ExSemVer, permissions::Permission},
+    utils::conditions::ensure_args,
+};
+use anyhow::{anyhow, Result};
+use evalexpr::{error, Function, Value};
+use std::cmp::Ordering;
+
+use super::{ArgType, EvalFunction};
+
+// 空字符串视为最低的版本号
+fn parse_version(text: &String) -> Result<Option<ExSemVer>> {
+    if text.is_empty() {
+        Ok(None)
+    } else {
+        ExSemVer::parse(text).map(Some)
+    }
+}
+
+pub struct VersionCompare {
+    //- 比较两个版本号，前者较小、相等、较大时分别返回 -1、0、1；空字符串视为最低的版本号
+    //@ 需要输入两个合法的版本号或空字符串
+    //# `if = 'VersionCompare("${PackageVersion}", "1.75.0.0")<0'`
+}
+
+impl EvalFunction for VersionCompare {
+    fn get_signature() -> Vec<ArgType> {
+        vec![ArgType::String, ArgType::String]
+    }
+    fn get_closure(_: String) -> Function {
+        let signature = Self::get_signature();
+        Function::new(move |val| {
+            let args = ensure_args(val, &signature)?;
+            let (a, b) = (args[0].as_string()?, args[1].as_string()?);
+            let wrapper = |e: anyhow::Error| {
+                error::EvalexprError::CustomMessage(format!("Invalid version : {e}"))
+            };
+            let ordering = parse_version(&a)
+                .map_err(wrapper)?
+                .cmp(&parse_version(&b).map_err(wrapper)?);
+
+            Ok(Value::Int(match ordering {
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+                Ordering::Greater => 1,
+            }))
+        })
+    }
+    fn get_permission(_: Vec<Value>) -> Result<Option<Permission>> {
+        Ok(None)
+    }
+    fn verify_arg(args: Vec<Value>) -> Result<()> {
+        // 内置变量在运行时才能确定，跳过校验
+        for arg in &args {
+            let arg = arg.as_string()?;
+            if arg.contains("${") {
+                continue;
+            }
+            parse_version(&arg).map_err(|e| {
+                anyhow!("Error:Arguments of 'VersionCompare' should be valid versions, got '{arg}' : {e}")
+            })?;
+        }
+        Ok(())
+    }
+    fn get_placeholder() -> Value {
+        Value::Int(0)
+    }
+}
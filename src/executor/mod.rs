@@ -8,15 +8,20 @@ use crate::{
     log, p2s,
     types::{
         package::GlobalPackage,
+        permissions::Permission,
         workflow::{WorkflowContext, WorkflowNode},
     },
     utils::{arch::is_current_arch_match, get_bare_apps, get_system_drive},
 };
 
 pub use self::functions::{
-    get_eval_function_names, get_eval_function_permission, verify_eval_function_arg,
+    get_eval_function_names, get_eval_function_permission, get_eval_function_placeholder,
+    match_signature, verify_eval_function_arg, ArgType,
+};
+pub use self::values::{
+    collect_unknown_values, correct_value_case, judge_perm_level, values_replacer,
+    values_validator_path,
 };
-pub use self::values::{judge_perm_level, values_replacer, values_validator_path};
 use self::{
     functions::set_context_with_function,
     values::{set_context_with_constant_values, set_context_with_mutable_values},
@@ -59,10 +64,12 @@ pub fn condition_eval(
 }
 
 // 执行工作流，返回最后一个步骤的退出码
+// permissions 为包声明并经用户同意的权限，步骤只能访问其中的文件系统目标
 pub fn workflow_executor(
     flow: Vec<WorkflowNode>,
     located: String,
     pkg: GlobalPackage,
+    permissions: Vec<Permission>,
 ) -> Result<i32> {
     let strict_mode = pkg.package.strict.unwrap_or(true);
 
@@ -77,6 +84,9 @@ pub fn workflow_executor(
     let package_version = pkg.package.version.clone();
     let mut cx = WorkflowContext::new(&located, pkg);
 
+    // 启用运行时沙箱
+    cx.enable_sandbox(permissions);
+
     // 遍历流节点
     for flow_node in flow {
         let name = flow_node.header.name.unwrap();
@@ -221,6 +231,15 @@ fn test_condition_eval() {
     )
     .unwrap();
     assert!(r7);
+
+    let r8 = condition_eval(
+        &String::from("VersionCompare(\"1.0.0.0\", \"1.0.0.1\")<0 && VersionCompare(\"${PackageVersion}\", \"\")>0 && FileContains(\"src/main.rs\", \"^mod executor;\") && FileHash(\"./src/unknown.rs\")==\"\" && Env(\"EPT_UNKNOWN_VAR\")==\"\""),
+        0,
+        &String::from("./"),
+        &"1.0.0.0".to_string(),
+    )
+    .unwrap();
+    assert!(r8);
 }
 
 #[test]
@@ -270,7 +289,7 @@ fn test_workflow_executor() {
             }),
         },
     ];
-    assert!(workflow_executor(wf1, cx.located, cx.pkg).is_err());
+    assert!(workflow_executor(wf1, cx.located, cx.pkg, Vec::new()).is_err());
 }
 
 #[test]
@@ -309,7 +328,7 @@ fn test_workflow_executor_interpreter() {
     ];
     let mut cx = WorkflowContext::_demo();
     cx.pkg.package.strict = Some(false);
-    let code = workflow_executor(flow, cx.located, cx.pkg).unwrap();
+    let code = workflow_executor(flow, cx.located, cx.pkg, Vec::new()).unwrap();
     assert_eq!(code, 0);
 }
 
@@ -337,11 +356,11 @@ fn test_workflow_with_strict_mode() {
 
     // 默认情况下是严格模式
     let cx = WorkflowContext::_demo();
-    assert!(workflow_executor(flow.clone(), cx.located, cx.pkg).is_err());
+    assert!(workflow_executor(flow.clone(), cx.located, cx.pkg, Vec::new()).is_err());
 
     // 显式申明禁用严格模式
     let mut cx = WorkflowContext::_demo();
     cx.pkg.package.strict = Some(false);
-    let code = workflow_executor(flow.clone(), cx.located, cx.pkg).unwrap();
+    let code = workflow_executor(flow.clone(), cx.located, cx.pkg, Vec::new()).unwrap();
     assert_eq!(code, 3);
 }
@@ -82,6 +82,22 @@ pub fn collect_values(raw: &String) -> Result<Vec<String>> {
     Ok(collection)
 }
 
+// 收集未知的内置变量，不打印警告
+pub fn collect_unknown_values(raw: &str) -> Vec<String> {
+    let valid_values: HashSet<String> = HashSet::from_iter(get_arr(true));
+    RE.captures_iter(raw)
+        .map(|cap| cap.get(0).unwrap().as_str().to_string())
+        .filter(|str| !valid_values.contains(str))
+        .collect()
+}
+
+// 对仅有大小写拼写错误的内置变量给出正确写法
+pub fn correct_value_case(unknown: &str) -> Option<String> {
+    get_arr(true)
+        .into_iter()
+        .find(|value| value.eq_ignore_ascii_case(unknown))
+}
+
 /// 适用于路径入参的内置变量使用规范校验器
 pub fn values_validator_path(raw: &String) -> Result<()> {
     // "${DefaultLocation}" 不是合法的路径内置变量，应该使用相对路径
@@ -206,6 +222,19 @@ define_values! {
     {"${Arch}",get_arch().unwrap().to_string(),PermissionLevel::Normal}
 }
 
+#[test]
+fn test_collect_unknown_values() {
+    assert_eq!(
+        collect_unknown_values("${AppData}/${appdata}/${Foo}"),
+        vec!["${appdata}".to_string(), "${Foo}".to_string()]
+    );
+    assert_eq!(
+        correct_value_case("${appdata}"),
+        Some("${AppData}".to_string())
+    );
+    assert_eq!(correct_value_case("${Foo}"), None);
+}
+
 #[test]
 fn test_collect_values() {
     values_validator_path(&"${AppData}/${ExitCode}.${SystemData}/".to_string()).unwrap();
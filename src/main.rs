@@ -18,14 +18,15 @@ use self::types::cfg::Cfg;
 use self::types::cli::{Action, ActionConfig, Args};
 use crate::entrances::config::{config_get, config_init, config_list, config_set, config_which};
 use crate::entrances::{
-    auto_mirror_update_all, clean, info, install_using_package, list, pack, uninstall, update_all,
+    auto_mirror_update_all, autoremove, clean, doctor, du, history, info_detailed,
+    install_using_package, lint, list_entries, new, pack, pin, sandbox_test,
+    uninstall_using_parsed, unpin, update_all,
 };
-use crate::utils::cfg::get_config;
+use crate::utils::cfg::{get_config, set_profile};
 use crate::utils::flags::{get_flag, set_flag, Flag};
 use crate::utils::launch_clean;
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use colored::Colorize;
 use entrances::meta;
 use std::fs::write;
 use std::process::exit;
@@ -33,18 +34,29 @@ use std::process::exit;
 #[cfg(not(tarpaulin_include))]
 fn router(action: Action, cfg: Cfg) -> Result<String> {
     // 环境变量读取
-    use entrances::{install_using_parsed, update_using_parsed, upgrade};
-    use types::{cli::ActionMirror, extended_semver::ExSemVer};
+    use entrances::{cat, diff, install_using_parsed, ls_package, update_using_parsed, upgrade};
+    use std::io::{stdout, Write};
+    use types::{
+        cli::{ActionMirror, ActionPolicy, NewArgs},
+        inspect::PackageEntry,
+        install_record::InstallReason,
+        lint::{LintFormat, LintSeverity},
+        list::{ListFilter, ListSortBy},
+        pack::PackOptions,
+        scaffold::{ScaffoldDraft, ScaffoldWorkflow},
+    };
     use utils::{
-        fmt_print::{fmt_mirror_line, fmt_package_line},
-        get_path_apps,
+        fmt_print::{
+            fmt_mirror_line, fmt_package_entry_line, fmt_package_line, fmt_size, fmt_usage_line,
+        },
         parse_inputs::{parse_install_inputs, parse_uninstall_inputs, parse_update_inputs},
         term::ask_yn,
     };
 
     use crate::{
         entrances::{
-            mirror_add, mirror_list, mirror_remove, mirror_update, mirror_update_all, search,
+            mirror_add, mirror_list, mirror_remove, mirror_update, mirror_update_all, policy_list,
+            policy_set, policy_unset, search,
         },
         types::matcher::{PackageInputEnum, PackageMatcher},
     };
@@ -52,7 +64,15 @@ fn router(action: Action, cfg: Cfg) -> Result<String> {
 
     // 匹配入口
     match action {
-        Action::Install { packages } => {
+        Action::Install {
+            packages,
+            allow_downgrade,
+            reason,
+        } => {
+            if allow_downgrade {
+                set_flag(Flag::AllowDowngrade, true);
+            }
+            let reason = reason.map(|r| InstallReason::parse(&r)).transpose()?;
             // 解析输入
             let parsed = parse_install_inputs(packages)?;
             // 询问是否执行
@@ -72,7 +92,7 @@ fn router(action: Action, cfg: Cfg) -> Result<String> {
                 return Err(anyhow!("Error:Operation canceled by user"));
             }
             // 执行
-            install_using_parsed(parsed, verify_signature).map(|arr| {
+            install_using_parsed(parsed, verify_signature, reason).map(|arr| {
                 let length = arr.len();
                 if length == 1 {
                     String::new()
@@ -81,7 +101,10 @@ fn router(action: Action, cfg: Cfg) -> Result<String> {
                 }
             })
         }
-        Action::Update { packages } => {
+        Action::Update { packages, force } => {
+            if force {
+                set_flag(Flag::Force, true);
+            }
             if let Some(packages) = packages {
                 // 解析输入
                 let parsed = parse_update_inputs(packages)?;
@@ -142,21 +165,12 @@ fn router(action: Action, cfg: Cfg) -> Result<String> {
             ) {
                 return Err(anyhow!("Error:Operation canceled by user"));
             }
-            let length = parsed.len();
-            for (scope, name, _) in parsed {
-                let tip = uninstall(Some(scope.clone()), &name).map(|(scope, name)| {
-                    format!("Success:Package '{scope}/{name}' uninstalled successfully")
-                }).map_err(|e|{
-                    // 卸载失败时提示用户如何手动解决坏包
-                    let app_path=get_path_apps(&scope, &name, false).unwrap();
-                    anyhow!("Error:Failed to uninstall package '{scope}/{name}' : '{e}', try to manually delete '{}' if this package is broken",p2s!(app_path))
-                })?;
-                log!("{tip}");
-            }
-            Ok(if length == 1 {
-                String::new()
-            } else {
-                format!("Success:{length} packages uninstalled successfully")
+            uninstall_using_parsed(parsed).map(|length| {
+                if length == 1 {
+                    String::new()
+                } else {
+                    format!("Success:{length} packages uninstalled successfully")
+                }
             })
         }
         Action::Search { keyword, regex } => {
@@ -180,42 +194,209 @@ fn router(action: Action, cfg: Cfg) -> Result<String> {
         Action::Info { package_matcher } => {
             auto_mirror_update_all(&cfg)?;
             let parse_res = PackageMatcher::parse(&package_matcher, true, true)?;
-            info(parse_res.scope, &parse_res.name).map(|res| format!("{res:#?}"))
+            info_detailed(parse_res.scope, &parse_res.name).map(|res| format!("{res:#?}"))
         }
-        Action::List => list().map(|list| {
-            if list.is_empty() {
-                return "Info:No installed package".to_string();
-            }
-            let res: String =
+        Action::List {
+            releases,
+            scope,
+            category,
+            upgradable,
+            pinned,
+            broken,
+            from_mirror,
+            sort,
+            long,
+        } => {
+            let filter = ListFilter {
+                scope,
+                category,
+                upgradable,
+                pinned,
+                broken,
+                from_mirror,
+            };
+            let sort = ListSortBy::parse(&sort)?;
+            list_entries(&filter, sort, long).map(|list| {
+                if list.is_empty() {
+                    return "Info:No matched package".to_string();
+                }
                 list.into_iter()
                     .fold(String::from("\nInstalled packages:\n"), |acc, node| {
-                        let local_ver = node.local.unwrap().version;
-                        let update_tip = if let Some(online_diff) = node.online {
-                            let online_ver = online_diff.version;
-                            if ExSemVer::parse(&online_ver).unwrap()
-                                > ExSemVer::parse(&local_ver).unwrap()
-                            {
-                                format!("  ↑ {online_ver}").green().to_string()
-                            } else {
-                                String::new()
-                            }
-                        } else {
-                            String::new()
-                        };
-                        acc + &fmt_package_line(
-                            &node.software.unwrap().scope,
-                            &node.name,
-                            &format!("{local_ver}{update_tip}"),
-                            None,
+                        acc + &node.format(releases, long)
+                    })
+            })
+        }
+        Action::Du => du().map(|usage| {
+            let mut res =
+                usage
+                    .packages
+                    .iter()
+                    .fold(String::from("\nInstalled packages:\n"), |acc, node| {
+                        acc + &fmt_usage_line(
+                            &format!("{}/{}", node.scope, node.name),
+                            node.total(),
+                            &format!(
+                                "app {}, context {}, cache {}",
+                                fmt_size(node.app),
+                                fmt_size(node.context),
+                                fmt_size(node.cache)
+                            ),
                         )
                     });
-            res
+            res += "\nOthers:\n";
+            res += &fmt_usage_line("cache", usage.cache, "cached neps");
+            res += &fmt_usage_line("temp", usage.temp, "temporary files");
+            res += &fmt_usage_line("mirror", usage.mirror, "mirror indexes");
+            res + &format!("\nTotal: {}\n", fmt_size(usage.total()))
         }),
+        Action::Pin { package_matcher } => pin(&package_matcher)
+            .map(|(scope, name, desc)| format!("Success:Package '{scope}/{name}' {desc}")),
+        Action::Unpin { package_matcher } => unpin(&package_matcher)
+            .map(|(scope, name)| format!("Success:Package '{scope}/{name}' unpinned")),
+        Action::Policy { operation } => match operation {
+            ActionPolicy::Set {
+                package_matcher,
+                policy,
+                prerelease,
+            } => policy_set(&package_matcher, &policy, prerelease).map(|(scope, name, desc)| {
+                format!("Success:Package '{scope}/{name}' now follows {desc}")
+            }),
+            ActionPolicy::Unset { package_matcher } => {
+                policy_unset(&package_matcher).map(|(scope, name)| {
+                    format!("Success:Package '{scope}/{name}' now follows global policy")
+                })
+            }
+            ActionPolicy::List => policy_list().map(|(global, list)| {
+                list.into_iter()
+                    .fold(format!("\nGlobal: {global}\n"), |acc, (key, desc)| {
+                        acc + &format!("  {key:<46} {desc}\n")
+                    })
+            }),
+        },
+        Action::New(args) => {
+            let NewArgs {
+                dir,
+                source,
+                name,
+                description,
+                pkg_version,
+                authors,
+                license,
+                scope,
+                upstream,
+                category,
+                language,
+                main_program,
+                with,
+            } = *args;
+            let workflows = with
+                .map(|arr| {
+                    arr.iter()
+                        .map(|raw| ScaffoldWorkflow::parse(raw))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()?;
+            let draft = ScaffoldDraft {
+                source,
+                name,
+                description,
+                version: pkg_version,
+                authors,
+                license,
+                scope,
+                upstream,
+                category,
+                language,
+                main_program,
+                workflows,
+            };
+            new(&dir, draft).map(|global| {
+                format!(
+                    "Success:Package source '{name}' created at '{dir}', run 'ept pack \"{dir}\"' to pack it",
+                    name = global.package.name
+                )
+            })
+        }
+        Action::Lint {
+            source_dir,
+            format,
+            output,
+            fix,
+        } => {
+            let format = LintFormat::parse(&format)?;
+            let report = lint(&source_dir, fix)?;
+            if report.fixed > 0 {
+                log!("Success:Fixed {} issues", report.fixed);
+            }
+            let text = match format {
+                LintFormat::Text => report.to_text(),
+                LintFormat::Json => report.to_json()?,
+                LintFormat::Sarif => report.to_sarif()?,
+            };
+            if let Some(output) = output {
+                write(&output, text)
+                    .map_err(|e| anyhow!("Error:Failed to write report to '{output}' : {e}"))?;
+            } else if !text.is_empty() {
+                println!("{text}");
+            }
+            let errors = report.count(LintSeverity::Error);
+            let warnings = report.count(LintSeverity::Warning);
+            if errors > 0 {
+                Err(anyhow!(
+                    "Error:Found {errors} errors and {warnings} warnings in '{source_dir}'"
+                ))
+            } else if warnings > 0 {
+                Ok(format!(
+                    "Warning:Found {warnings} warnings in '{source_dir}'"
+                ))
+            } else {
+                Ok(format!("Success:No issue found in '{source_dir}'"))
+            }
+        }
         Action::Pack {
             source_dir,
             into_file,
-        } => pack(&source_dir, into_file, verify_signature)
-            .map(|location| format!("Success:Package stored at '{location}'")),
+            reproducible,
+            level,
+            threads,
+            delta_from,
+        } => pack(
+            &source_dir,
+            into_file,
+            verify_signature,
+            &PackOptions {
+                delta_from,
+                ..PackOptions::new(reproducible, level, threads)?
+            },
+        )
+        .map(|location| format!("Success:Package stored at '{location}'")),
+        Action::LsPackage { package } => ls_package(&package, verify_signature).map(|entries| {
+            let files: Vec<&PackageEntry> = entries.iter().filter(|e| !e.is_dir).collect();
+            let total: u64 = files.iter().map(|e| e.size).sum();
+            entries.iter().fold(String::from("\n"), |acc, e| {
+                acc + &fmt_package_entry_line(&e.path, e.size, e.is_dir)
+            }) + &format!("\n{} files, {} in total\n", files.len(), fmt_size(total))
+        }),
+        Action::Cat { package, file } => {
+            let content = cat(&package, &file, verify_signature)?;
+            // 直接写入标准输出，避免内容被当作日志格式化
+            stdout()
+                .write_all(&content)
+                .map_err(|e| anyhow!("Error:Failed to write to stdout : {e}"))?;
+            Ok(String::new())
+        }
+        Action::Diff { old, new } => diff(&old, &new, verify_signature).map(|res| {
+            if res.is_empty() {
+                "Info:No difference found".to_string()
+            } else {
+                res.to_string()
+            }
+        }),
+        Action::Test {
+            package,
+            allow_execute,
+        } => sandbox_test(&package, allow_execute)
+            .map(|_| format!("Success:Sandbox test of '{package}' passed")),
         Action::Meta { package, save_at } => {
             // 调用 meta
             let package_input_enum = PackageInputEnum::parse(package, true, true)?;
@@ -245,11 +426,45 @@ fn router(action: Action, cfg: Cfg) -> Result<String> {
             }
         }),
 
+        Action::Autoremove => autoremove().map(|count| {
+            if count == 0 {
+                "Info:No package to remove, only packages installed with '--reason batch' or '--reason import' are removed".to_string()
+            } else {
+                format!("Success:{count} packages removed")
+            }
+        }),
+
+        Action::History { package, since } => history(package, since).map(|entries| {
+            if entries.is_empty() {
+                "Info:No history found".to_string()
+            } else {
+                let len = entries.len();
+                entries
+                    .into_iter()
+                    .fold(format!("\nFound {len} operations:\n"), |acc, entry| {
+                        acc + &entry.to_string()
+                    })
+            }
+        }),
+
+        Action::Doctor => doctor(verify_signature).map(|(found, fixed)| {
+            if found == 0 {
+                "Success:No issue found".to_string()
+            } else if found == fixed {
+                format!("Success:{found} issues found and fixed")
+            } else {
+                format!(
+                    "Warning:{found} issues found, {fixed} fixed and {left} left",
+                    left = found - fixed
+                )
+            }
+        }),
+
         Action::Config { operation } => match operation {
             ActionConfig::Set { table, key, value } => config_set(&table, &key, &value)
                 .map(|_| format!("Success:Config value of '{key}' set to '{value}'")),
             ActionConfig::Get { table, key } => config_get(&table, &key),
-            ActionConfig::List => config_list(),
+            ActionConfig::List { show_origin } => config_list(show_origin),
             ActionConfig::Init => config_init()
                 .map(|location| format!("Success:Initial config stored at '{location}'")),
             ActionConfig::Which => config_which(),
@@ -304,6 +519,7 @@ fn main() {
     launch_clean().unwrap();
 
     // 启用虚拟终端
+    #[cfg(windows)]
     colored::control::set_virtual_terminal(true).unwrap();
 
     // 配置环境变量
@@ -324,6 +540,23 @@ fn main() {
         set_flag(Flag::Confirm, true);
     }
 
+    // 选择配置档案，需要在读取配置前完成
+    if let Some(profile) = &args.profile {
+        let exists = match Cfg::get_profile_path(profile) {
+            Ok(p) => p.exists(),
+            Err(e) => {
+                log!("{e}");
+                exit(1);
+            }
+        };
+        if !exists && !matches!(&args.action, Action::Config { operation: _ }) {
+            log!("Error:Profile '{profile}' doesn't exist, create it with 'ept --profile {profile} config init'");
+            exit(1);
+        }
+        log!("Info:Using config profile '{profile}'");
+        set_profile(Some(profile.to_owned()));
+    }
+
     // 获取配置
     let cfg = get_config();
 
@@ -3,6 +3,6 @@ mod package;
 mod signature;
 mod workflow;
 pub use self::author::parse_author;
-pub use self::package::parse_package;
-pub use self::signature::{fast_parse_signature, parse_signature};
-pub use self::workflow::parse_workflow;
+pub use self::package::{collect_author_problems, is_nep_version_compatible, parse_package};
+pub use self::signature::fast_parse_signature;
+pub use self::workflow::{parse_workflow, parse_workflow_with_source};
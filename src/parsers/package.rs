@@ -1,6 +1,7 @@
 use crate::executor::values_replacer;
 use crate::types::interpretable::Interpretable;
 use crate::types::mixed_fs::MixedFS;
+use crate::types::source_map::SourceMap;
 use crate::types::verifiable::Verifiable;
 use crate::types::{extended_semver::ExSemVer, package::GlobalPackage};
 use crate::utils::reg_entry::get_reg_entry;
@@ -99,31 +100,37 @@ pub fn parse_package(
 
     let mut text = String::new();
     File::open(p)?.read_to_string(&mut text)?;
-    let dirty_toml: toml::Value =
-        toml::from_str(&text).map_err(|res| anyhow!("Error:Invalid toml file '{p}' : {res}"))?;
+    let source = SourceMap::new(p, &text);
+    let dirty_toml: toml::Value = toml::from_str(&text).map_err(|res| {
+        anyhow!(source.annotate_toml_error(&format!("Error:Invalid toml file '{p}'"), &res))
+    })?;
 
     // 检查 nep 版本号是否符合
     let ver_opt = dirty_toml.get("nep");
     if let Some(val) = ver_opt {
         let pkg_ver = val.as_str().unwrap_or("0.0").to_string();
-        is_nep_version_compatible(&pkg_ver, env!("CARGO_PKG_VERSION"))?;
+        is_nep_version_compatible(&pkg_ver, env!("CARGO_PKG_VERSION"))
+            .map_err(|e| anyhow!(source.annotate(e.to_string(), "nep")))?;
     } else {
         return Err(anyhow!("Error:Field 'nep' undefined in '{p}'"));
     }
 
     // 序列化
-    let pkg: GlobalPackage = dirty_toml
-        .try_into()
-        .map_err(|res| anyhow!("Error:Can't validate package.toml at '{p}' : {res}"))?;
+    let pkg: GlobalPackage = dirty_toml.try_into().map_err(|res| {
+        anyhow!(source.annotate_value_error(
+            format!("Error:Can't validate package.toml at '{p}'"),
+            "",
+            &res
+        ))
+    })?;
     let software = pkg.software.clone().unwrap();
 
     // 逐一解析作者
-    for (i, raw) in pkg.package.authors.clone().into_iter().enumerate() {
-        let author = parse_author(&raw)?;
-        // 第一作者必须提供邮箱
-        if i == 0 && author.email.is_none() {
-            return Err(anyhow!("Error:Can't validate package.toml : first author '{name}' in field 'package.authors' should have email (e.g. \"Cno <cno@edgeless.top>\")",name=author.name));
-        }
+    if let Some((key_path, problem)) = collect_author_problems(&pkg.package.authors)
+        .into_iter()
+        .next()
+    {
+        return Err(anyhow!(source.annotate(problem, &key_path)));
     }
 
     // 支持智能识别 located 指的 "根目录" 还是 "根目录/名称"
@@ -132,7 +139,8 @@ pub fn parse_package(
     } else {
         located
     };
-    pkg.verify_self(&MixedFS::new(mixed_located))?;
+    pkg.verify_self(&MixedFS::new(mixed_located))
+        .map_err(|e| anyhow!(source.annotate(e.to_string(), "package")))?;
 
     // 解释
     let package_version = pkg.package.version.clone();
@@ -166,7 +174,25 @@ pub fn parse_package(
     Ok(pkg)
 }
 
-fn is_nep_version_compatible(pkg_str: &String, ept_str: &str) -> Result<()> {
+// 收集作者字段的全部问题，返回（键路径，错误信息）
+pub fn collect_author_problems(authors: &[String]) -> Vec<(String, String)> {
+    let mut problems = Vec::new();
+    for (i, raw) in authors.iter().enumerate() {
+        let key_path = format!("package.authors[{i}]");
+        match parse_author(raw) {
+            Ok(author) => {
+                // 第一作者必须提供邮箱
+                if i == 0 && author.email.is_none() {
+                    problems.push((key_path, format!("Error:Can't validate package.toml : first author '{name}' in field 'package.authors' should have email (e.g. \"Cno <cno@edgeless.top>\")", name = author.name)));
+                }
+            }
+            Err(e) => problems.push((key_path, e.to_string())),
+        }
+    }
+    problems
+}
+
+pub fn is_nep_version_compatible(pkg_str: &String, ept_str: &str) -> Result<()> {
     // 检查 nep 版本号是一位数字
     if pkg_str.len() != 1 || pkg_str.parse::<u32>().is_err() {
         return Err(anyhow!("Error:Invalid nep version '{pkg_str}'"));
@@ -253,3 +279,25 @@ fn test_parse_package() {
     };
     assert_eq!(pkg, answer)
 }
+
+#[test]
+fn test_parse_package_span() {
+    use std::fs::{create_dir_all, read_to_string, write};
+    create_dir_all("test").unwrap();
+    let p = "test/span_package.toml".to_string();
+    let located = &"examples/VSCode".to_string();
+    let origin = read_to_string("examples/VSCode/package.toml").unwrap();
+
+    // 字段类型错误
+    write(&p, origin.replace("license = \"MIT\"", "license = 1")).unwrap();
+    let err = parse_package(&p, located, false).unwrap_err().to_string();
+    assert!(err.contains("in field 'package.license'"));
+    assert!(err.contains("license = 1"));
+
+    // 第一作者缺少邮箱
+    write(&p, origin.replace("Cno <dsyourshy@qq.com>", "Cno")).unwrap();
+    let err = parse_package(&p, located, false).unwrap_err().to_string();
+    assert!(err.contains("--> test/span_package.toml:"));
+    assert!(err.contains("^^^^^"));
+    std::fs::remove_file(&p).unwrap();
+}
@@ -4,10 +4,17 @@ use std::path::Path;
 use std::{fs::File, io::Read};
 use toml::Value;
 
+use crate::types::source_map::SourceMap;
 use crate::types::steps::Step;
 use crate::types::workflow::{WorkflowHeader, WorkflowNode};
 
 pub fn parse_workflow(p: &String) -> Result<Vec<WorkflowNode>> {
+    let (nodes, _) = parse_workflow_with_source(p)?;
+    Ok(nodes.into_iter().map(|(_, node)| node).collect())
+}
+
+// 解析工作流并保留步骤键与源码位置，用于在校验时指出出错的位置
+pub fn parse_workflow_with_source(p: &String) -> Result<(Vec<(String, WorkflowNode)>, SourceMap)> {
     let workflow_path = Path::new(p);
     if !workflow_path.exists() {
         return Err(anyhow!("Error:Fatal:Can't find workflow path : {p}"));
@@ -16,10 +23,13 @@ pub fn parse_workflow(p: &String) -> Result<Vec<WorkflowNode>> {
     // 读取文件
     let mut text = String::new();
     File::open(p)?.read_to_string(&mut text)?;
+    let source = SourceMap::new(p, &text);
 
     // 反序列化工作流并解析为 Table
-    let plain_flow: Value = toml::from_str(&text)
-        .map_err(|err| anyhow!("Error:Can't parse '{p}' as legal toml file : {err}"))?;
+    let plain_flow: Value = toml::from_str(&text).map_err(|err| {
+        anyhow!(source
+            .annotate_toml_error(&format!("Error:Can't parse '{p}' as legal toml file"), &err))
+    })?;
     let table = plain_flow
         .as_table()
         .ok_or(anyhow!("Error:Failed to convert workflow as valid table"))?
@@ -29,10 +39,13 @@ pub fn parse_workflow(p: &String) -> Result<Vec<WorkflowNode>> {
     let mut res = Vec::new();
     for (key, val) in table {
         // 解析步骤头
-        let mut header: WorkflowHeader = val
-            .clone()
-            .try_into()
-            .map_err(|e| anyhow!("Error:Illegal workflow node at key '{key}' : {e}"))?;
+        let mut header: WorkflowHeader = val.clone().try_into().map_err(|e| {
+            anyhow!(source.annotate_value_error(
+                format!("Error:Illegal workflow node at key '{key}'"),
+                &key,
+                &e
+            ))
+        })?;
 
         // 如果步骤头没有提供 name 则使用 key 的 sentence case
         if header.name.is_none() {
@@ -40,12 +53,12 @@ pub fn parse_workflow(p: &String) -> Result<Vec<WorkflowNode>> {
         }
 
         // 解析步骤体
-        let body = Step::try_from_kv(key, val)?;
+        let body = Step::try_from_kv(key.clone(), val, &source)?;
 
-        res.push(WorkflowNode { header, body })
+        res.push((key, WorkflowNode { header, body }))
     }
 
-    Ok(res)
+    Ok((res, source))
 }
 
 #[test]
@@ -94,3 +107,39 @@ fn test_parse_workflow() {
     ];
     assert_eq!(res, answer);
 }
+
+#[test]
+fn test_parse_workflow_with_source() {
+    use std::fs::{create_dir_all, write};
+    create_dir_all("test").unwrap();
+    let p = "test/span_setup.toml".to_string();
+
+    write(
+        &p,
+        "[create_shortcut]\nstep = \"Link\"\nsource_file = \"Code.exe\"\n\n[wait]\nstep = \"Wait\"\ntimeout = \"30s\"\n",
+    )
+    .unwrap();
+    let err = parse_workflow(&p).unwrap_err().to_string();
+    assert!(err.contains("in field 'timeout'"));
+    assert!(err.contains("--> test/span_setup.toml:7:11"));
+    assert!(err.contains("7 | timeout = \"30s\""));
+
+    write(&p, "[wait]\nstep = \"Wait\"\n").unwrap();
+    let err = parse_workflow(&p).unwrap_err().to_string();
+    assert!(err.contains("missing field `timeout`"));
+    assert!(err.contains("--> test/span_setup.toml:1:2"));
+
+    write(&p, "[wait]\nstep = \n").unwrap();
+    let err = parse_workflow(&p).unwrap_err().to_string();
+    assert!(err.contains("--> test/span_setup.toml:2:8"));
+
+    write(
+        &p,
+        "[create_shortcut]\nstep = \"Link\"\nsource_file = \"Code.exe\"\n",
+    )
+    .unwrap();
+    let (nodes, source) = parse_workflow_with_source(&p).unwrap();
+    assert_eq!(nodes[0].0, "create_shortcut");
+    assert!(source.span("create_shortcut.source_file").is_some());
+    std::fs::remove_file(&p).unwrap();
+}
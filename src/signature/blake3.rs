@@ -1,7 +1,7 @@
 use anyhow::Result;
 use blake3::{hash, Hasher};
 use std::fs::File;
-use std::io;
+use std::io::{self, Read};
 
 use crate::log;
 
@@ -30,6 +30,37 @@ pub fn fast_compute_hash_blake3(raw: &[u8]) -> Result<String> {
     Ok(hash)
 }
 
+// 在读取数据的同时计算 blake3 摘要，用于流式解包时校验签名
+pub struct Blake3Reader<R: Read> {
+    reader: R,
+    hasher: Hasher,
+}
+
+impl<R: Read> Blake3Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            hasher: Hasher::new(),
+        }
+    }
+
+    // 读取剩余的数据后返回完整的摘要
+    pub fn finalize(mut self) -> Result<String> {
+        io::copy(&mut self, &mut io::sink())?;
+        let hash = self.hasher.finalize().to_hex().to_string();
+        log!("Debug:Calculated blake3 hash from stream : '{hash}'");
+        Ok(hash)
+    }
+}
+
+impl<R: Read> Read for Blake3Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
 fn try_into_memmap_file(file: &File) -> Result<Option<io::Cursor<memmap2::Mmap>>> {
     let metadata = file.metadata()?;
     let file_size = metadata.len();
@@ -77,3 +108,14 @@ fn test_compute_hash_blake3() {
         res
     );
 }
+
+#[test]
+fn test_blake3_reader() {
+    let mut reader = Blake3Reader::new(File::open("./examples/VSCode/VSCode/favicon.ico").unwrap());
+    let mut head = [0; 1024];
+    reader.read_exact(&mut head).unwrap();
+    assert_eq!(
+        reader.finalize().unwrap(),
+        compute_hash_blake3(&"./examples/VSCode/VSCode/favicon.ico".to_string()).unwrap()
+    );
+}
@@ -11,9 +11,15 @@ fn slice_to_array_64<T>(slice: &[T]) -> Result<&[T; 64]> {
     }
 }
 
-pub fn sign_with_ecdsa(private_key: &str, digest: &String) -> Result<String> {
+// deterministic 为 true 时不加入随机噪声，相同的输入得到相同的签名
+pub fn sign_with_ecdsa(private_key: &str, digest: &String, deterministic: bool) -> Result<String> {
     let private = SecretKey::from_pem(private_key)?;
-    let signature = private.sign(digest.as_bytes(), Some(Noise::generate()));
+    let noise = if deterministic {
+        None
+    } else {
+        Some(Noise::generate())
+    };
+    let signature = private.sign(digest.as_bytes(), noise);
     let signature_base64 = general_purpose::STANDARD.encode(signature);
 
     Ok(signature_base64)
@@ -39,11 +45,19 @@ MC4CAQAwBQYDK2VwBCIEIBiVVXXhLTr/EY/FROnl67TVJz/jGV1WWN9HgptLMNWO
 -----END PRIVATE KEY-----"
         .to_string();
 
-    let signature = sign_with_ecdsa(&private_key, &"114514".to_string()).unwrap();
+    let signature = sign_with_ecdsa(&private_key, &"114514".to_string(), false).unwrap();
     println!("{signature}");
     let res = verify_with_ecdsa(&public_key, &"114514".to_string(), &signature).unwrap();
     assert!(res);
 
+    // 确定性签名
+    let deterministic = sign_with_ecdsa(&private_key, &"114514".to_string(), true).unwrap();
+    assert_eq!(
+        deterministic,
+        sign_with_ecdsa(&private_key, &"114514".to_string(), true).unwrap()
+    );
+    assert!(verify_with_ecdsa(&public_key, &"114514".to_string(), &deterministic).unwrap());
+
     let res = verify_with_ecdsa(
         &public_key,
         &"114514".to_string(),
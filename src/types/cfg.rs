@@ -1,6 +1,10 @@
 use std::{
-    fs::{create_dir_all, write},
+    collections::BTreeMap,
+    env::var,
+    fmt::{Display, Formatter},
+    fs::{create_dir_all, read_to_string, write},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use anyhow::{anyhow, Result};
@@ -8,18 +12,23 @@ use config::Config;
 use dirs::home_dir;
 use humantime::parse_duration;
 use serde::{Deserialize, Deserializer, Serialize};
-use toml::{to_string_pretty, Value};
+use toml::{to_string_pretty, Table, Value};
 
 use crate::{log, p2s, types::verifiable::Verifiable};
 
-use super::mixed_fs::MixedFS;
+use super::{mixed_fs::MixedFS, permissions::PermissionKey, policy::UpdatePolicy};
 
 lazy_static! {
     static ref CUR_DIR: PathBuf = Path::new("./").to_path_buf();
     static ref USER_DIR: PathBuf = home_dir().unwrap().join("ept");
+    static ref SYSTEM_DIR: PathBuf =
+        Path::new(&var("ProgramData").unwrap_or("C:/ProgramData".to_string())).join("ept");
 }
 
 const FILE_NAME: &str = "eptrc.toml";
+const PROFILE_DIR: &str = "profiles";
+const ENV_PREFIX: &str = "EPT";
+const ENV_SEPARATOR: &str = "__";
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Local {
     pub base: String,
@@ -84,11 +93,51 @@ pub struct Preference {
     pub expandable: PreferenceEnum,
 }
 
+// 全局的更新策略，可以被单个包的策略覆盖
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Update {
+    pub policy: UpdatePolicy,
+    pub allow_prerelease: bool,
+}
+
+// 安装包时对其所需权限采取的操作
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionAction {
+    Allow,
+    Ask,
+    Deny,
+}
+
+// 安装包前根据权限等级决定操作，可以按权限类型覆盖，受信任的 scope 或签名者不受限制
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermissionPolicy {
+    pub normal: PermissionAction,
+    pub important: PermissionAction,
+    pub sensitive: PermissionAction,
+    pub overrides: BTreeMap<String, PermissionAction>,
+    pub trusted_scopes: Vec<String>,
+    pub trusted_signers: Vec<String>,
+}
+
+// 打包时使用的 zstd 压缩参数
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Pack {
+    pub level: i32,
+    // 压缩线程数，为 0 时使用全部逻辑核心
+    pub threads: u32,
+    // 启用长距离匹配与更大的窗口，提升大包的压缩率
+    pub long_window: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Cfg {
     pub local: Local,
     pub online: Online,
     pub preference: Preference,
+    pub update: Update,
+    pub permission: PermissionPolicy,
+    pub pack: Pack,
 }
 
 impl Default for Cfg {
@@ -107,13 +156,101 @@ impl Default for Cfg {
                 portable: PreferenceEnum::HighPriority,
                 expandable: PreferenceEnum::HighPriority,
             },
+            update: Update {
+                policy: UpdatePolicy::Major,
+                allow_prerelease: false,
+            },
+            permission: PermissionPolicy {
+                normal: PermissionAction::Allow,
+                important: PermissionAction::Ask,
+                sensitive: PermissionAction::Deny,
+                overrides: BTreeMap::new(),
+                trusted_scopes: Vec::new(),
+                trusted_signers: Vec::new(),
+            },
+            pack: Pack {
+                level: 3,
+                threads: 0,
+                long_window: false,
+            },
+        }
+    }
+}
+
+// 配置的来源层级，优先级由低到高
+#[derive(Clone, Debug, PartialEq)]
+pub enum CfgLayer {
+    Default,
+    System(PathBuf),
+    User(PathBuf),
+    Project(PathBuf),
+    Profile(String, PathBuf),
+    Env(String),
+}
+
+impl CfgLayer {
+    pub fn get_path(&self) -> Option<&PathBuf> {
+        match self {
+            CfgLayer::System(p) | CfgLayer::User(p) | CfgLayer::Project(p) => Some(p),
+            CfgLayer::Profile(_, p) => Some(p),
+            CfgLayer::Default | CfgLayer::Env(_) => None,
+        }
+    }
+}
+
+impl Display for CfgLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfgLayer::Default => write!(f, "default"),
+            CfgLayer::System(p) => write!(f, "system:{}", p2s!(p)),
+            CfgLayer::User(p) => write!(f, "user:{}", p2s!(p)),
+            CfgLayer::Project(p) => write!(f, "project:{}", p2s!(p)),
+            CfgLayer::Profile(name, p) => write!(f, "profile({name}):{}", p2s!(p)),
+            CfgLayer::Env(key) => write!(f, "env:{key}"),
         }
     }
 }
 
 impl Cfg {
-    pub fn use_which() -> Result<PathBuf> {
-        let from = if CUR_DIR.join(FILE_NAME).exists() {
+    pub fn get_profile_path(name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(anyhow!(
+                "Error:Invalid profile name '{name}', expect letters, digits, '-' or '_'"
+            ));
+        }
+        Ok(USER_DIR.join(PROFILE_DIR).join(format!("{name}.toml")))
+    }
+    // 返回存在的配置文件层级，优先级由低到高
+    pub fn get_layers(profile: Option<&str>) -> Result<Vec<CfgLayer>> {
+        let mut layers = vec![
+            CfgLayer::System(SYSTEM_DIR.join(FILE_NAME)),
+            CfgLayer::User(USER_DIR.join(FILE_NAME)),
+            CfgLayer::Project(CUR_DIR.join(FILE_NAME)),
+        ];
+        if let Some(name) = profile {
+            layers.push(CfgLayer::Profile(
+                name.to_string(),
+                Self::get_profile_path(name)?,
+            ));
+        }
+        Ok(layers
+            .into_iter()
+            .filter(|layer| layer.get_path().map(|p| p.exists()).unwrap_or(false))
+            .collect())
+    }
+    // 返回写入配置时使用的文件
+    pub fn use_which(profile: Option<&str>) -> Result<PathBuf> {
+        let from = if let Some(name) = profile {
+            let from = Self::get_profile_path(name)?;
+            let dir = USER_DIR.join(PROFILE_DIR);
+            create_dir_all(&dir)
+                .map_err(|e| anyhow!("Error:Can't create '{d}' : {e}", d = p2s!(dir)))?;
+            from
+        } else if CUR_DIR.join(FILE_NAME).exists() {
             CUR_DIR.join(FILE_NAME)
         } else {
             let from = USER_DIR.join(FILE_NAME);
@@ -134,45 +271,130 @@ impl Cfg {
         log!("Debug:Use config at '{f}'", f = p2s!(from));
         Ok(from)
     }
-    pub fn init() -> Result<Self> {
-        let from = Self::use_which()?;
-        let f = p2s!(from);
+    pub fn init(profile: Option<&str>) -> Result<Self> {
+        // 确保用户配置存在
+        Self::use_which(None)?;
+        let layers = Self::get_layers(profile)?;
+        if let Some(name) = profile {
+            if !layers.iter().any(|l| matches!(l, CfgLayer::Profile(_, _))) {
+                log!("Warning:Profile '{name}' doesn't exist, using config without profile");
+            }
+        }
+        let layers_desc = layers
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        log!("Debug:Use config layers [{layers_desc}]");
+
+        // 按优先级由低到高叠加
         let default_val = Value::try_from(Self::default()).unwrap();
-        let settings = Config::builder()
-            .add_source(config::File::from_str(
-                &to_string_pretty(&default_val).unwrap(),
-                config::FileFormat::Toml,
-            ))
-            .add_source(config::File::with_name(&f))
-            .add_source(config::Environment::with_prefix("EPT"))
+        let mut builder = Config::builder().add_source(config::File::from_str(
+            &to_string_pretty(&default_val).unwrap(),
+            config::FileFormat::Toml,
+        ));
+        for layer in &layers {
+            let p = layer.get_path().unwrap();
+            builder = builder.add_source(config::File::new(&p2s!(p), config::FileFormat::Toml));
+        }
+        let settings = builder
+            .add_source(
+                config::Environment::with_prefix(ENV_PREFIX)
+                    .prefix_separator("_")
+                    .separator(ENV_SEPARATOR)
+                    .try_parsing(true),
+            )
             .build()
-            .map_err(|e| anyhow!("Error:Failed to build config with located config '{f}' : {e}"))?;
+            .map_err(|e| {
+                anyhow!("Error:Failed to build config with layers [{layers_desc}] : {e}")
+            })?;
         let cfg: Self = settings.try_deserialize().map_err(|e| {
-            anyhow!(
-                "Error:Invalid config content, try delete '{f}' : {e}",
-                f = p2s!(from),
-            )
+            anyhow!("Error:Invalid config content, check config layers [{layers_desc}] : {e}")
         })?;
         let mixed_fs = MixedFS::new("");
         // 校验
         cfg.verify_self(&mixed_fs)
-            .map_err(|e| anyhow!("Error:Invalid config '{f}' : {e}", f = p2s!(from)))?;
+            .map_err(|e| anyhow!("Error:Invalid config with layers [{layers_desc}] : {e}"))?;
 
         Ok(cfg)
     }
-    pub fn overwrite(other: Self) -> Result<()> {
+    // 返回每个配置项的值及其来源层级
+    pub fn get_origins(&self, profile: Option<&str>) -> Result<Vec<(String, Value, CfgLayer)>> {
+        let mut parsed = Vec::new();
+        for layer in Self::get_layers(profile)? {
+            let p = layer.get_path().unwrap();
+            let text = read_to_string(p)
+                .map_err(|e| anyhow!("Error:Failed to read config '{f}' : {e}", f = p2s!(p)))?;
+            let val: Value = toml::from_str(&text)
+                .map_err(|e| anyhow!("Error:Failed to parse config '{f}' : {e}", f = p2s!(p)))?;
+            parsed.push((layer, val));
+        }
+
+        let mut res = Vec::new();
+        let full = Value::try_from(self.clone())?;
+        for (table, tab) in full.as_table().unwrap() {
+            for (key, val) in tab.as_table().unwrap() {
+                let env_key = format!(
+                    "{ENV_PREFIX}_{}{ENV_SEPARATOR}{}",
+                    table.to_uppercase(),
+                    key.to_uppercase()
+                );
+                let origin = if var(&env_key).is_ok() {
+                    CfgLayer::Env(env_key)
+                } else {
+                    parsed
+                        .iter()
+                        .rev()
+                        .find(|(_, v)| v.get(table).and_then(|t| t.get(key)).is_some())
+                        .map(|(layer, _)| layer.to_owned())
+                        .unwrap_or(CfgLayer::Default)
+                };
+                res.push((format!("{table}.{key}"), val.to_owned(), origin));
+            }
+        }
+
+        Ok(res)
+    }
+    pub fn overwrite(other: Self, profile: Option<&str>) -> Result<()> {
         // 校验
         let mixed_fs = MixedFS::new("");
         other
             .verify_self(&mixed_fs)
             .map_err(|e| anyhow!("Error:Invalid overwrite config : {e}"))?;
 
-        let from = Self::use_which()?;
+        let from = Self::use_which(profile)?;
         let value = Value::try_from(other)?;
         let text = to_string_pretty(&value)?;
         write(from, text)?;
         Ok(())
     }
+    // 仅修改写入层级中的单个配置项，保留其他层级的配置
+    pub fn write_value(table: &str, key: &str, value: Value, profile: Option<&str>) -> Result<()> {
+        let from = Self::use_which(profile)?;
+        let mut doc = if from.exists() {
+            let text = read_to_string(&from)
+                .map_err(|e| anyhow!("Error:Failed to read config '{f}' : {e}", f = p2s!(from)))?;
+            toml::from_str(&text)
+                .map_err(|e| anyhow!("Error:Failed to parse config '{f}' : {e}", f = p2s!(from)))?
+        } else {
+            Value::Table(Table::new())
+        };
+        let root = doc
+            .as_table_mut()
+            .ok_or(anyhow!("Error:Invalid config '{f}'", f = p2s!(from)))?;
+        let tab = root
+            .entry(table)
+            .or_insert(Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or(anyhow!(
+                "Error:Field '{table}' in config '{f}' should be a table",
+                f = p2s!(from)
+            ))?;
+        tab.insert(key.to_string(), value);
+        write(&from, to_string_pretty(&doc)?)
+            .map_err(|e| anyhow!("Error:Failed to write config '{f}' : {e}", f = p2s!(from)))?;
+        Ok(())
+    }
 }
 
 impl Verifiable for Cfg {
@@ -195,6 +417,24 @@ impl Verifiable for Cfg {
         // mirror_update_interval 可解析
         parse_duration(&self.online.mirror_update_interval).map_err(|e| anyhow!("Error:Failed to parse field 'online.mirror_update_interval' as valid time span : '{e}', e.g. '5d' '14m54s'"))?;
 
+        // 压缩等级需要在 zstd 支持的范围内
+        let level_range = zstd::compression_level_range();
+        if !level_range.contains(&self.pack.level) {
+            return Err(anyhow!(
+                "Error:Field 'pack.level' should be in range {start}..={end}, got '{level}'",
+                start = level_range.start(),
+                end = level_range.end(),
+                level = self.pack.level
+            ));
+        }
+
+        // 覆盖的权限类型需要存在
+        for key in self.permission.overrides.keys() {
+            PermissionKey::from_str(key).map_err(|_| {
+                anyhow!("Error:Unknown permission key '{key}' in field 'permission.overrides'")
+            })?;
+        }
+
         Ok(())
     }
 }
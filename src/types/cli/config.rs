@@ -12,9 +12,13 @@ pub enum ActionConfig {
     Get { table: String, key: String },
     /// Displays the current configuration [alias 'ls']
     #[clap(alias = "ls")]
-    List,
+    List {
+        /// Show which layer each value comes from
+        #[arg(long)]
+        show_origin: bool,
+    },
     /// Initialize config file
     Init,
-    /// Print which config file is selected
+    /// Print which config file is selected for writing
     Which,
 }
@@ -1,7 +1,9 @@
 mod config;
 mod mirror;
+mod policy;
 pub use self::config::ActionConfig;
 pub use self::mirror::ActionMirror;
+pub use self::policy::ActionPolicy;
 use clap::{Parser, Subcommand};
 
 /// Edgeless Package Tool (ept) for Next-Generation Edgeless Packages (nep)
@@ -25,6 +27,10 @@ pub struct Args {
     /// Run commands in debug mode
     #[arg(short, long)]
     pub debug: bool,
+
+    /// Use a named config profile, e.g. --profile pe-build
+    #[arg(long)]
+    pub profile: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -34,6 +40,12 @@ pub enum Action {
     Install {
         /// Package matchers（expect pattern ((MIRROR/)SCOPE/)NAME(@SEMVER)）or Nep package url or Nep package local path
         packages: Vec<String>,
+        /// Allow replacing installed packages with older versions, e.g. ept install vscode@=1.75.0 --allow-downgrade
+        #[arg(long)]
+        allow_downgrade: bool,
+        /// Record why the packages are installed, expect 'explicit' (default), 'batch' or 'import'; only packages tagged 'batch' or 'import' are removed by 'ept autoremove'
+        #[arg(long)]
+        reason: Option<String>,
     },
 
     /// Update all updatable packages or a specified package [alias 'up']
@@ -41,6 +53,9 @@ pub enum Action {
     Update {
         /// Package matchers（expect pattern ((MIRROR/)SCOPE/)NAME(@SEMVER)）or Nep package url or Nep package local path
         packages: Option<Vec<String>>,
+        /// Update pinned packages regardless of their pins
+        #[arg(short, long)]
+        force: bool,
     },
 
     /// Uninstall packages [alias 'remove' 'rm']
@@ -67,7 +82,56 @@ pub enum Action {
 
     /// List information of installed packages [alias 'ls']
     #[clap(alias = "ls")]
-    List,
+    List {
+        /// Show all available releases in mirrors
+        #[arg(short, long)]
+        releases: bool,
+        /// Only show packages in the given scope
+        #[arg(long)]
+        scope: Option<String>,
+        /// Only show packages in the given category
+        #[arg(long)]
+        category: Option<String>,
+        /// Only show packages that can be updated
+        #[arg(long)]
+        upgradable: bool,
+        /// Only show pinned packages
+        #[arg(long)]
+        pinned: bool,
+        /// Only show broken installation folders
+        #[arg(long)]
+        broken: bool,
+        /// Only show packages installed from the given mirror
+        #[arg(long)]
+        from_mirror: Option<String>,
+        /// Sort packages, expect 'name', 'date' or 'size'
+        #[arg(short, long, default_value = "name")]
+        sort: String,
+        /// Show details of each package, including software fields, install path and size
+        #[arg(short, long)]
+        long: bool,
+    },
+
+    /// Show disk usage of installed packages, cache, temporary files and mirror indexes
+    Du,
+
+    /// Pin a package to prevent it from being updated out of the given range
+    Pin {
+        /// Package matcher, expect pattern (SCOPE/)NAME(@SEMVER), hold current version if no SEMVER provided
+        package_matcher: String,
+    },
+
+    /// Release a pinned package
+    Unpin {
+        /// Package matcher, expect pattern (SCOPE/)NAME
+        package_matcher: String,
+    },
+
+    /// Manage update policies of packages
+    Policy {
+        #[command(subcommand)]
+        operation: ActionPolicy,
+    },
 
     /// Get meta data of given package
     Meta {
@@ -77,12 +141,73 @@ pub enum Action {
         save_at: Option<String>,
     },
 
+    /// List files inside a nep package with sizes, without installing it
+    LsPackage {
+        /// Nep package local path
+        package: String,
+    },
+
+    /// Print a file inside a nep package to stdout, e.g. 'workflows/setup.toml'
+    Cat {
+        /// Nep package local path
+        package: String,
+        /// Path of the file relative to the package root
+        file: String,
+    },
+
+    /// Compare two versions of a package, showing changed fields, workflow steps, permissions and files
+    Diff {
+        /// Old nep package or source directory path
+        old: String,
+        /// New nep package or source directory path
+        new: String,
+    },
+
+    /// Run setup, update and remove workflows of a source directory in a sandbox, then check manifest and leftover files
+    Test {
+        /// Source directory path
+        package: String,
+        /// Run 'Execute' steps, which are not sandboxed and run commands on the host
+        #[arg(long)]
+        allow_execute: bool,
+    },
+
+    /// Create a package source directory with prompts, missing fields are asked interactively unless '-y' is given
+    New(Box<NewArgs>),
+
+    /// Check a package source directory and report all found issues
+    Lint {
+        /// Source directory to check
+        source_dir: String,
+        /// Output format, expect 'text', 'json' or 'sarif'
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// Write the report to file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Fix mechanical issues in place, e.g. backslashes in commands
+        #[arg(long)]
+        fix: bool,
+    },
+
     /// Pack a directory content into nep
     Pack {
         /// Source directory ready to be packed
         source_dir: String,
         /// (Optional) Store packed nep at
         into_file: Option<String>,
+        /// Produce byte-identical nep for the same source, using mtime from SOURCE_DATE_EPOCH if provided
+        #[arg(long)]
+        reproducible: bool,
+        /// Zstd compression level, default to 'pack.level' in config
+        #[arg(long, allow_negative_numbers = true)]
+        level: Option<i32>,
+        /// Compression threads, 0 for all logical cores, default to 'pack.threads' in config
+        #[arg(long)]
+        threads: Option<u32>,
+        /// Produce a delta nep containing only changes relative to the given base nep, the full nep is stored beside it
+        #[arg(long)]
+        delta_from: Option<String>,
     },
 
     /// Manage ept config
@@ -106,4 +231,62 @@ pub enum Action {
 
     /// Clean temporary or illegal files
     Clean,
+
+    /// Uninstall packages that were never explicitly requested, i.e. installed with '--reason batch' or '--reason import'
+    Autoremove,
+
+    /// Query the history of install, update, uninstall and clean operations
+    History {
+        /// Only show operations involving the package, expect pattern (SCOPE/)NAME
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Only show operations within the time span, e.g. ept history --since 7d
+        #[arg(short, long)]
+        since: Option<String>,
+    },
+
+    /// Diagnose installed packages and offer fixes for found issues
+    Doctor,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct NewArgs {
+    /// Directory to create, should be empty or not exist
+    pub dir: String,
+    /// Directory of the program files, which will be copied into the package
+    #[arg(long)]
+    pub source: Option<String>,
+    /// Package name, e.g. --name VSCode
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Package description
+    #[arg(long)]
+    pub description: Option<String>,
+    /// Package version, defaults to the version of main program
+    #[arg(long)]
+    pub pkg_version: Option<String>,
+    /// Package authors separated by comma, e.g. --authors "Cno <dsyourshy@qq.com>,Microsoft"
+    #[arg(long, value_delimiter = ',')]
+    pub authors: Option<Vec<String>>,
+    /// License SPDX identifier or EULA link
+    #[arg(long)]
+    pub license: Option<String>,
+    /// Software scope, e.g. --scope Microsoft
+    #[arg(long)]
+    pub scope: Option<String>,
+    /// Upstream url
+    #[arg(long)]
+    pub upstream: Option<String>,
+    /// Software category
+    #[arg(long)]
+    pub category: Option<String>,
+    /// Software language, expect 'Multi', 'zh-CN' or 'en-US'
+    #[arg(long)]
+    pub language: Option<String>,
+    /// Main program relative to the source directory
+    #[arg(long)]
+    pub main_program: Option<String>,
+    /// Optional workflow skeletons separated by comma, expect 'update', 'remove' or 'expand'
+    #[arg(long, value_delimiter = ',')]
+    pub with: Option<Vec<String>>,
 }
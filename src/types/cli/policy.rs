@@ -0,0 +1,24 @@
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum ActionPolicy {
+    /// Set update policy of a package
+    Set {
+        /// Package matcher, expect pattern (SCOPE/)NAME
+        package_matcher: String,
+        /// Update policy, expect 'major', 'minor', 'patch' or 'reserved'
+        policy: String,
+        /// Allow updating to prerelease versions
+        #[arg(short, long)]
+        prerelease: bool,
+    },
+    /// Remove update policy of a package, fallback to global policy in config [alias 'rm']
+    #[clap(alias = "rm")]
+    Unset {
+        /// Package matcher, expect pattern (SCOPE/)NAME
+        package_matcher: String,
+    },
+    /// List update policies of packages [alias 'ls']
+    #[clap(alias = "ls")]
+    List,
+}
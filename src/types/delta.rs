@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+// 增量包内包中的 delta.toml，随内包一同签名
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Delta {
+    pub delta: DeltaNode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeltaNode {
+    pub scope: String,
+    pub name: String,
+    // 增量包的目标版本
+    pub version: String,
+    pub base_version: String,
+    // 基础版本内包的 blake3 摘要，需要与已安装版本的安装记录一致
+    pub base_hash: String,
+    // 目标版本完整包内包的 blake3 摘要，应用后写入安装记录以便继续应用下一个增量包
+    pub full_hash: String,
+    // 相对于程序目录，需要删除的文件
+    pub deleted: Vec<String>,
+}
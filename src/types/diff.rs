@@ -0,0 +1,135 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+use super::permissions::Permission;
+use crate::utils::{
+    fmt_print::{fmt_change_line, fmt_size},
+    permissions::fmt_permissions,
+};
+
+// 单个字段的变化，新增时 old 为 None，移除时 new 为 None
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    // 以 '.' 分隔的字段路径
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+// 工作流中单个步骤的变化，old 与 new 为步骤类型
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StepChange {
+    pub workflow: String,
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileStat {
+    pub size: u64,
+    pub hash: String,
+}
+
+impl Display for FileStat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // 摘要仅展示前 8 位
+        let short = self.hash.get(0..8).unwrap_or(&self.hash);
+        write!(f, "{} ({short})", fmt_size(self.size))
+    }
+}
+
+// 单个文件的变化，路径以 '/' 分隔
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub old: Option<FileStat>,
+    pub new: Option<FileStat>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PackageDiff {
+    pub fields: Vec<FieldChange>,
+    pub steps: Vec<StepChange>,
+    pub permissions_added: Vec<Permission>,
+    pub permissions_removed: Vec<Permission>,
+    pub files: Vec<FileChange>,
+}
+
+impl PackageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.steps.is_empty()
+            && self.permissions_added.is_empty()
+            && self.permissions_removed.is_empty()
+            && self.files.is_empty()
+    }
+}
+
+impl Display for PackageDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.fields.is_empty() {
+            writeln!(f, "\nPackage fields:")?;
+            for node in &self.fields {
+                write!(
+                    f,
+                    "{}",
+                    fmt_change_line(&node.key, node.old.as_deref(), node.new.as_deref())
+                )?;
+            }
+        }
+        if !self.steps.is_empty() {
+            writeln!(f, "\nWorkflow steps:")?;
+            for node in &self.steps {
+                write!(
+                    f,
+                    "{}",
+                    fmt_change_line(
+                        &format!("{}/{}", node.workflow, node.key),
+                        node.old.as_deref(),
+                        node.new.as_deref()
+                    )
+                )?;
+                for field in &node.fields {
+                    write!(
+                        f,
+                        "{}",
+                        fmt_change_line(
+                            &format!("  {}", field.key),
+                            field.old.as_deref(),
+                            field.new.as_deref()
+                        )
+                    )?;
+                }
+            }
+        }
+        if !self.permissions_added.is_empty() {
+            write!(
+                f,
+                "\nPermissions added:\n{}",
+                fmt_permissions(&self.permissions_added)
+            )?;
+        }
+        if !self.permissions_removed.is_empty() {
+            write!(
+                f,
+                "\nPermissions removed:\n{}",
+                fmt_permissions(&self.permissions_removed)
+            )?;
+        }
+        if !self.files.is_empty() {
+            writeln!(f, "\nFiles:")?;
+            for node in &self.files {
+                let old = node.old.as_ref().map(|stat| stat.to_string());
+                let new = node.new.as_ref().map(|stat| stat.to_string());
+                write!(
+                    f,
+                    "{}",
+                    fmt_change_line(&node.path, old.as_deref(), new.as_deref())
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use colored::Colorize;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use crate::p2s;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DoctorIssueKind {
+    // 安装目录不完整或无法读取包信息
+    BrokenPackage {
+        scope: String,
+        name: String,
+    },
+    // 声明的主程序不存在
+    MissingMainProgram {
+        scope: String,
+        name: String,
+        main_program: String,
+    },
+    // bin 目录中无主的入口
+    OrphanEntrance,
+    // 镜像源目录缺失必要的索引文件
+    DanglingMirror {
+        name: String,
+    },
+    // 残留的临时目录
+    TempLeftover,
+}
+
+#[derive(Clone, Debug)]
+pub struct DoctorIssue {
+    pub kind: DoctorIssueKind,
+    pub target: PathBuf,
+    pub reason: String,
+}
+
+impl Display for DoctorIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let title = match &self.kind {
+            DoctorIssueKind::BrokenPackage { scope, name } => {
+                format!("Broken package '{scope}/{name}'")
+            }
+            DoctorIssueKind::MissingMainProgram { scope, name, .. } => {
+                format!("Missing main program of '{scope}/{name}'")
+            }
+            DoctorIssueKind::OrphanEntrance => "Orphan entrance".to_string(),
+            DoctorIssueKind::DanglingMirror { name } => format!("Dangling mirror '{name}'"),
+            DoctorIssueKind::TempLeftover => "Temporary leftover".to_string(),
+        };
+        writeln!(
+            f,
+            "  {title:<48} {target}\n  {reason}",
+            title = title.yellow(),
+            target = p2s!(self.target).truecolor(100, 100, 100),
+            reason = self.reason.as_str().truecolor(100, 100, 100).italic()
+        )
+    }
+}
+
+impl DoctorIssue {
+    // 返回问题修复方式的提问
+    pub fn fix_prompt(&self) -> String {
+        match &self.kind {
+            DoctorIssueKind::BrokenPackage { scope, name } => {
+                format!("Reinstall broken package '{scope}/{name}' from mirror?")
+            }
+            DoctorIssueKind::MissingMainProgram { scope, name, .. } => {
+                format!("Re-run setup workflow of package '{scope}/{name}'?")
+            }
+            DoctorIssueKind::OrphanEntrance
+            | DoctorIssueKind::DanglingMirror { .. }
+            | DoctorIssueKind::TempLeftover => {
+                format!("Remove '{target}'?", target = p2s!(self.target))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_doctor_issue() {
+    let issue = DoctorIssue {
+        kind: DoctorIssueKind::BrokenPackage {
+            scope: "Microsoft".to_string(),
+            name: "VSCode".to_string(),
+        },
+        target: PathBuf::from("./apps/Microsoft/VSCode"),
+        reason: "Error:Invalid nep app folder".to_string(),
+    };
+    println!("{issue}");
+    assert_eq!(
+        issue.fix_prompt(),
+        "Reinstall broken package 'Microsoft/VSCode' from mirror?".to_string()
+    );
+}
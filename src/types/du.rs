@@ -0,0 +1,58 @@
+// 单个已安装包的磁盘占用，单位为字节
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageUsage {
+    pub scope: String,
+    pub name: String,
+    // 应用文件，不含 .nep_context
+    pub app: u64,
+    // .nep_context 目录
+    pub context: u64,
+    // 缓存目录中属于该包的 nep
+    pub cache: u64,
+}
+
+impl PackageUsage {
+    pub fn total(&self) -> u64 {
+        self.app + self.context + self.cache
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DiskUsage {
+    // 按占用空间降序排列
+    pub packages: Vec<PackageUsage>,
+    pub cache: u64,
+    pub temp: u64,
+    pub mirror: u64,
+}
+
+impl DiskUsage {
+    // 包的缓存已经计入缓存目录，不重复计算
+    pub fn total(&self) -> u64 {
+        self.packages
+            .iter()
+            .fold(0, |acc, node| acc + node.app + node.context)
+            + self.cache
+            + self.temp
+            + self.mirror
+    }
+}
+
+#[test]
+fn test_disk_usage_total() {
+    let node = PackageUsage {
+        scope: "Microsoft".to_string(),
+        name: "VSCode".to_string(),
+        app: 100,
+        context: 10,
+        cache: 50,
+    };
+    assert_eq!(node.total(), 160);
+    let usage = DiskUsage {
+        packages: vec![node],
+        cache: 80,
+        temp: 5,
+        mirror: 1,
+    };
+    assert_eq!(usage.total(), 196);
+}
@@ -0,0 +1,145 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+use crate::utils::fmt_print::{fmt_detail_line, fmt_timestamp};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOperation {
+    Install,
+    Update,
+    Uninstall,
+    Clean,
+}
+
+impl Display for HistoryOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            HistoryOperation::Install => "install",
+            HistoryOperation::Update => "update",
+            HistoryOperation::Uninstall => "uninstall",
+            HistoryOperation::Clean => "clean",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryPackage {
+    // 形如 SCOPE/NAME，无法解析出包名时为用户输入的路径或 URL
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl HistoryPackage {
+    pub fn new(scope: &str, name: &str, from: Option<String>, to: Option<String>) -> Self {
+        Self {
+            name: format!("{scope}/{name}"),
+            from,
+            to,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match (&self.from, &self.to) {
+            (Some(from), Some(to)) => format!("{} ({from} → {to})", self.name),
+            (Some(from), None) => format!("{} ({from})", self.name),
+            (None, Some(to)) => format!("{} ({to})", self.name),
+            (None, None) => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    // 操作开始的时间戳，单位为秒
+    pub timestamp: u64,
+    pub operation: HistoryOperation,
+    pub packages: Vec<HistoryPackage>,
+    pub success: bool,
+    // 操作耗时，单位为毫秒
+    pub duration: u64,
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    // 匹配 SCOPE/NAME 或 NAME，不区分大小写
+    pub fn involves(&self, package: &str) -> bool {
+        let package = package.to_lowercase();
+        self.packages.iter().any(|node| {
+            let name = node.name.to_lowercase();
+            name == package || name.ends_with(&format!("/{package}"))
+        })
+    }
+}
+
+impl Display for HistoryEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let result = if self.success {
+            "success".green()
+        } else {
+            "failure".red()
+        };
+        writeln!(
+            f,
+            "  {} {:<10} {result} {}",
+            fmt_timestamp(self.timestamp),
+            self.operation.to_string().cyan().bold(),
+            format!("in {:.1}s", self.duration as f64 / 1000.0).truecolor(100, 100, 100)
+        )?;
+        for node in &self.packages {
+            write!(f, "{}", fmt_detail_line("package :", &node.describe()))?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, "{}", fmt_detail_line("error :", error))?;
+        }
+        Ok(())
+    }
+}
+
+// 历史记录文件，使用 [[entries]] 表数组以便追加写入
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct History {
+    #[serde(default)]
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[test]
+fn test_history_entry() {
+    let entry = HistoryEntry {
+        timestamp: 1700000000,
+        operation: HistoryOperation::Update,
+        packages: vec![HistoryPackage::new(
+            "Microsoft",
+            "VSCode",
+            Some("1.75.4.0".to_string()),
+            Some("1.76.0.0".to_string()),
+        )],
+        success: true,
+        duration: 1500,
+        error: None,
+    };
+    assert!(entry.involves("vscode"));
+    assert!(entry.involves("microsoft/VSCode"));
+    assert!(!entry.involves("code"));
+
+    // 逐条序列化的文本拼接后仍能作为整体解析
+    let single = |entry: &HistoryEntry| {
+        toml::to_string(&History {
+            entries: vec![entry.clone()],
+        })
+        .unwrap()
+    };
+    let failed = HistoryEntry {
+        operation: HistoryOperation::Clean,
+        packages: Vec::new(),
+        success: false,
+        error: Some("Error:Operation cancelled by user".to_string()),
+        ..entry.clone()
+    };
+    let text = single(&entry) + &single(&failed);
+    let history: History = toml::from_str(&text).unwrap();
+    assert_eq!(history.entries, vec![entry, failed]);
+}
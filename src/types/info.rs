@@ -3,16 +3,24 @@ use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-use crate::types::software::Software;
+use crate::types::{
+    history::HistoryPackage, install_record::InstallRecord, permissions::Permission,
+    software::Software,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Info {
     pub name: String,
     pub template: String,
     pub license: Option<String>,
+    pub icon: Option<String>,
     pub local: Option<InfoDiff>,
     pub online: Option<InfoDiff>,
     pub software: Option<Software>,
+    pub releases: Vec<InfoRelease>,
+    // 提供在线信息的镜像源名称
+    pub mirror: Option<String>,
+    pub installed: Option<InfoInstalled>,
 }
 
 // 线上与本地的差异点
@@ -22,6 +30,28 @@ pub struct InfoDiff {
     pub authors: Vec<String>,
 }
 
+// 镜像源中可用的发行版本，按版本号降序排列
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InfoRelease {
+    pub version: String,
+    pub file_name: String,
+    pub size: u64,
+    pub timestamp: u64,
+}
+
+// 已安装包的本地状态
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InfoInstalled {
+    // 安装时间戳，单位为秒
+    pub installed_at: u64,
+    // 安装目录占用的磁盘空间，单位为字节
+    pub disk_usage: u64,
+    // 已安装工作流需要的权限
+    pub permissions: Vec<Permission>,
+    // 在此功能之前安装的包没有安装记录
+    pub record: Option<InstallRecord>,
+}
+
 pub struct UpdateInfo {
     pub name: String,
     pub scope: String,
@@ -52,6 +82,14 @@ impl UpdateInfo {
             to_ver = &self.to_version
         )
     }
+    pub fn to_history_package(&self) -> HistoryPackage {
+        HistoryPackage::new(
+            &self.scope,
+            &self.name,
+            Some(self.from_version.clone()),
+            Some(self.to_version.clone()),
+        )
+    }
     pub fn format_failure(&self, e: Error) -> String {
         format!(
             "Error:Failed to update '{scope}/{name}' from '{from_ver}' to '{to_ver}' : {e}",
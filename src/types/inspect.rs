@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+// nep 内包中的一个条目
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PackageEntry {
+    // 以 '/' 分隔的相对路径
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 包被安装的原因
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallReason {
+    // 用户单独请求安装
+    Explicit,
+    // 随一批包一起安装
+    Batch,
+    // 从包列表导入安装
+    Import,
+}
+
+impl InstallReason {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.to_lowercase().as_str() {
+            "explicit" => Ok(InstallReason::Explicit),
+            "batch" => Ok(InstallReason::Batch),
+            "import" => Ok(InstallReason::Import),
+            _ => Err(anyhow!(
+                "Error:Invalid install reason '{raw}', expect 'explicit', 'batch' or 'import'"
+            )),
+        }
+    }
+}
+
+impl Display for InstallReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            InstallReason::Explicit => "explicit",
+            InstallReason::Batch => "batch",
+            InstallReason::Import => "import",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// 安装使用的包来源
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum InstallSource {
+    Local { path: String },
+    Url { url: String },
+    Mirror { mirror: String, file_name: String },
+}
+
+impl Display for InstallSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallSource::Local { path } => write!(f, "local file '{path}'"),
+            InstallSource::Url { url } => write!(f, "url '{url}'"),
+            InstallSource::Mirror { mirror, file_name } => {
+                write!(f, "mirror '{mirror}' ({file_name})")
+            }
+        }
+    }
+}
+
+// 保存在 .nep_context/install.toml 中的安装记录
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InstallRecord {
+    pub reason: InstallReason,
+    // 首次安装的时间戳，单位为秒
+    pub installed_at: u64,
+    // 最近一次更新的时间戳，单位为秒
+    pub updated_at: Option<u64>,
+    pub source: InstallSource,
+    // 安装或更新时使用的 ept 版本
+    pub ept_version: String,
+    // 安装或更新时内包的 blake3 摘要，用于匹配增量包的基础版本
+    pub package_hash: Option<String>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl InstallRecord {
+    pub fn new(reason: InstallReason, source: InstallSource) -> Self {
+        Self {
+            reason,
+            installed_at: now_secs(),
+            updated_at: None,
+            source,
+            ept_version: env!("CARGO_PKG_VERSION").to_string(),
+            package_hash: None,
+        }
+    }
+
+    // 更新时保留安装原因和首次安装时间
+    pub fn refresh(self, source: InstallSource) -> Self {
+        Self {
+            updated_at: Some(now_secs()),
+            source,
+            ept_version: env!("CARGO_PKG_VERSION").to_string(),
+            ..self
+        }
+    }
+
+    // 从目录安装时没有内包，摘要为 None
+    pub fn with_package_hash(self, package_hash: Option<String>) -> Self {
+        Self {
+            package_hash,
+            ..self
+        }
+    }
+}
+
+#[test]
+fn test_install_record() {
+    let record = InstallRecord::new(
+        InstallReason::Batch,
+        InstallSource::Mirror {
+            mirror: "official".to_string(),
+            file_name: "VSCode_1.75.4.0_Cno.nep".to_string(),
+        },
+    );
+    let text = toml::to_string_pretty(&record).unwrap();
+    assert!(text.contains("reason = \"batch\""));
+    assert!(text.contains("type = \"mirror\""));
+    let parsed: InstallRecord = toml::from_str(&text).unwrap();
+    assert_eq!(parsed, record);
+
+    let refreshed = record.clone().refresh(InstallSource::Local {
+        path: "./VSCode_1.76.0.0_Cno.nep".to_string(),
+    });
+    assert_eq!(refreshed.reason, InstallReason::Batch);
+    assert_eq!(refreshed.installed_at, record.installed_at);
+    assert!(refreshed.updated_at.is_some());
+
+    // 旧的安装记录中没有摘要
+    let hashed = refreshed.with_package_hash(Some("af1349b9".to_string()));
+    let text = toml::to_string_pretty(&hashed).unwrap();
+    assert!(text.contains("package_hash = \"af1349b9\""));
+    let legacy = text.replace("package_hash = \"af1349b9\"\n", "");
+    let parsed: InstallRecord = toml::from_str(&legacy).unwrap();
+    assert_eq!(parsed.package_hash, None);
+
+    assert_eq!(
+        InstallReason::parse("Import").unwrap(),
+        InstallReason::Import
+    );
+    assert!(InstallReason::parse("dependency").is_err());
+}
@@ -0,0 +1,279 @@
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+impl Display for LintSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// 可以机械修复的问题，将字符串字面量替换为新值
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LintEdit {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LintDiagnostic {
+    // 规则名称，如 execute-backslash
+    pub rule: String,
+    pub severity: LintSeverity,
+    // 相对于包源目录的文件路径
+    pub file: String,
+    // TOML 键路径，如 run_installer.command
+    pub key_path: Option<String>,
+    // 从 1 开始的行列号，无法定位时为 None
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    // 修复建议
+    pub suggestion: Option<String>,
+    pub edit: Option<LintEdit>,
+}
+
+impl LintDiagnostic {
+    pub fn error(rule: &str, file: &str, key_path: Option<String>, message: String) -> Self {
+        Self {
+            rule: rule.to_string(),
+            severity: LintSeverity::Error,
+            file: file.to_string(),
+            key_path,
+            line: None,
+            column: None,
+            message: strip_log_head(&message),
+            suggestion: None,
+            edit: None,
+        }
+    }
+
+    pub fn warning(rule: &str, file: &str, key_path: Option<String>, message: String) -> Self {
+        Self {
+            severity: LintSeverity::Warning,
+            ..Self::error(rule, file, key_path, message)
+        }
+    }
+
+    pub fn with_suggestion(self, suggestion: String) -> Self {
+        Self {
+            suggestion: Some(suggestion),
+            ..self
+        }
+    }
+
+    pub fn with_edit(self, from: String, to: String) -> Self {
+        Self {
+            edit: Some(LintEdit { from, to }),
+            ..self
+        }
+    }
+
+    pub fn with_position(self, line: usize, column: usize) -> Self {
+        Self {
+            line: Some(line),
+            column: Some(column),
+            ..self
+        }
+    }
+
+    pub fn location(&self) -> String {
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            format!("{}:{line}:{column}", self.file)
+        } else if let Some(key_path) = &self.key_path {
+            format!("{}:{key_path}", self.file)
+        } else {
+            self.file.clone()
+        }
+    }
+}
+
+// 去掉错误信息中 "Error:" 或 "Error(Step):" 形式的日志头
+fn strip_log_head(message: &str) -> String {
+    for head in ["Error", "Warning"] {
+        if let Some(rest) = message.strip_prefix(head) {
+            if let Some(rest) = rest.strip_prefix(':') {
+                return rest.to_string();
+            }
+            if rest.starts_with('(') {
+                if let Some(index) = rest.find("):") {
+                    return rest[index + 2..].to_string();
+                }
+            }
+        }
+    }
+    message.to_string()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+impl LintFormat {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.to_lowercase().as_str() {
+            "text" => Ok(LintFormat::Text),
+            "json" => Ok(LintFormat::Json),
+            "sarif" => Ok(LintFormat::Sarif),
+            _ => Err(anyhow!(
+                "Error:Invalid lint format '{raw}', expect 'text', 'json' or 'sarif'"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LintReport {
+    pub diagnostics: Vec<LintDiagnostic>,
+    // 已经自动修复的问题数量
+    pub fixed: usize,
+}
+
+impl LintReport {
+    pub fn count(&self, severity: LintSeverity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    pub fn to_text(&self) -> String {
+        self.diagnostics.iter().fold(String::new(), |acc, d| {
+            let severity = match d.severity {
+                LintSeverity::Error => d.severity.to_string().bright_red(),
+                LintSeverity::Warning => d.severity.to_string().bright_yellow(),
+            };
+            let mut line = format!(
+                "  {severity:<8} {} {}\n           {}\n",
+                d.location().cyan(),
+                format!("[{}]", d.rule).truecolor(100, 100, 100),
+                d.message
+            );
+            if let Some(suggestion) = &d.suggestion {
+                line += &format!(
+                    "           {}\n",
+                    format!("help: {suggestion}").truecolor(100, 100, 100)
+                );
+            }
+            acc + &line
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.diagnostics)?)
+    }
+
+    // 生成 SARIF 2.1.0 格式的报告，供 CI 平台展示
+    pub fn to_sarif(&self) -> Result<String> {
+        let results: Vec<serde_json::Value> = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                let mut location = json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": d.file }
+                    }
+                });
+                if let (Some(line), Some(column)) = (d.line, d.column) {
+                    location["physicalLocation"]["region"] =
+                        json!({ "startLine": line, "startColumn": column });
+                }
+                if let Some(key_path) = &d.key_path {
+                    location["logicalLocations"] = json!([{ "fullyQualifiedName": key_path }]);
+                }
+                let mut result = json!({
+                    "ruleId": d.rule,
+                    "level": d.severity.to_string(),
+                    "message": { "text": d.message },
+                    "locations": [location]
+                });
+                if let Some(suggestion) = &d.suggestion {
+                    result["fixes"] = json!([{ "description": { "text": suggestion } }]);
+                }
+                result
+            })
+            .collect();
+        let sarif = json!({
+            "version": "2.1.0",
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "ept",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/EdgelessPE/ept"
+                    }
+                },
+                "results": results
+            }]
+        });
+        Ok(serde_json::to_string_pretty(&sarif)?)
+    }
+}
+
+#[test]
+fn test_lint_report() {
+    let report = LintReport {
+        diagnostics: vec![
+            LintDiagnostic::error(
+                "execute-backslash",
+                "workflows/setup.toml",
+                Some("run.command".to_string()),
+                "Error(Execute):Backslash (\\) in 'a\\b' is not allowed".to_string(),
+            )
+            .with_suggestion("use 'a/b' instead".to_string())
+            .with_edit("a\\b".to_string(), "a/b".to_string()),
+            LintDiagnostic::warning(
+                "unknown-value",
+                "package.toml",
+                None,
+                "Warning:Unknown value '${Foo}'".to_string(),
+            ),
+        ],
+        fixed: 0,
+    };
+    assert_eq!(report.count(LintSeverity::Error), 1);
+    assert_eq!(
+        report.diagnostics[0].message,
+        "Backslash (\\) in 'a\\b' is not allowed"
+    );
+    assert_eq!(
+        report.diagnostics[0].location(),
+        "workflows/setup.toml:run.command"
+    );
+    assert_eq!(report.diagnostics[1].message, "Unknown value '${Foo}'");
+    assert_eq!(
+        report.diagnostics[1].clone().with_position(3, 9).location(),
+        "package.toml:3:9"
+    );
+
+    let sarif: serde_json::Value = serde_json::from_str(&report.to_sarif().unwrap()).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(
+        results[0]["locations"][0]["logicalLocations"][0]["fullyQualifiedName"],
+        "run.command"
+    );
+    assert!(report
+        .to_json()
+        .unwrap()
+        .contains("\"severity\": \"warning\""));
+    assert!(LintFormat::parse("xml").is_err());
+}
@@ -0,0 +1,306 @@
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use super::{
+    du::PackageUsage,
+    info::Info,
+    install_record::{InstallRecord, InstallSource},
+    pin::PinNode,
+};
+use crate::{
+    p2s,
+    utils::fmt_print::{
+        fmt_detail_line, fmt_package_line, fmt_releases_line, fmt_size, fmt_timestamp,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListSortBy {
+    Name,
+    // 安装时间，较新的在前
+    Date,
+    // 磁盘占用，较大的在前
+    Size,
+}
+
+impl ListSortBy {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.to_lowercase().as_str() {
+            "name" => Ok(ListSortBy::Name),
+            "date" => Ok(ListSortBy::Date),
+            "size" => Ok(ListSortBy::Size),
+            _ => Err(anyhow!(
+                "Error:Invalid sort key '{raw}', expect 'name', 'date' or 'size'"
+            )),
+        }
+    }
+}
+
+// 包的安装方式
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstallKind {
+    // 免安装
+    Portable,
+    // 调用安装器安装，提供了 registry_entry
+    Installer,
+    // 可展开的包
+    Expandable,
+}
+
+impl Display for InstallKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            InstallKind::Portable => "portable",
+            InstallKind::Installer => "installer",
+            InstallKind::Expandable => "expandable",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ListEntry {
+    pub scope: String,
+    pub name: String,
+    // 安装目录无效时为 None
+    pub info: Option<Info>,
+    pub broken_reason: Option<String>,
+    pub install_path: PathBuf,
+    pub pin: Option<PinNode>,
+    // 锁定规则和更新策略允许更新到的版本
+    pub upgradable: Option<String>,
+    // 以下字段仅在需要排序或使用长格式时读取
+    pub installed_at: Option<u64>,
+    pub usage: Option<PackageUsage>,
+    pub kind: Option<InstallKind>,
+    pub record: Option<InstallRecord>,
+}
+
+impl ListEntry {
+    pub fn format(&self, releases: bool, long: bool) -> String {
+        let info = if let Some(info) = &self.info {
+            info
+        } else {
+            return fmt_package_line(
+                &self.scope,
+                &self.name,
+                "broken",
+                self.broken_reason.clone(),
+            );
+        };
+
+        let local_ver = info.local.clone().map(|l| l.version).unwrap_or_default();
+        let update_tip = self
+            .upgradable
+            .clone()
+            .map(|version| format!("  ↑ {version}").green().to_string())
+            .unwrap_or_default();
+        let mut res = fmt_package_line(
+            &self.scope,
+            &self.name,
+            &format!("{local_ver}{update_tip}"),
+            self.pin.clone().map(|pin| pin.describe()),
+        );
+
+        if long {
+            if let Some(software) = &info.software {
+                res += &fmt_detail_line("category :", &software.category);
+                res += &fmt_detail_line("language :", &software.language);
+                if let Some(arch) = &software.arch {
+                    res += &fmt_detail_line("arch :", arch);
+                }
+                res += &fmt_detail_line("upstream :", &software.upstream);
+                if let Some(main_program) = &software.main_program {
+                    res += &fmt_detail_line("main :", main_program);
+                }
+                if let Some(tags) = &software.tags {
+                    res += &fmt_detail_line("tags :", &tags.join(", "));
+                }
+                if let Some(alias) = &software.alias {
+                    res += &fmt_detail_line("alias :", &alias.join(", "));
+                }
+            }
+            if let Some(kind) = &self.kind {
+                res += &fmt_detail_line("kind :", &kind.to_string());
+            }
+            res += &fmt_detail_line("path :", &p2s!(self.install_path));
+            if let Some(installed_at) = self.installed_at {
+                res += &fmt_detail_line("installed :", &fmt_timestamp(installed_at));
+            }
+            if let Some(usage) = &self.usage {
+                res += &fmt_detail_line("size :", &fmt_size(usage.total()));
+            }
+            if let Some(record) = &self.record {
+                res += &fmt_detail_line("reason :", &record.reason.to_string());
+                res += &fmt_detail_line("source :", &record.source.to_string());
+                res += &fmt_detail_line("by ept :", &record.ept_version);
+            }
+        }
+
+        if releases && !info.releases.is_empty() {
+            let versions: Vec<String> = info.releases.iter().map(|r| r.version.clone()).collect();
+            res += &fmt_releases_line(&versions);
+        }
+
+        res
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ListFilter {
+    pub scope: Option<String>,
+    pub category: Option<String>,
+    pub upgradable: bool,
+    pub pinned: bool,
+    // 仅显示无效的安装目录，否则跳过它们
+    pub broken: bool,
+    pub from_mirror: Option<String>,
+}
+
+impl ListFilter {
+    pub fn matches(&self, entry: &ListEntry) -> bool {
+        if let Some(scope) = &self.scope {
+            if !entry.scope.eq_ignore_ascii_case(scope) {
+                return false;
+            }
+        }
+        let info = if let Some(info) = &entry.info {
+            info
+        } else {
+            return self.broken;
+        };
+        if self.broken {
+            return false;
+        }
+        if let Some(category) = &self.category {
+            let matched = info
+                .software
+                .as_ref()
+                .map(|s| s.category.eq_ignore_ascii_case(category))
+                .unwrap_or(false);
+            if !matched {
+                return false;
+            }
+        }
+        if self.upgradable && entry.upgradable.is_none() {
+            return false;
+        }
+        if self.pinned && entry.pin.is_none() {
+            return false;
+        }
+        // 按照安装记录中的来源筛选，而不是当前提供该包的镜像源
+        if let Some(mirror) = &self.from_mirror {
+            let matched = entry
+                .record
+                .as_ref()
+                .map(|record| match &record.source {
+                    InstallSource::Mirror { mirror: m, .. } => m == mirror,
+                    _ => false,
+                })
+                .unwrap_or(false);
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[test]
+fn test_list_filter() {
+    use crate::types::info::InfoDiff;
+    let info = Info {
+        name: "VSCode".to_string(),
+        template: "Software".to_string(),
+        license: None,
+        icon: None,
+        local: Some(InfoDiff {
+            version: "1.75.4.0".to_string(),
+            authors: Vec::new(),
+        }),
+        online: None,
+        software: None,
+        releases: Vec::new(),
+        mirror: Some("official".to_string()),
+        installed: None,
+    };
+    let entry = ListEntry {
+        scope: "Microsoft".to_string(),
+        name: "VSCode".to_string(),
+        info: Some(info),
+        broken_reason: None,
+        install_path: PathBuf::from("apps/Microsoft/VSCode"),
+        pin: None,
+        upgradable: Some("1.76.0.0".to_string()),
+        installed_at: None,
+        usage: None,
+        kind: None,
+        record: Some(InstallRecord::new(
+            crate::types::install_record::InstallReason::Explicit,
+            InstallSource::Mirror {
+                mirror: "official".to_string(),
+                file_name: "VSCode_1.75.4.0_Cno.nep".to_string(),
+            },
+        )),
+    };
+    let broken = ListEntry {
+        name: "Broken".to_string(),
+        info: None,
+        broken_reason: Some("missing '.nep_context'".to_string()),
+        upgradable: None,
+        ..entry.clone()
+    };
+
+    let filter = ListFilter::default();
+    assert!(filter.matches(&entry));
+    assert!(!filter.matches(&broken));
+    let filter = ListFilter {
+        broken: true,
+        ..Default::default()
+    };
+    assert!(!filter.matches(&entry));
+    assert!(filter.matches(&broken));
+
+    assert!(ListFilter {
+        scope: Some("microsoft".to_string()),
+        upgradable: true,
+        from_mirror: Some("official".to_string()),
+        ..Default::default()
+    }
+    .matches(&entry));
+    assert!(!ListFilter {
+        pinned: true,
+        ..Default::default()
+    }
+    .matches(&entry));
+    assert!(!ListFilter {
+        category: Some("办公编辑".to_string()),
+        ..Default::default()
+    }
+    .matches(&entry));
+    assert!(!ListFilter {
+        from_mirror: Some("mock-server".to_string()),
+        ..Default::default()
+    }
+    .matches(&entry));
+    // 当前镜像源提供该包，但安装来源不是镜像源
+    let local = ListEntry {
+        record: Some(InstallRecord::new(
+            crate::types::install_record::InstallReason::Explicit,
+            InstallSource::Local {
+                path: "VSCode_1.75.4.0_Cno.nep".to_string(),
+            },
+        )),
+        ..entry.clone()
+    };
+    assert!(!ListFilter {
+        from_mirror: Some("official".to_string()),
+        ..Default::default()
+    }
+    .matches(&local));
+
+    assert_eq!(ListSortBy::parse("Size").unwrap(), ListSortBy::Size);
+    assert!(ListSortBy::parse("version").is_err());
+}
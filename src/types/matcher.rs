@@ -9,7 +9,7 @@ use crate::utils::{format_path, is_url};
 
 lazy_static! {
     static ref PACKAGE_MATCHER_REGEX: Regex =
-        Regex::new(r"^(([^/]+/)?[^/]+/)?[^/@]+(@[\^\*\w\.-]+)?$").unwrap();
+        Regex::new(r#"^(([^/]+/)?[^/]+/)?[^/@]+(@[\^\*=~<>,"\w\.-]+)?$"#).unwrap();
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -193,4 +193,13 @@ fn test_parse_package_input_enum() {
             version_req: Some(VersionReq::parse("1.1.4").unwrap())
         })
     );
+    assert_eq!(
+        PackageInputEnum::parse("Microsoft/VSCode@=1.1.4".to_string(), false, false).unwrap(),
+        PackageInputEnum::PackageMatcher(PackageMatcher {
+            name: "VSCode".to_string(),
+            scope: Some("Microsoft".to_string()),
+            mirror: None,
+            version_req: Some(VersionReq::parse("=1.1.4").unwrap())
+        })
+    );
 }
@@ -1,17 +1,31 @@
 pub mod author;
 pub mod cfg;
 pub mod cli;
+pub mod delta;
+pub mod diff;
+pub mod doctor;
+pub mod du;
 pub mod extended_semver;
+pub mod history;
 pub mod info;
+pub mod inspect;
+pub mod install_record;
 pub mod interpretable;
+pub mod lint;
+pub mod list;
 pub mod matcher;
 pub mod meta;
 pub mod mirror;
 pub mod mixed_fs;
+pub mod pack;
 pub mod package;
 pub mod permissions;
+pub mod pin;
+pub mod policy;
+pub mod scaffold;
 pub mod signature;
 pub mod software;
+pub mod source_map;
 pub mod steps;
 pub mod uninstall_reg_entry;
 pub mod verifiable;
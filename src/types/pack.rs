@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use std::env::var;
+
+use crate::utils::cfg::get_config;
+
+// 未提供 SOURCE_DATE_EPOCH 时可复现打包使用的修改时间，即 2000-01-01 00:00:00 UTC
+const DEFAULT_SOURCE_DATE_EPOCH: u64 = 946684800;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackOptions {
+    // 可复现打包时写入全部条目的修改时间，为 None 时保留文件系统中的元数据
+    pub reproducible_mtime: Option<u64>,
+    // zstd 压缩等级，为 0 时使用 zstd 的缺省等级
+    pub level: i32,
+    // 压缩线程数，为 0 时使用全部逻辑核心
+    pub threads: u32,
+    pub long_window: bool,
+    // 生成增量包时使用的基础版本 nep
+    pub delta_from: Option<String>,
+}
+
+impl PackOptions {
+    // 命令行未提供的参数使用配置中 [pack] 表的值
+    pub fn new(reproducible: bool, level: Option<i32>, threads: Option<u32>) -> Result<Self> {
+        let reproducible_mtime = if reproducible {
+            Some(read_source_date_epoch()?)
+        } else {
+            None
+        };
+        let cfg = get_config().pack;
+        let level = level.unwrap_or(cfg.level);
+        let level_range = zstd::compression_level_range();
+        if !level_range.contains(&level) {
+            return Err(anyhow!(
+                "Error:Compression level should be in range {start}..={end}, got '{level}'",
+                start = level_range.start(),
+                end = level_range.end()
+            ));
+        }
+        Ok(Self {
+            reproducible_mtime,
+            level,
+            threads: threads.unwrap_or(cfg.threads),
+            long_window: cfg.long_window,
+            delta_from: None,
+        })
+    }
+
+    pub fn is_reproducible(&self) -> bool {
+        self.reproducible_mtime.is_some()
+    }
+}
+
+fn read_source_date_epoch() -> Result<u64> {
+    if let Ok(raw) = var("SOURCE_DATE_EPOCH") {
+        raw.trim().parse().map_err(|e| {
+            anyhow!(
+                "Error:Invalid SOURCE_DATE_EPOCH '{raw}', expect unix timestamp in seconds : {e}"
+            )
+        })
+    } else {
+        Ok(DEFAULT_SOURCE_DATE_EPOCH)
+    }
+}
+
+#[test]
+fn test_pack_options() {
+    assert!(!PackOptions::default().is_reproducible());
+    let options = PackOptions::new(true, Some(19), Some(2)).unwrap();
+    if var("SOURCE_DATE_EPOCH").is_err() {
+        assert_eq!(options.reproducible_mtime, Some(DEFAULT_SOURCE_DATE_EPOCH));
+    }
+    assert_eq!(options.level, 19);
+    assert_eq!(options.threads, 2);
+
+    let cfg = get_config().pack;
+    let options = PackOptions::new(false, None, None).unwrap();
+    assert_eq!(options.level, cfg.level);
+    assert!(PackOptions::new(false, Some(23), None).is_err());
+}
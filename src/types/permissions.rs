@@ -45,6 +45,8 @@ pub enum PermissionKey {
     notify_toast,
     /// 杀死进程
     process_kill,
+    /// 读取环境变量
+    env_read,
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, TS)]
 #[ts(export)]
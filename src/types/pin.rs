@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::extended_semver::ExSemVer;
+
+// 锁定的包，不提供 version_req 时表示完全冻结
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PinNode {
+    pub version_req: Option<String>,
+}
+
+impl PinNode {
+    pub fn get_version_req(&self) -> Result<Option<VersionReq>> {
+        if let Some(req) = &self.version_req {
+            let parsed = VersionReq::parse(req)
+                .map_err(|e| anyhow!("Error:Failed to parse pinned version req '{req}' : {e}"))?;
+            Ok(Some(parsed))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // 判断是否允许更新到指定版本
+    pub fn allows(&self, version: &ExSemVer) -> Result<bool> {
+        Ok(self
+            .get_version_req()?
+            .map(|req| req.matches(&version.semver_instance))
+            .unwrap_or(false))
+    }
+
+    pub fn describe(&self) -> String {
+        self.version_req
+            .clone()
+            .map(|req| format!("pinned to '{req}'"))
+            .unwrap_or("held".to_string())
+    }
+}
+
+// 键为 SCOPE/NAME
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Pins {
+    pub pins: BTreeMap<String, PinNode>,
+}
+
+#[test]
+fn test_pin_node() {
+    let held = PinNode { version_req: None };
+    assert!(!held
+        .allows(&ExSemVer::parse(&"1.0.0.0".to_string()).unwrap())
+        .unwrap());
+    assert_eq!(held.describe(), "held".to_string());
+
+    let pinned = PinNode {
+        version_req: Some("~1.75".to_string()),
+    };
+    assert!(pinned
+        .allows(&ExSemVer::parse(&"1.75.4.2".to_string()).unwrap())
+        .unwrap());
+    assert!(!pinned
+        .allows(&ExSemVer::parse(&"1.76.0.0".to_string()).unwrap())
+        .unwrap());
+    assert!(PinNode {
+        version_req: Some("~1.x.x.x".to_string())
+    }
+    .get_version_req()
+    .is_err());
+}
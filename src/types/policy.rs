@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use super::{extended_semver::ExSemVer, mirror::MirrorPkgSoftwareRelease};
+
+// 更新通道，决定允许自动更新到哪一段版本号
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatePolicy {
+    // 允许任意版本
+    Major,
+    // 仅允许主版本号不变的更新
+    Minor,
+    // 仅允许主版本号、次版本号不变的更新
+    Patch,
+    // 仅允许保留号的更新，即同一上游版本的重新打包
+    Reserved,
+}
+
+impl Display for UpdatePolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UpdatePolicy::Major => "major",
+            UpdatePolicy::Minor => "minor",
+            UpdatePolicy::Patch => "patch",
+            UpdatePolicy::Reserved => "reserved",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl UpdatePolicy {
+    pub fn allows(&self, local: &ExSemVer, target: &ExSemVer) -> bool {
+        match self {
+            UpdatePolicy::Major => true,
+            UpdatePolicy::Minor => local.major == target.major,
+            UpdatePolicy::Patch => local.major == target.major && local.minor == target.minor,
+            UpdatePolicy::Reserved => {
+                local.major == target.major
+                    && local.minor == target.minor
+                    && local.patch == target.patch
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PolicyNode {
+    pub policy: UpdatePolicy,
+    pub allow_prerelease: bool,
+}
+
+impl PolicyNode {
+    pub fn allows(&self, local: &ExSemVer, target: &ExSemVer) -> bool {
+        if !self.allow_prerelease && !target.pre.is_empty() {
+            return false;
+        }
+        self.policy.allows(local, target)
+    }
+
+    pub fn describe(&self) -> String {
+        let prerelease_tip = if self.allow_prerelease {
+            " with prerelease"
+        } else {
+            ""
+        };
+        format!("policy '{}'{prerelease_tip}", self.policy)
+    }
+}
+
+// 键为 SCOPE/NAME
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Policies {
+    pub policies: BTreeMap<String, PolicyNode>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UpdateDecision {
+    // 可以更新到的版本
+    Update(MirrorPkgSoftwareRelease),
+    // 存在更新的版本但是被锁定或更新策略拦截，附带原因
+    Held(String),
+    UpToDate,
+}
+
+#[test]
+fn test_update_policy() {
+    let v = |s: &str| ExSemVer::parse(&s.to_string()).unwrap();
+    let local = v("1.75.4.0");
+
+    assert!(UpdatePolicy::Major.allows(&local, &v("2.0.0.0")));
+    assert!(!UpdatePolicy::Minor.allows(&local, &v("2.0.0.0")));
+    assert!(UpdatePolicy::Minor.allows(&local, &v("1.76.0.0")));
+    assert!(!UpdatePolicy::Patch.allows(&local, &v("1.76.0.0")));
+    assert!(UpdatePolicy::Patch.allows(&local, &v("1.75.5.0")));
+    assert!(!UpdatePolicy::Reserved.allows(&local, &v("1.75.5.0")));
+    assert!(UpdatePolicy::Reserved.allows(&local, &v("1.75.4.1")));
+
+    let node = PolicyNode {
+        policy: UpdatePolicy::Major,
+        allow_prerelease: false,
+    };
+    assert!(!node.allows(&local, &v("1.76.0.0-beta.1")));
+    assert!(PolicyNode {
+        policy: UpdatePolicy::Major,
+        allow_prerelease: true,
+    }
+    .allows(&local, &v("1.76.0.0-beta.1")));
+}
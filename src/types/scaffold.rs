@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+// 可选生成的工作流骨架
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScaffoldWorkflow {
+    Update,
+    Remove,
+    Expand,
+}
+
+impl ScaffoldWorkflow {
+    pub fn all() -> Vec<Self> {
+        vec![
+            ScaffoldWorkflow::Update,
+            ScaffoldWorkflow::Remove,
+            ScaffoldWorkflow::Expand,
+        ]
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.to_lowercase().as_str() {
+            "update" => Ok(ScaffoldWorkflow::Update),
+            "remove" => Ok(ScaffoldWorkflow::Remove),
+            "expand" => Ok(ScaffoldWorkflow::Expand),
+            _ => Err(anyhow!(
+                "Error:Invalid workflow '{raw}', expect 'update', 'remove' or 'expand'"
+            )),
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{self}.toml")
+    }
+}
+
+impl Display for ScaffoldWorkflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ScaffoldWorkflow::Update => "update",
+            ScaffoldWorkflow::Remove => "remove",
+            ScaffoldWorkflow::Expand => "expand",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// 命令行提供的字段，未提供的字段需要询问用户或使用缺省值
+#[derive(Clone, Debug, Default)]
+pub struct ScaffoldDraft {
+    // 程序文件所在的目录
+    pub source: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub authors: Option<Vec<String>>,
+    pub license: Option<String>,
+    pub scope: Option<String>,
+    pub upstream: Option<String>,
+    pub category: Option<String>,
+    pub language: Option<String>,
+    pub main_program: Option<String>,
+    pub workflows: Option<Vec<ScaffoldWorkflow>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScaffoldOptions {
+    pub source: String,
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    pub license: Option<String>,
+    pub scope: String,
+    pub upstream: String,
+    pub category: String,
+    pub language: String,
+    // 相对于程序目录的主程序路径，为 None 时不生成快捷方式和 PATH 步骤
+    pub main_program: Option<String>,
+    pub workflows: Vec<ScaffoldWorkflow>,
+}
+
+#[test]
+fn test_scaffold_workflow() {
+    assert_eq!(
+        ScaffoldWorkflow::parse("Update").unwrap(),
+        ScaffoldWorkflow::Update
+    );
+    assert_eq!(ScaffoldWorkflow::Expand.file_name(), "expand.toml");
+    assert!(ScaffoldWorkflow::parse("setup").is_err());
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, ops::Range};
+use toml_edit::{ImDocument, TableLike};
+
+// TOML 源文件中各个键路径所在的位置，用于在错误信息中指出行列与代码片段
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceMap {
+    pub file: String,
+    text: String,
+    // 键路径到位置的映射，值指向值本身，表指向表头中的键名
+    spans: HashMap<String, Range<usize>>,
+}
+
+fn collect_spans(prefix: &str, table: &dyn TableLike, res: &mut HashMap<String, Range<usize>>) {
+    for (key, item) in table.iter() {
+        let key_path = if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        if let Some(sub) = item.as_table_like() {
+            if let Some(span) = table.key(key).and_then(|k| k.span()) {
+                res.insert(key_path.clone(), span);
+            }
+            collect_spans(&key_path, sub, res);
+            continue;
+        }
+        if let Some(span) = item.span() {
+            res.insert(key_path.clone(), span);
+        }
+        if let Some(arr) = item.as_array() {
+            for (i, val) in arr.iter().enumerate() {
+                if let Some(span) = val.span() {
+                    res.insert(format!("{key_path}[{i}]"), span);
+                }
+            }
+        }
+    }
+}
+
+impl SourceMap {
+    // 解析失败时返回空的映射，语法错误由 toml 反序列化负责报告
+    pub fn new(file: &str, text: &str) -> Self {
+        let mut spans = HashMap::new();
+        if let Ok(doc) = ImDocument::parse(text) {
+            collect_spans("", doc.as_table(), &mut spans);
+        }
+        Self {
+            file: file.to_string(),
+            text: text.to_string(),
+            spans,
+        }
+    }
+
+    pub fn span(&self, key_path: &str) -> Option<Range<usize>> {
+        self.spans.get(key_path).cloned()
+    }
+
+    // 将字节偏移转换为从 1 开始的行号与列号
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.text.len());
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+
+    pub fn position(&self, span: &Range<usize>) -> String {
+        let (line, column) = self.line_col(span.start);
+        format!("{}:{line}:{column}", self.file)
+    }
+
+    // 生成位置与出错行的代码片段，跨行的位置仅标注首行
+    pub fn describe(&self, span: Range<usize>) -> String {
+        let (line, column) = self.line_col(span.start);
+        let line_text = self.text.lines().nth(line - 1).unwrap_or("");
+        let line_text = line_text.trim_end_matches('\r');
+        let rest = line_text.chars().count().saturating_sub(column - 1);
+        let len = self.text[span.start.min(self.text.len())..span.end.min(self.text.len())]
+            .lines()
+            .next()
+            .map(|s| s.chars().count())
+            .unwrap_or(0)
+            .clamp(1, rest.max(1));
+        let gutter = " ".repeat(line.to_string().len());
+        format!(
+            "{gutter}--> {position}\n{gutter} |\n{line} | {line_text}\n{gutter} | {pad}{carets}",
+            position = self.position(&span),
+            pad = " ".repeat(column - 1),
+            carets = "^".repeat(len)
+        )
+    }
+
+    // 为错误信息附加键路径所在的位置，找不到键路径时原样返回
+    pub fn annotate(&self, message: String, key_path: &str) -> String {
+        if let Some(span) = self.span(key_path) {
+            format!(
+                "{message}\n{snippet}",
+                message = message.trim_end(),
+                snippet = self.describe(span)
+            )
+        } else {
+            message
+        }
+    }
+
+    // 反序列化 toml::Value 得到的错误不含位置，根据错误中的字段名定位到具体的键
+    pub fn annotate_value_error(&self, head: String, key: &str, e: &toml::de::Error) -> String {
+        let raw = e.to_string();
+        let mut lines = raw.lines();
+        let mut message = lines.next().unwrap_or_default().to_string();
+        let mut key_path = key.to_string();
+        if let Some(field) = lines.find_map(|l| l.strip_prefix("in `")?.strip_suffix('`')) {
+            message += &format!(" in field '{field}'");
+            let field_path = if key.is_empty() {
+                field.to_string()
+            } else {
+                format!("{key}.{field}")
+            };
+            if self.span(&field_path).is_some() {
+                key_path = field_path;
+            }
+        }
+        self.annotate(format!("{head} : {message}"), &key_path)
+    }
+
+    // 将 toml 语法或反序列化错误转换为带位置的信息
+    pub fn annotate_toml_error(&self, head: &str, e: &toml::de::Error) -> String {
+        let message = format!("{head} : {}", e.message().trim_end());
+        if let Some(span) = e.span() {
+            format!("{message}\n{}", self.describe(span))
+        } else {
+            message
+        }
+    }
+}
+
+#[test]
+fn test_source_map() {
+    let text = "nep = \"0\"\n\n[package]\nname = \"VSCode\"\nauthors = [\"Cno\", \"Microsoft\"]\n\n[run]\nstep = \"Execute\"\n    command = 'a\\b'\n";
+    let map = SourceMap::new("workflows/setup.toml", text);
+    assert_eq!(
+        map.position(&map.span("nep").unwrap()),
+        "workflows/setup.toml:1:7"
+    );
+    assert_eq!(
+        map.position(&map.span("package").unwrap()),
+        "workflows/setup.toml:3:2"
+    );
+    assert_eq!(
+        map.position(&map.span("package.authors[1]").unwrap()),
+        "workflows/setup.toml:5:19"
+    );
+    assert_eq!(
+        map.annotate("Error:Backslash is not allowed".to_string(), "run.command"),
+        "Error:Backslash is not allowed\n --> workflows/setup.toml:9:15\n  |\n9 |     command = 'a\\b'\n  |               ^^^^^"
+    );
+    assert_eq!(
+        map.annotate("Error:Unknown".to_string(), "run.at"),
+        "Error:Unknown"
+    );
+
+    // 根据字段名定位
+    #[derive(serde::Deserialize, Debug)]
+    struct Node {
+        _step: String,
+        _timeout: u32,
+    }
+    let text = "[wait]\n_step = \"Wait\"\n_timeout = \"1s\"\n";
+    let map = SourceMap::new("setup.toml", text);
+    let val: toml::Value = toml::from_str::<toml::Table>(text).unwrap()["wait"].clone();
+    let e = val.try_into::<Node>().unwrap_err();
+    let message = map.annotate_value_error("Error:Can't parse".to_string(), "wait", &e);
+    assert!(message.starts_with("Error:Can't parse : invalid type"));
+    assert!(message.contains("in field '_timeout'"));
+    assert!(message.contains("--> setup.toml:3:12"));
+
+    // 语法错误
+    let text = "[run]\nstep = \n";
+    let e = toml::from_str::<toml::Value>(text).unwrap_err();
+    let map = SourceMap::new("setup.toml", text);
+    assert!(map
+        .annotate_toml_error("Error:Invalid toml file", &e)
+        .contains("--> setup.toml:2:8"));
+}
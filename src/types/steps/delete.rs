@@ -14,8 +14,18 @@ use crate::{
     },
 };
 use anyhow::{anyhow, Result};
+#[cfg(windows)]
 use force_delete_win::force_delete_file_folder;
 use serde::{Deserialize, Serialize};
+
+// 其他平台不存在文件被占用而无法删除的情况，直接删除
+#[cfg(not(windows))]
+fn force_delete_file_folder(target: OsString) -> bool {
+    std::fs::remove_dir_all(&target)
+        .or_else(|_| std::fs::remove_file(&target))
+        .is_ok()
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct StepDelete {
     /// 删除目标路径，支持相对路径和绝对路径，支持使用通配符。
@@ -64,10 +74,15 @@ impl TStep for StepDelete {
         //- 删除文件/文件夹。
         let force = self.force.unwrap_or(false);
         if contains_wild_match(&self.at) {
-            for target in parse_wild_match(self.at, &cx.located)? {
+            let matched = parse_wild_match(self.at, &cx.located)?;
+            for target in &matched {
+                cx.check_fs_access(PermissionKey::fs_write, &p2s!(target))?;
+            }
+            for target in matched {
                 delete(&p2s!(target), force)?;
             }
         } else {
+            cx.check_fs_access(PermissionKey::fs_write, &self.at)?;
             delete(&self.at, force)?;
         }
 
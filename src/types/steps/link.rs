@@ -9,17 +9,11 @@ use crate::utils::fs::{count_sub_files, try_recycle};
 use crate::utils::is_starts_with_inner_value;
 use crate::{log, p2s, utils::path::parse_relative_path_with_located};
 use anyhow::{anyhow, Result};
-use mslnk::ShellLink;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs::create_dir_all;
 use std::path::Path;
-use std::ptr::null_mut;
-use winapi::shared::minwindef::{LPARAM, WPARAM};
-use winapi::um::winuser::{
-    SendMessageTimeoutA, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
-};
 
 lazy_static! {
     static ref TARGET_RE: Regex = Regex::new(r"^(([^/\\]+)/)?([^/\\]+)$").unwrap();
@@ -59,10 +53,50 @@ fn parse_target(name: &String, base: &String) -> Result<(String, bool)> {
     Ok(target)
 }
 
-fn create_shortcut(sl: &ShellLink, name: &String, base: &String) -> Result<()> {
+#[cfg(windows)]
+fn write_shortcut(
+    source: &String,
+    icon: Option<String>,
+    args: Option<String>,
+    target: &String,
+) -> Result<()> {
+    use mslnk::ShellLink;
+    let mut sl = ShellLink::new(source)
+        .map_err(|_| anyhow!("Error(Link):Can't find source file '{source}'"))?;
+    if icon.is_some() {
+        sl.set_icon_location(icon);
+    }
+    if args.is_some() {
+        sl.set_arguments(args);
+    }
+    sl.create_lnk(target)
+        .map_err(|err| anyhow!("Error(Link):Can't create shortcut {target} : {err}"))
+}
+
+// 其他平台无法生成 Windows 快捷方式，写入记录源文件路径的占位文件
+#[cfg(not(windows))]
+fn write_shortcut(
+    source: &String,
+    _icon: Option<String>,
+    _args: Option<String>,
+    target: &String,
+) -> Result<()> {
+    if !Path::new(source).exists() {
+        return Err(anyhow!("Error(Link):Can't find source file '{source}'"));
+    }
+    std::fs::write(target, source)
+        .map_err(|err| anyhow!("Error(Link):Can't create shortcut {target} : {err}"))
+}
+
+fn create_shortcut(
+    source: &String,
+    icon: &Option<String>,
+    args: &Option<String>,
+    name: &String,
+    base: &String,
+) -> Result<()> {
     let (target, _) = parse_target(name, base)?;
-    sl.create_lnk(&target)
-        .map_err(|err| anyhow!("Error(Link):Can't create shortcut {target} : {err}"))?;
+    write_shortcut(source, icon.to_owned(), args.to_owned(), &target)?;
     log!("Info(Link):Created shortcut at '{target}'");
     Ok(())
 }
@@ -85,7 +119,14 @@ fn delete_shortcut(name: &String, base: &String) -> Result<()> {
     Ok(())
 }
 
+#[cfg(windows)]
 fn update_start_menu() {
+    use std::ptr::null_mut;
+    use winapi::shared::minwindef::{LPARAM, WPARAM};
+    use winapi::um::winuser::{
+        SendMessageTimeoutA, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+    };
+
     // 发送全局广播
     let result = unsafe {
         SendMessageTimeoutA(
@@ -103,6 +144,10 @@ fn update_start_menu() {
     }
 }
 
+// 其他平台没有开始菜单，无需广播
+#[cfg(not(windows))]
+fn update_start_menu() {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct StepLink {
     /// 源文件路径，支持相对路径和绝对路径。
@@ -152,34 +197,42 @@ impl TStep for StepLink {
         let abs_clear_source_path =
             parse_relative_path_with_located(&self.source_file, &cx.located);
         let abs_clear_source = p2s!(abs_clear_source_path);
-
-        // 创建实例
-        let mut sl = ShellLink::new(&abs_clear_source)
-            .map_err(|_| anyhow!("Error(Link):Can't find source file '{abs_clear_source}'"))?;
-
-        // 填充额外参数
-        if self.target_icon.is_some() {
-            sl.set_icon_location(self.target_icon.map(|relative_icon| {
-                p2s!(parse_relative_path_with_located(
-                    &relative_icon,
-                    &cx.located
-                ))
-            }));
-        }
-        if self.target_args.is_some() {
-            sl.set_arguments(self.target_args);
+        if !abs_clear_source_path.exists() {
+            return Err(anyhow!(
+                "Error(Link):Can't find source file '{abs_clear_source}'"
+            ));
         }
 
+        // 解析额外参数
+        let icon = self.target_icon.map(|relative_icon| {
+            p2s!(parse_relative_path_with_located(
+                &relative_icon,
+                &cx.located
+            ))
+        });
+
         // 分流
         let set: HashSet<String> =
             HashSet::from_iter(self.at.clone().unwrap_or(vec!["Desktop".to_string()]));
         if set.contains("Desktop") {
             log!("Info(Link):Adding shortcut '{target_name}' to desktop");
-            create_shortcut(&sl, &target_name, &env_desktop())?;
+            create_shortcut(
+                &abs_clear_source,
+                &icon,
+                &self.target_args,
+                &target_name,
+                &env_desktop(),
+            )?;
         }
         if set.contains("StartMenu") {
             log!("Info(Link):Adding shortcut '{target_name}' to start menu");
-            create_shortcut(&sl, &target_name, &env_start_menu())?;
+            create_shortcut(
+                &abs_clear_source,
+                &icon,
+                &self.target_args,
+                &target_name,
+                &env_start_menu(),
+            )?;
             update_start_menu();
         }
 
@@ -43,13 +43,17 @@ pub trait TStep: Generalizable + Interpretable {
     fn verify_step(&self, ctx: &VerifyStepCtx) -> Result<()>;
 }
 
-fn toml_try_into<'de, T>(key: String, val: Value) -> Result<T>
+fn toml_try_into<'de, T>(key: String, val: Value, source: &SourceMap) -> Result<T>
 where
     T: de::Deserialize<'de>,
 {
     val.to_owned().try_into().map_err(|err| {
         let step = val["step"].as_str().unwrap_or("unknown step");
-        anyhow!("Error:Can't parse workflow node '{key}' into step '{step}' : {err}")
+        anyhow!(source.annotate_value_error(
+            format!("Error:Can't parse workflow node '{key}' into step '{step}'"),
+            &key,
+            &err
+        ))
     })
 }
 
@@ -63,15 +67,15 @@ macro_rules! def_enum_step {
         }
 
         impl Step {
-            pub fn try_from_kv(key:String,val:Value)->Result<Step>{
+            pub fn try_from_kv(key:String,val:Value,source:&SourceMap)->Result<Step>{
                 // 读取步骤名称
                 let step=String::from("Step")+val["step"].as_str().unwrap();
 
                 // 根据步骤名称解析步骤体
                 let res=match step.as_str() {
-                    $( stringify!($x) => Step::$x(toml_try_into(key,val)?) ),* ,
+                    $( stringify!($x) => Step::$x(toml_try_into(key,val,source)?) ),* ,
                     _ => {
-                        return Err(anyhow!("Error:Unknown step '{step}'"));
+                        return Err(anyhow!(source.annotate(format!("Error:Unknown step '{step}'"),&format!("{key}.step"))));
                     },
                 };
                 Ok(res)
@@ -148,4 +152,5 @@ pub use self::wait::StepWait;
 
 use super::interpretable::Interpretable;
 use super::mixed_fs::MixedFS;
+use super::source_map::SourceMap;
 use super::workflow::WorkflowContext;
@@ -72,11 +72,17 @@ impl TStep for StepMove {
     fn run(self, cx: &mut WorkflowContext) -> Result<i32> {
         //- 移动文件/文件夹。
         let overwrite = self.overwrite.unwrap_or(false);
+        cx.check_fs_access(PermissionKey::fs_write, &self.to)?;
         if contains_wild_match(&self.from) {
-            for from in parse_wild_match(self.from, &cx.located)? {
+            let matched = parse_wild_match(self.from, &cx.located)?;
+            for from in &matched {
+                cx.check_fs_access(PermissionKey::fs_write, &p2s!(from))?;
+            }
+            for from in matched {
                 mv(&p2s!(from), &self.to, &cx.located, overwrite, true)?;
             }
         } else {
+            cx.check_fs_access(PermissionKey::fs_write, &self.from)?;
             mv(&self.from, &self.to, &cx.located, overwrite, false)?;
         }
 
@@ -47,8 +47,9 @@ fn new_dir(at: &String) -> Result<()> {
 }
 
 impl TStep for StepNew {
-    fn run(self, _: &mut WorkflowContext) -> Result<i32> {
+    fn run(self, cx: &mut WorkflowContext) -> Result<i32> {
         //- 新建文件/文件夹。
+        cx.check_fs_access(PermissionKey::fs_write, &self.at)?;
         // 检测是否存在
         let p = Path::new(&self.at);
         if p.exists() {
@@ -4,6 +4,7 @@ use crate::types::interpretable::Interpretable;
 use crate::types::mixed_fs::MixedFS;
 use crate::types::permissions::{Generalizable, Permission, PermissionKey, PermissionLevel};
 use crate::types::workflow::WorkflowContext;
+use crate::utils::env::get_sandbox_root;
 use crate::utils::is_starts_with_inner_value;
 use crate::utils::{get_path_bin, path::parse_relative_path_with_located, term::ask_yn_in_step};
 use crate::{log, p2s};
@@ -12,13 +13,7 @@ use serde::{Deserialize, Serialize};
 use std::fs::{create_dir, remove_file, File};
 use std::io::Write;
 use std::path::Path;
-use std::ptr::null_mut;
 use which::which;
-use winapi::shared::minwindef::{LPARAM, WPARAM};
-use winapi::um::winuser::{
-    SendMessageTimeoutA, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
-};
-use winreg::{enums::*, RegKey};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct StepPath {
@@ -55,6 +50,11 @@ fn conflict_resolver(bin_abs: &String, stem: &String, scope: &String) -> String
         };
     }
 
+    // 沙箱中的入口不会暴露到系统 PATH，无需检查冲突
+    if get_sandbox_root().is_some() {
+        return origin;
+    }
+
     // 检查系统全局 PATH 冲突
     let which_res = which(stem);
     if let Ok(res) = which_res {
@@ -77,6 +77,23 @@ fn conflict_resolver(bin_abs: &String, stem: &String, scope: &String) -> String
 
 // 配置系统 PATH 变量；返回的 bool 表示是否发送了全局广播
 fn set_system_path(record: &str, is_add: bool) -> Result<bool> {
+    // 沙箱中不修改系统 PATH
+    if get_sandbox_root().is_some() {
+        log!("Debug(Path):Skip modifying system PATH for '{record}' in sandbox");
+        return Ok(false);
+    }
+    write_system_path(record, is_add)
+}
+
+#[cfg(windows)]
+fn write_system_path(record: &str, is_add: bool) -> Result<bool> {
+    use std::ptr::null_mut;
+    use winapi::shared::minwindef::{LPARAM, WPARAM};
+    use winapi::um::winuser::{
+        SendMessageTimeoutA, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+    };
+    use winreg::{enums::*, RegKey};
+
     // 转换 record 为反斜杠
     let record = record.replace('/', r"\");
     let record_str = record.as_str();
@@ -141,6 +158,14 @@ fn set_system_path(record: &str, is_add: bool) -> Result<bool> {
     Ok(true)
 }
 
+// 系统 PATH 保存在注册表中，其他平台仅支持在沙箱中运行
+#[cfg(not(windows))]
+fn write_system_path(record: &str, _is_add: bool) -> Result<bool> {
+    Err(anyhow!(
+        "Error(Path):Can't modify system PATH for '{record}' on this platform"
+    ))
+}
+
 impl TStep for StepPath {
     fn run(self, cx: &mut WorkflowContext) -> Result<i32> {
         //- 将可执行文件/文件夹暴露到 PATH 中：
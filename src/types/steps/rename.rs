@@ -11,6 +11,7 @@ use crate::{
     log, p2s,
     types::permissions::{Generalizable, Permission},
     utils::{
+        format_path,
         path::{parse_relative_path_with_located, split_parent},
         wild_match::contains_wild_match,
     },
@@ -78,6 +79,11 @@ fn rename(from: &String, to: &String, located: &String) -> Result<()> {
 impl TStep for StepRename {
     fn run(self, cx: &mut crate::types::workflow::WorkflowContext) -> Result<i32> {
         //- 重命名文件/文件夹。
+        cx.check_fs_access(PermissionKey::fs_write, &self.from)?;
+        cx.check_fs_access(
+            PermissionKey::fs_write,
+            &concat_to(&self.to, &self.from, &cx.located),
+        )?;
         rename(&self.from, &self.to, &cx.located)?;
         Ok(0)
     }
@@ -126,10 +132,14 @@ impl Interpretable for StepRename {
 
 impl Generalizable for StepRename {
     fn generalize_permissions(&self) -> Result<Vec<Permission>> {
+        // 重命名的目标与源位于同一目录
         Ok(vec![Permission {
             key: PermissionKey::fs_write,
             level: judge_perm_level(&self.from)?,
-            targets: vec![self.from.clone()],
+            targets: vec![
+                self.from.clone(),
+                format_path(&concat_to(&self.to, &self.from, &String::new())),
+            ],
         }])
     }
 }
@@ -202,6 +212,35 @@ fn test_rename() {
     .unwrap();
     assert!(Path::new("test/source/tools/steps/rename.rs").exists());
     assert!(!Path::new("test/source/types/steps/rename.rs").exists());
+
+    // 沙箱中重命名的目标同样需要在声明的范围内
+    cx.enable_sandbox(vec![Permission {
+        key: PermissionKey::fs_write,
+        level: crate::types::permissions::PermissionLevel::Normal,
+        targets: vec!["test/source/tools".to_string()],
+    }]);
+    assert!(StepRename {
+        from: "test/source/tools".to_string(),
+        to: "types".to_string(),
+    }
+    .run(&mut cx)
+    .is_err());
+    assert!(Path::new("test/source/tools").exists());
+    cx.enable_sandbox(
+        StepRename {
+            from: "test/source/tools".to_string(),
+            to: "types".to_string(),
+        }
+        .generalize_permissions()
+        .unwrap(),
+    );
+    StepRename {
+        from: "test/source/tools".to_string(),
+        to: "types".to_string(),
+    }
+    .run(&mut cx)
+    .unwrap();
+    assert!(Path::new("test/source/types").exists());
 }
 
 #[test]
@@ -8,9 +8,8 @@ use crate::types::{
     permissions::{Generalizable, PermissionLevel},
     workflow::WorkflowContext,
 };
-use anyhow::{anyhow, Ok, Result};
+use anyhow::{Ok, Result};
 use serde::{Deserialize, Serialize};
-use winrt_notification::{Duration, Sound, Toast};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct StepToast {
@@ -22,22 +21,34 @@ pub struct StepToast {
     pub content: String,
 }
 
+#[cfg(windows)]
+fn show_toast(title: &String, content: &String) -> Result<()> {
+    use anyhow::anyhow;
+    use winrt_notification::{Duration, Sound, Toast};
+    Toast::new(Toast::POWERSHELL_APP_ID)
+        .title(title)
+        .text1(content)
+        .sound(Some(Sound::SMS))
+        .duration(Duration::Short)
+        .show()
+        .map_err(|e| {
+            anyhow!(
+                "Error(Toast):Failed to send toast : '{e}' (title : '{title}', content : '{content}')"
+            )
+        })
+}
+
+// 其他平台不弹出通知，仅记录日志
+#[cfg(not(windows))]
+fn show_toast(_: &String, _: &String) -> Result<()> {
+    log!("Warning(Toast):Toast is not supported on this platform, skipping");
+    Ok(())
+}
+
 impl TStep for StepToast {
     fn run(self, _: &mut WorkflowContext) -> Result<i32> {
         //- 弹出消息通知。
-        Toast::new(Toast::POWERSHELL_APP_ID)
-            .title(&self.title)
-            .text1(&self.content)
-            .sound(Some(Sound::SMS))
-            .duration(Duration::Short)
-            .show()
-            .map_err(|e| {
-                anyhow!(
-                    "Error(Toast):Failed to send toast : '{e}' (title : '{t}', content : '{c}')",
-                    t = self.title,
-                    c = self.content
-                )
-            })?;
+        show_toast(&self.title, &self.content)?;
 
         log!(
             "Log(Toast):Sent toast with title : '{t}', content : '{c}'",
@@ -1,4 +1,4 @@
-use std::{env::current_dir, process::Child};
+use std::{env::current_dir, path::Path, process::Child};
 
 use super::mixed_fs::MixedFS;
 use super::steps::VerifyStepCtx;
@@ -10,9 +10,16 @@ use crate::utils::{
     conditions::{get_permissions_from_conditions, verify_conditions},
     term::read_console,
 };
-use crate::{p2s, types::permissions::Permission};
+use crate::{
+    executor::values_replacer,
+    p2s,
+    types::permissions::{Permission, PermissionKey},
+    utils::{path::parse_relative_path_with_located, wild_match::contains_wild_match},
+};
 use anyhow::{anyhow, Result};
+use path_clean::PathClean;
 use serde::{Deserialize, Serialize};
+use wildmatch::WildMatch;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct WorkflowHeader {
@@ -144,6 +151,31 @@ pub struct WorkflowContext {
     pub pkg: GlobalPackage,
     pub async_execution_handlers: Vec<(String, Child, bool)>, // 命令，handler，是否被抛弃
     pub exit_code: i32,
+    // 运行时沙箱，由工作流泛化得到的文件系统权限，为 None 时不做限制
+    pub sandbox: Option<Vec<Permission>>,
+}
+
+// 规范化路径以便比较，Windows 路径大小写不敏感
+fn normalize_sandbox_path(raw: &str, located: &String) -> String {
+    let p = parse_relative_path_with_located(raw, located).clean();
+    p2s!(p)
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+// 判断具体路径是否被声明的目标覆盖，目标的子路径同样被覆盖
+fn is_covered_by_target(target: &str, concrete: &str) -> bool {
+    if concrete == target || concrete.starts_with(&format!("{target}/")) {
+        return true;
+    }
+    if contains_wild_match(target) {
+        let matcher = WildMatch::new(target);
+        return Path::new(concrete)
+            .ancestors()
+            .any(|p| matcher.matches(&p2s!(p)));
+    }
+    false
 }
 
 impl WorkflowContext {
@@ -157,6 +189,65 @@ impl WorkflowContext {
             located: located.to_owned(),
             async_execution_handlers: Vec::new(),
             exit_code: 0,
+            sandbox: None,
+        }
+    }
+
+    // 启用沙箱，仅保留文件系统相关的权限
+    pub fn enable_sandbox(&mut self, permissions: Vec<Permission>) {
+        let fs_permissions = permissions
+            .into_iter()
+            .filter(|perm| {
+                perm.key == PermissionKey::fs_read || perm.key == PermissionKey::fs_write
+            })
+            .collect();
+        self.sandbox = Some(fs_permissions);
+    }
+
+    // 在步骤操作文件系统前检查具体路径是否在声明的权限范围内
+    pub fn check_fs_access(&self, key: PermissionKey, target: &str) -> Result<()> {
+        let permissions = if let Some(p) = &self.sandbox {
+            p
+        } else {
+            return Ok(());
+        };
+
+        // 写权限同时覆盖读权限
+        let accepted_keys = if key == PermissionKey::fs_read {
+            vec![PermissionKey::fs_read, PermissionKey::fs_write]
+        } else {
+            vec![key.clone()]
+        };
+        let package_version = &self.pkg.package.version;
+        let concrete = normalize_sandbox_path(target, &self.located);
+        let declared: Vec<String> = permissions
+            .iter()
+            .filter(|perm| accepted_keys.contains(&perm.key))
+            .flat_map(|perm| perm.targets.clone())
+            .collect();
+        let covered = declared.iter().any(|raw| {
+            let interpreted = values_replacer(
+                raw.to_owned(),
+                self.exit_code,
+                &self.located,
+                package_version,
+            );
+            is_covered_by_target(
+                &normalize_sandbox_path(&interpreted, &self.located),
+                &concrete,
+            )
+        });
+        if covered {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Error(Sandbox):Access '{key:?}' to '{target}' is out of declared targets ({targets}), step aborted",
+                targets = declared
+                    .iter()
+                    .map(|t| format!("'{t}'"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))
         }
     }
 
@@ -196,3 +287,53 @@ impl WorkflowContext {
         Ok(self.exit_code)
     }
 }
+
+#[test]
+fn test_sandbox() {
+    use crate::types::permissions::PermissionLevel;
+    let mut cx = WorkflowContext::_demo();
+
+    // 未启用沙箱时不做限制
+    cx.check_fs_access(PermissionKey::fs_write, "C:/Windows/System32/cmd.exe")
+        .unwrap();
+
+    cx.enable_sandbox(vec![
+        Permission {
+            key: PermissionKey::fs_read,
+            level: PermissionLevel::Normal,
+            targets: vec!["./src/types/*.rs".to_string()],
+        },
+        Permission {
+            key: PermissionKey::fs_write,
+            level: PermissionLevel::Normal,
+            targets: vec!["./test/".to_string()],
+        },
+        Permission {
+            key: PermissionKey::link_desktop,
+            level: PermissionLevel::Normal,
+            targets: vec!["VSCode".to_string()],
+        },
+    ]);
+
+    // 通配符匹配
+    cx.check_fs_access(PermissionKey::fs_read, "src/types/workflow.rs")
+        .unwrap();
+    assert!(cx
+        .check_fs_access(PermissionKey::fs_read, "src/utils/mod.rs")
+        .is_err());
+    assert!(cx
+        .check_fs_access(PermissionKey::fs_write, "src/types/workflow.rs")
+        .is_err());
+
+    // 子路径与写权限覆盖读权限
+    cx.check_fs_access(PermissionKey::fs_write, "./test/sub/1.txt")
+        .unwrap();
+    cx.check_fs_access(PermissionKey::fs_read, "test/Sub")
+        .unwrap();
+    assert!(cx
+        .check_fs_access(PermissionKey::fs_write, "./test/../src/main.rs")
+        .is_err());
+    assert!(cx
+        .check_fs_access(PermissionKey::fs_write, "./test2/1.txt")
+        .is_err());
+}
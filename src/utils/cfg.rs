@@ -1,11 +1,24 @@
 use std::sync::{Arc, RwLock};
 
 use anyhow::{anyhow, Result};
+use toml::Value;
 
 use crate::types::cfg::Cfg;
 
 lazy_static! {
-    static ref CFG: Arc<RwLock<Cfg>> = Arc::new(RwLock::new(Cfg::init().unwrap()));
+    static ref PROFILE: RwLock<Option<String>> = RwLock::new(None);
+    static ref CFG: Arc<RwLock<Cfg>> =
+        Arc::new(RwLock::new(Cfg::init(get_profile().as_deref()).unwrap()));
+}
+
+// 需要在首次读取配置前调用
+pub fn set_profile(profile: Option<String>) {
+    let mut lock = PROFILE.write().unwrap();
+    *lock = profile;
+}
+
+pub fn get_profile() -> Option<String> {
+    PROFILE.read().unwrap().clone()
 }
 
 pub fn get_config() -> Cfg {
@@ -13,7 +26,24 @@ pub fn get_config() -> Cfg {
 }
 
 pub fn set_config(next: Cfg) -> Result<()> {
-    Cfg::overwrite(next.clone())?;
+    Cfg::overwrite(next.clone(), get_profile().as_deref())?;
+    let mut lock = CFG.write().unwrap();
+    *lock = next;
+
+    Ok(())
+}
+
+// 仅修改内存中的配置而不写入配置文件
+pub fn _update_config_in_memory<F: FnOnce(&mut Cfg)>(updater: F) {
+    let mut lock = CFG.write().unwrap();
+    updater(&mut lock);
+}
+
+// 写入单个配置项，然后重新叠加各层级的配置
+pub fn set_config_value(table: &str, key: &str, value: Value) -> Result<()> {
+    let profile = get_profile();
+    Cfg::write_value(table, key, value, profile.as_deref())?;
+    let next = Cfg::init(profile.as_deref())?;
     let mut lock = CFG.write().unwrap();
     *lock = next;
 
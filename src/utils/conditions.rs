@@ -6,7 +6,7 @@ use std::sync::{Arc, Mutex};
 use crate::{
     executor::{
         condition_eval, get_eval_context, get_eval_function_names, get_eval_function_permission,
-        verify_eval_function_arg,
+        get_eval_function_placeholder, match_signature, verify_eval_function_arg, ArgType,
     },
     types::permissions::Permission,
 };
@@ -15,18 +15,31 @@ lazy_static! {
     static ref RESOURCE_REGEX: Regex = Regex::new(r"^[^/]+/[^/]+$").unwrap();
 }
 
-pub fn ensure_arg(val: &Value) -> std::result::Result<String, EvalexprError> {
-    if let Value::String(str) = val {
-        Ok(str.to_string())
-    } else {
-        Err(EvalexprError::ExpectedString {
-            actual: val.clone(),
-        })
+// 将函数入参展开为列表，多个入参时 evalexpr 会以元组形式传入
+fn flatten_args(val: &Value) -> Vec<Value> {
+    match val {
+        Value::Tuple(tuple) => tuple.to_owned(),
+        Value::Empty => Vec::new(),
+        _ => vec![val.to_owned()],
     }
 }
 
+// 按照函数签名检查并展开入参
+pub fn ensure_args(
+    val: &Value,
+    signature: &[ArgType],
+) -> std::result::Result<Vec<Value>, EvalexprError> {
+    let args = flatten_args(val);
+    if let Some(reason) = match_signature(signature, &args) {
+        return Err(EvalexprError::CustomMessage(format!(
+            "Invalid arguments : {reason}"
+        )));
+    }
+    Ok(args)
+}
+
 /// 使用虚拟的函数定义捕获函数运行信息，返回（函数名，参数，所属表达式）
-fn capture_function_info(conditions: &Vec<String>) -> Result<Vec<(String, String, String)>> {
+fn capture_function_info(conditions: &Vec<String>) -> Result<Vec<(String, Vec<Value>, String)>> {
     // 获取已注册的 eval 函数名称
     let info_arr = get_eval_function_names();
 
@@ -44,11 +57,11 @@ fn capture_function_info(conditions: &Vec<String>) -> Result<Vec<(String, String
                 .set_function(
                     name.to_string(),
                     Function::new(move |val| {
-                        let arg = ensure_arg(val)?;
+                        let args = flatten_args(val);
                         let mut r = r.lock().unwrap();
-                        r.push((name.to_string(), arg, c.to_owned()));
+                        r.push((name.to_string(), args, c.to_owned()));
 
-                        Ok(Value::Boolean(true))
+                        Ok(get_eval_function_placeholder(name))
                     }),
                 )
                 .unwrap();
@@ -69,8 +82,10 @@ pub fn get_permissions_from_conditions(conditions: Vec<String>) -> Result<Vec<Pe
 
     // 匹配生成权限信息
     let mut permissions = Vec::new();
-    for (name, arg, _) in func_info {
-        permissions.push(get_eval_function_permission(name, arg)?);
+    for (name, args, _) in func_info {
+        if let Some(permission) = get_eval_function_permission(name, args)? {
+            permissions.push(permission);
+        }
     }
 
     Ok(permissions)
@@ -92,8 +107,8 @@ pub fn verify_conditions(
     let func_info = capture_function_info(&conditions)?;
 
     // 匹配函数入参进行校验
-    for (name, arg, _) in func_info {
-        verify_eval_function_arg(name, arg)?;
+    for (name, args, _) in func_info {
+        verify_eval_function_arg(name, args)?;
     }
 
     // 对条件进行 eval 校验
@@ -118,6 +133,8 @@ fn test_condition() {
         "Exist(\"${AppData}\") && IsDirectory(\"${SystemDrive}/Windows\")",
         "Exist(\"./src/main.ts\")",
         "(IsInstalled(\"Foo/Bar\") && IsAlive(\"陈睿's mother.exe\")) || IsAlive(\"aunt.exe\")",
+        "VersionCompare(InstalledVersion(\"Foo/Bar\"), \"1.0.0.0\")<0 && FileContains(\"${AppData}/foo.ini\", \"^a=1$\")",
+        "FileHash(\"src/main.rs\")!=\"\" && Env(\"PATH\")!=\"\"",
     ]
     .into_iter()
     .map(|s| s.to_string())
@@ -128,7 +145,7 @@ fn test_condition() {
 
     // capture_function_info
     let res = capture_function_info(&conditions.clone()).unwrap();
-    let answer: Vec<(String, String, String)> = vec![
+    let answer: Vec<(String, Vec<Value>, String)> = vec![
         (
             "Exist",
             "src/main.rs",
@@ -167,10 +184,56 @@ fn test_condition() {
         ),
     ]
     .into_iter()
-    .map(|(func, arg, source)| (func.to_string(), arg.to_string(), source.to_string()))
+    .map(|(func, arg, source)| {
+        (
+            func.to_string(),
+            vec![Value::String(arg.to_string())],
+            source.to_string(),
+        )
+    })
+    .chain(
+        vec![
+            ("InstalledVersion", vec!["Foo/Bar"], conditions[8].as_str()),
+            (
+                "VersionCompare",
+                vec!["", "1.0.0.0"],
+                conditions[8].as_str(),
+            ),
+            (
+                "FileContains",
+                vec!["${AppData}/foo.ini", "^a=1$"],
+                conditions[8].as_str(),
+            ),
+            ("FileHash", vec!["src/main.rs"], conditions[9].as_str()),
+            ("Env", vec!["PATH"], conditions[9].as_str()),
+        ]
+        .into_iter()
+        .map(|(func, args, source)| {
+            (
+                func.to_string(),
+                args.into_iter()
+                    .map(|s| Value::String(s.to_string()))
+                    .collect(),
+                source.to_string(),
+            )
+        }),
+    )
     .collect();
     assert_eq!(res, answer);
 
+    // 入参个数或类型不匹配时在校验阶段报错
+    let verify = |cond: &str| {
+        verify_conditions(vec![cond.to_string()], &located, &"1.0.0.0".to_string())
+            .unwrap_err()
+            .to_string()
+    };
+    assert!(verify("Exist(\"src\", \"main.rs\")").contains("expect 1 argument(s) (String), got 2"));
+    assert!(verify("FileContains(\"src/main.rs\")")
+        .contains("expect 2 argument(s) (String, String), got 1"));
+    assert!(verify("VersionCompare(\"1.0.0.0\", ExitCode)==0")
+        .contains("argument 2 should be String, got Int '0'"));
+    assert!(get_permissions_from_conditions(vec!["IsAlive(true)".to_string()]).is_err());
+
     // get_permissions_from_conditions
     use crate::types::permissions::{PermissionKey, PermissionLevel};
     let res = get_permissions_from_conditions(conditions.clone()).unwrap();
@@ -215,12 +278,47 @@ fn test_condition() {
             level: PermissionLevel::Normal,
             targets: vec!["aunt.exe".to_string()],
         },
+        Permission {
+            key: PermissionKey::nep_installed,
+            level: PermissionLevel::Normal,
+            targets: vec!["Foo/Bar".to_string()],
+        },
+        Permission {
+            key: PermissionKey::fs_read,
+            level: PermissionLevel::Sensitive,
+            targets: vec!["${AppData}/foo.ini".to_string()],
+        },
+        Permission {
+            key: PermissionKey::fs_read,
+            level: PermissionLevel::Normal,
+            targets: vec!["src/main.rs".to_string()],
+        },
+        Permission {
+            key: PermissionKey::env_read,
+            level: PermissionLevel::Normal,
+            targets: vec!["PATH".to_string()],
+        },
     ];
     assert_eq!(res, answer);
 
     // println!("{res:#?}");
 }
 
+#[test]
+fn test_match_signature() {
+    let signature = vec![ArgType::String, ArgType::Int, ArgType::Boolean];
+    let args = vec![
+        Value::String("a".to_string()),
+        Value::Int(1),
+        Value::Boolean(true),
+    ];
+    assert!(match_signature(&signature, &args).is_none());
+    assert!(match_signature(&signature, &args[..2]).is_some());
+    assert!(match_signature(&signature[..1], &[Value::Int(1)]).is_some());
+    assert!(ensure_args(&Value::Tuple(args.clone()), &signature).is_ok());
+    assert!(ensure_args(&Value::String("a".to_string()), &signature).is_err());
+}
+
 // 检查用户是否在字符串外面用了模板内置变量 ${} 的写法
 pub fn check_proper_template_inner_value(condition: &str) -> bool {
     // 当前指针是否在字符串内部
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{cell::RefCell, path::Path};
 
 use dirs::{data_dir, desktop_dir, home_dir};
 
@@ -6,15 +6,51 @@ use crate::p2s;
 
 use super::ensure_exist;
 
+thread_local! {
+    // 沙箱根目录仅对进入沙箱的线程生效，不影响同一进程中的其他操作
+    static SANDBOX_ROOT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// 进入沙箱的上下文，离开作用域时恢复进入前的沙箱根目录
+pub struct SandboxGuard {
+    previous: Option<String>,
+}
+
+impl Drop for SandboxGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SANDBOX_ROOT.with(|root| *root.borrow_mut() = previous);
+    }
+}
+
+// 进入沙箱后，当前线程中内置变量指向的系统目录与 bin 目录都会被重定向到 root 中
+pub fn enter_sandbox(root: String) -> SandboxGuard {
+    let previous = SANDBOX_ROOT.with(|cur| cur.borrow_mut().replace(root));
+    SandboxGuard { previous }
+}
+
+pub fn get_sandbox_root() -> Option<String> {
+    SANDBOX_ROOT.with(|root| root.borrow().clone())
+}
+
 pub fn env_system_drive() -> String {
+    if let Some(root) = get_sandbox_root() {
+        return root + "/SystemDrive";
+    }
     p2s!(data_dir().unwrap())[0..2].to_string()
 }
 
 pub fn env_appdata() -> String {
+    if get_sandbox_root().is_some() {
+        return env_home() + "/AppData";
+    }
     p2s!(data_dir().unwrap().parent().unwrap())
 }
 
 pub fn env_home() -> String {
+    if let Some(root) = get_sandbox_root() {
+        return root + "/Home";
+    }
     p2s!(home_dir().unwrap())
 }
 
@@ -27,6 +63,9 @@ pub fn env_program_files_x86() -> String {
 }
 
 pub fn env_desktop() -> String {
+    if get_sandbox_root().is_some() {
+        return env_home() + "/Desktop";
+    }
     p2s!(desktop_dir().unwrap())
 }
 
@@ -56,3 +95,24 @@ fn test_env() {
     //     "C:/Users/dsyou/AppData/Roaming/Microsoft/Windows/Start Menu/Programs/Nep Apps".to_string()
     // );
 }
+
+#[test]
+fn test_enter_sandbox() {
+    let guard = enter_sandbox("./test/Sandbox".to_string());
+    assert_eq!(env_desktop(), "./test/Sandbox/Home/Desktop".to_string());
+    assert_eq!(env_system_drive(), "./test/Sandbox/SystemDrive".to_string());
+
+    // 其他线程不受沙箱影响
+    assert!(std::thread::spawn(get_sandbox_root)
+        .join()
+        .unwrap()
+        .is_none());
+
+    // 嵌套进入时恢复外层沙箱
+    let inner = enter_sandbox("./test/Inner".to_string());
+    assert_eq!(get_sandbox_root(), Some("./test/Inner".to_string()));
+    drop(inner);
+    assert_eq!(get_sandbox_root(), Some("./test/Sandbox".to_string()));
+    drop(guard);
+    assert!(get_sandbox_root().is_none());
+}
@@ -6,7 +6,7 @@ use std::path::Path;
 use crate::p2s;
 
 fn get_64_version<P: AsRef<Path>>(file_path: P) -> Result<String> {
-    use pelite::pe::Pe;
+    use pelite::pe64::Pe;
     let path = file_path.as_ref();
     if let Ok(map) = FileMap::open(path) {
         let file = pe64::PeFile::from_bytes(&map)
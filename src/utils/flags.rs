@@ -4,9 +4,13 @@ use std::sync::Mutex;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Flag {
+    AllowDowngrade,
     Cache,
     Confirm,
     Debug,
+    Force,
+    // 确认模式由批量操作隐式开启，而非用户指定
+    ImplicitConfirm,
     Offline,
     QA,
 }
@@ -1,6 +1,6 @@
 use chrono::DateTime;
 use colored::{ColoredString, Colorize};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 fn ellipsis(raw: &str, limit: usize) -> String {
     let len = raw.len();
@@ -28,6 +28,87 @@ pub fn fmt_package_line(scope: &str, name: &str, version: &str, mirror: Option<S
     )
 }
 
+pub fn fmt_releases_line(versions: &[String]) -> String {
+    fmt_detail_line("releases :", &versions.join(", "))
+}
+
+pub fn fmt_detail_line(label: &str, value: &str) -> String {
+    format!(
+        "  {:>15} {}\n",
+        label.truecolor(100, 100, 100),
+        value.truecolor(100, 100, 100)
+    )
+}
+
+pub fn fmt_timestamp(secs: u64) -> String {
+    let time = UNIX_EPOCH + Duration::from_secs(secs);
+    let date_time: DateTime<chrono::Local> = time.into();
+    date_time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+pub fn fmt_permission_line(level: &str, key: &str, targets: &[String]) -> String {
+    format!(
+        "  {:<10} {:<16} {}\n",
+        level,
+        key.cyan(),
+        targets.join(", ").as_str().truecolor(100, 100, 100)
+    )
+}
+
+pub fn fmt_size(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut index = 0;
+    while size >= 1024.0 && index < units.len() - 1 {
+        size /= 1024.0;
+        index += 1;
+    }
+    if index == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", units[index])
+    }
+}
+
+pub fn fmt_usage_line(label: &str, bytes: u64, detail: &str) -> String {
+    format!(
+        "  {:<46} {:>10} {}\n",
+        label.cyan(),
+        fmt_size(bytes),
+        detail.truecolor(100, 100, 100)
+    )
+}
+
+// 按目录层级缩进，目录名以 '/' 结尾
+pub fn fmt_package_entry_line(path: &str, size: u64, is_dir: bool) -> String {
+    let depth = path.matches('/').count();
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let label = "  ".repeat(depth + 1) + name;
+    if is_dir {
+        format!("{}\n", (label + "/").cyan())
+    } else {
+        format!("{label:<48} {:>10}\n", fmt_size(size))
+    }
+}
+
+// 新增以 '+' 标记，移除以 '-' 标记，修改以 '~' 标记并展示新旧值
+pub fn fmt_change_line(label: &str, old: Option<&str>, new: Option<&str>) -> String {
+    match (old, new) {
+        (None, Some(new)) => format!("  {} {label:<40} {new}\n", "+".green()),
+        (Some(old), None) => format!(
+            "  {} {label:<40} {}\n",
+            "-".red(),
+            old.truecolor(100, 100, 100)
+        ),
+        (Some(old), Some(new)) => format!(
+            "  {} {label:<40} {} -> {new}\n",
+            "~".yellow(),
+            old.truecolor(100, 100, 100)
+        ),
+        (None, None) => String::new(),
+    }
+}
+
 pub fn fmt_mirror_line(name: &str, updated_at: SystemTime) -> String {
     let date_time: DateTime<chrono::Local> = updated_at.into();
     let time_str = date_time.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -48,6 +129,14 @@ fn test_ellipsis() {
     );
 }
 
+#[test]
+fn test_fmt_size() {
+    assert_eq!(fmt_size(0), "0 B".to_string());
+    assert_eq!(fmt_size(1023), "1023 B".to_string());
+    assert_eq!(fmt_size(1536), "1.5 KB".to_string());
+    assert_eq!(fmt_size(94245376), "89.9 MB".to_string());
+}
+
 #[test]
 fn test_fmt() {
     println!("{}", fmt_log("Test".purple(), "This is a fmt test message"));
@@ -69,4 +158,16 @@ fn test_fmt() {
         )
     );
     print!("{}", fmt_mirror_line("mock-server", SystemTime::now()));
+    print!(
+        "{}",
+        fmt_usage_line("Microsoft/VSCode", 94245376, "app 89.9 MB, context 0 B")
+    );
+    print!(
+        "{}",
+        fmt_releases_line(&["1.75.4.2".to_string(), "1.75.4.0".to_string()])
+    );
+    print!(
+        "{}",
+        fmt_detail_line("installed :", &fmt_timestamp(1704554724))
+    );
 }
@@ -1,11 +1,14 @@
 use crate::p2s;
 use anyhow::{anyhow, Result};
 use fs_extra::dir::CopyOptions;
+use serde::{de::DeserializeOwned, Serialize};
 use std::{
-    fs::{create_dir_all, read_dir, remove_dir_all, remove_file, rename},
+    fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, remove_file, rename, write},
     path::Path,
 };
 
+use super::path::parse_relative_path_with_base;
+
 pub fn try_recycle<P: AsRef<Path>>(path: P) -> Result<()> {
     let p = path.as_ref();
     if !p.exists() {
@@ -95,6 +98,27 @@ pub fn ensure_dir_exist<P: AsRef<Path>>(path: P) -> Result<()> {
     Ok(())
 }
 
+// 读取 ept 目录下的 toml 存储文件，文件不存在时返回缺省值，label 用于错误信息
+pub fn read_toml_store<T: DeserializeOwned + Default>(file_name: &str, label: &str) -> Result<T> {
+    let p = parse_relative_path_with_base(file_name)?;
+    if !p.exists() {
+        return Ok(T::default());
+    }
+    let text = read_to_string(&p)
+        .map_err(|e| anyhow!("Error:Failed to read {label} file '{}' : {e}", p2s!(p)))?;
+    toml::from_str(&text)
+        .map_err(|e| anyhow!("Error:Failed to parse {label} file '{}' : {e}", p2s!(p)))
+}
+
+pub fn write_toml_store<T: Serialize>(file_name: &str, label: &str, value: &T) -> Result<()> {
+    let p = parse_relative_path_with_base(file_name)?;
+    if let Some(parent) = p.parent() {
+        ensure_dir_exist(parent)?;
+    }
+    let text = toml::to_string_pretty(value)?;
+    write(&p, text).map_err(|e| anyhow!("Error:Failed to write {label} file '{}' : {e}", p2s!(p)))
+}
+
 pub fn move_or_copy<P: AsRef<Path>>(from: P, to: P) -> Result<()> {
     let from = from.as_ref();
     let to = to.as_ref();
@@ -130,6 +154,13 @@ where
     Ok(())
 }
 
+// 计算目录占用的磁盘空间，单位为字节
+pub fn get_dir_size<P: AsRef<Path>>(path: P) -> Result<u64> {
+    let path_str = p2s!(path.as_ref());
+    fs_extra::dir::get_size(path)
+        .map_err(|e| anyhow!("Error:Failed to get size of directory '{path_str}' : {e}"))
+}
+
 #[test]
 fn test_read_sub_dir() {
     assert_eq!(
@@ -146,3 +177,12 @@ fn test_count_sub_files() {
     );
     assert_eq!(count_sub_files("examples/VSCode", |_| false).unwrap(), 0);
 }
+
+#[test]
+fn test_get_dir_size() {
+    let size = get_dir_size("examples/VSCode").unwrap();
+    let package_size = std::fs::metadata("examples/VSCode/package.toml")
+        .unwrap()
+        .len();
+    assert!(size > package_size);
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{read_to_string, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    p2s,
+    types::history::{History, HistoryEntry, HistoryOperation, HistoryPackage},
+};
+
+use super::path::parse_relative_path_with_base;
+
+fn get_path_history() -> Result<PathBuf> {
+    parse_relative_path_with_base("history.toml")
+}
+
+pub fn read_history() -> Result<Vec<HistoryEntry>> {
+    let p = get_path_history()?;
+    if !p.exists() {
+        return Ok(Vec::new());
+    }
+    let text = read_to_string(&p)
+        .map_err(|e| anyhow!("Error:Failed to read history file '{}' : {e}", p2s!(p)))?;
+    let history: History = toml::from_str(&text)
+        .map_err(|e| anyhow!("Error:Failed to parse history file '{}' : {e}", p2s!(p)))?;
+    Ok(history.entries)
+}
+
+// 以追加的方式写入，避免每次重写整个文件
+fn append_history(entry: HistoryEntry) -> Result<()> {
+    let p = get_path_history()?;
+    let text = toml::to_string(&History {
+        entries: vec![entry],
+    })?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&p)
+        .map_err(|e| anyhow!("Error:Failed to open history file '{}' : {e}", p2s!(p)))?;
+    file.write_all(format!("\n{text}").as_bytes())
+        .map_err(|e| anyhow!("Error:Failed to write history file '{}' : {e}", p2s!(p)))
+}
+
+// 记录一次操作，写入失败不影响操作本身的结果
+pub fn record_history<T>(
+    operation: HistoryOperation,
+    packages: Vec<HistoryPackage>,
+    started: Instant,
+    res: &Result<T>,
+) {
+    let timestamp = SystemTime::now()
+        .checked_sub(started.elapsed())
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let entry = HistoryEntry {
+        timestamp,
+        operation,
+        packages,
+        success: res.is_ok(),
+        duration: started.elapsed().as_millis() as u64,
+        error: res.as_ref().err().map(|e| e.to_string()),
+    };
+    if let Err(e) = append_history(entry) {
+        log!("Warning:Failed to record history : {e}");
+    }
+}
+
+#[test]
+fn test_record_history() {
+    let started = Instant::now();
+    let before = read_history().unwrap().len();
+    let res: Result<()> = Err(anyhow!("Error:Test history failure"));
+    record_history(
+        HistoryOperation::Uninstall,
+        vec![HistoryPackage::new(
+            "Microsoft",
+            "HistoryTest",
+            Some("1.0.0.0".to_string()),
+            None,
+        )],
+        started,
+        &res,
+    );
+    let entries = read_history().unwrap();
+    assert_eq!(entries.len(), before + 1);
+    let last = entries.last().unwrap();
+    assert!(!last.success);
+    assert!(last.involves("HistoryTest"));
+    assert_eq!(last.error, Some("Error:Test history failure".to_string()));
+}
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    entrances::info_online,
+    p2s,
+    types::install_record::{InstallRecord, InstallSource},
+};
+
+use super::{download::fill_url_template, fs::read_sub_dir, get_path_mirror};
+
+fn get_path_install_record(ctx_path: &Path) -> PathBuf {
+    ctx_path.join("install.toml")
+}
+
+// 在此功能之前安装的包没有安装记录
+pub fn read_install_record(ctx_path: &Path) -> Result<Option<InstallRecord>> {
+    let p = get_path_install_record(ctx_path);
+    if !p.exists() {
+        return Ok(None);
+    }
+    let text = read_to_string(&p)
+        .map_err(|e| anyhow!("Error:Failed to read install record '{}' : {e}", p2s!(p)))?;
+    let record = toml::from_str(&text)
+        .map_err(|e| anyhow!("Error:Failed to parse install record '{}' : {e}", p2s!(p)))?;
+    Ok(Some(record))
+}
+
+pub fn write_install_record(ctx_path: &Path, record: &InstallRecord) -> Result<()> {
+    let p = get_path_install_record(ctx_path);
+    let text = toml::to_string_pretty(record)?;
+    write(&p, text).map_err(|e| anyhow!("Error:Failed to write install record '{}' : {e}", p2s!(p)))
+}
+
+// 通过下载地址反查提供该包的镜像源，找不到时记录为 URL 来源
+pub fn resolve_url_source(scope: &String, name: &String, url: &str) -> InstallSource {
+    let mirror_names = get_path_mirror().and_then(read_sub_dir).unwrap_or_default();
+    for mirror in mirror_names {
+        if let Ok((item, url_template)) = info_online(scope, name, Some(mirror.clone())) {
+            for release in item.releases {
+                let matched =
+                    fill_url_template(&url_template, scope, &item.name, &release.file_name)
+                        .map(|u| u == url)
+                        .unwrap_or(false);
+                if matched {
+                    return InstallSource::Mirror {
+                        mirror,
+                        file_name: release.file_name,
+                    };
+                }
+            }
+        }
+    }
+    InstallSource::Url {
+        url: url.to_string(),
+    }
+}
+
+#[test]
+fn test_install_record_file() {
+    use crate::types::install_record::InstallReason;
+    crate::utils::test::_ensure_clear_test_dir();
+    let ctx_path = Path::new("test");
+    assert!(read_install_record(ctx_path).unwrap().is_none());
+
+    let record = InstallRecord::new(
+        InstallReason::Explicit,
+        InstallSource::Url {
+            url: "http://localhost:3000/VSCode_1.75.4.0_Cno.nep".to_string(),
+        },
+    );
+    write_install_record(ctx_path, &record).unwrap();
+    assert_eq!(read_install_record(ctx_path).unwrap(), Some(record));
+}
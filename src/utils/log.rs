@@ -11,7 +11,7 @@ use super::{
 
 lazy_static! {
     static ref RE: Regex =
-        Regex::new(r"(Question|Debug|Info|Warning|Error|Success)(\(\w+\))?:(.+)").unwrap();
+        Regex::new(r"(?s)(Question|Debug|Info|Warning|Error|Success)(\(\w+\))?:(.+)").unwrap();
     static ref TERM: Term = Term::stdout();
     static ref LAST_LOG: Mutex<String> = Mutex::new("".to_string());
 }
@@ -12,9 +12,14 @@ pub mod env;
 pub mod flags;
 pub mod fmt_print;
 pub mod fs;
+pub mod history;
+pub mod install_record;
 pub mod mirror;
 pub mod parse_inputs;
 pub mod path;
+pub mod permissions;
+pub mod pin;
+pub mod policy;
 pub mod process;
 pub mod random;
 pub mod reg_entry;
@@ -29,8 +34,9 @@ use regex::Regex;
 
 use std::env::var;
 use std::fs::create_dir_all;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use self::env::get_sandbox_root;
 use self::fs::try_recycle;
 use self::path::parse_relative_path_with_base;
 use self::random::random_short_string;
@@ -100,6 +106,10 @@ pub fn allocate_path_temp(name: &str, sub_dir: bool) -> Result<PathBuf> {
 }
 
 pub fn get_path_bin() -> Result<PathBuf> {
+    // 沙箱中使用独立的 bin 目录
+    if let Some(root) = get_sandbox_root() {
+        return ensure_exist(Path::new(&root).join("bin"));
+    }
     ensure_exist(parse_relative_path_with_base("bin")?)
 }
 
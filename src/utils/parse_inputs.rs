@@ -9,15 +9,20 @@ use crate::{
     types::{
         extended_semver::ExSemVer,
         matcher::{PackageInputEnum, PackageMatcher},
+        policy::UpdateDecision,
     },
     utils::fmt_print::fmt_package_line,
 };
 
 use super::{
     cfg::get_config,
+    download::fill_url_template,
+    flags::{get_flag, Flag},
     get_path_apps,
     mirror::{filter_release, get_url_with_version_req},
     path::find_scope_with_name,
+    pin::get_pin,
+    policy::decide_update,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -105,9 +110,7 @@ pub fn parse_install_inputs(packages: Vec<String>) -> Result<Vec<ParseInputResEn
                 // 检查对应包名有没有被安装过，如果安装过就作为 update 解析
                 if let Ok((_, diff)) = info_local(&scope, &package_name) {
                     log!("Warning:Package '{scope}/{package_name}' has been installed({ver}), would be switched to update entrance",ver = diff.version);
-                    let mut update_parsed =
-                        parse_update_inputs(vec![format!("{scope}/{package_name}")])?;
-                    res.append(&mut update_parsed);
+                    res.push(parse_update_matcher(matcher)?);
                     continue;
                 }
                 // 解析 url
@@ -125,6 +128,66 @@ pub fn parse_install_inputs(packages: Vec<String>) -> Result<Vec<ParseInputResEn
     Ok(res)
 }
 
+// 将已安装包的 PackageMatcher 解析为更新信息
+fn parse_update_matcher(matcher: PackageMatcher) -> Result<ParseInputResEnum> {
+    // 查找 scope 并使用 scope 更新纠正大小写
+    let (scope, package_name) = find_scope_with_name(&matcher.name, matcher.scope.clone())?;
+    // 检查对应包名有没有被安装过
+    let (_global, local_diff) = info_local(&scope, &package_name).map_err(|_| {
+        anyhow!("Error:Package '{scope}/{package_name}' hasn't been installed, use 'ept install' instead")
+    })?;
+    let local_version = ExSemVer::parse(&local_diff.version)?;
+    let (online_item, url_template) = info_online(&scope, &package_name, matcher.mirror.clone())?;
+    let online_name = online_item.name.clone();
+    let selected_release = if matcher.version_req.is_none() {
+        // 未指定版本时遵循锁定规则和更新策略
+        match decide_update(&scope, &package_name, online_item.releases, &local_version)? {
+            UpdateDecision::Update(release) => release,
+            UpdateDecision::Held(reason) => {
+                return Err(anyhow!("Error:Package '{scope}/{package_name}' is held back ({reason}), specify a version explicitly or add '--force' to update anyway"));
+            }
+            UpdateDecision::UpToDate => {
+                return Err(anyhow!(
+                    "Error:Package '{package_name}' has been up to date ({local_version})",
+                    local_version = &local_diff.version
+                ));
+            }
+        }
+    } else {
+        let selected_release =
+            filter_release(online_item.releases, matcher.version_req.clone(), true)?;
+        // 显式指定版本时仍需满足锁定规则
+        if !get_flag(Flag::Force, false) {
+            if let Some(pin) = get_pin(&scope, &package_name)? {
+                if !pin.allows(&selected_release.version)? {
+                    return Err(anyhow!("Error:Package '{scope}/{package_name}' has been {desc}, can't update to '{ver}' without '--force'",desc=pin.describe(),ver=selected_release.version));
+                }
+            }
+        }
+        // 检查包的版本号是否允许升级，显式允许降级时仅拒绝相同的版本
+        if selected_release.version == local_version
+            || (selected_release.version < local_version && !get_flag(Flag::AllowDowngrade, false))
+        {
+            return Err(anyhow!("Error:Package '{name}' has been up to date ({local_version}), can't update to the version of given package ({fresh_version})",name=package_name,local_version=&local_diff.version,fresh_version=&selected_release.version));
+        }
+        selected_release
+    };
+    // 解析 url
+    let url = fill_url_template(
+        &url_template,
+        &scope,
+        &online_name,
+        &selected_release.file_name,
+    )?;
+    Ok(ParseInputResEnum::PackageMatcher(ParsePackageInputRes {
+        name: package_name,
+        scope,
+        current_version: Some(local_diff.version),
+        target_version: selected_release.version.to_string(),
+        download_url: url,
+    }))
+}
+
 pub fn parse_update_inputs(packages: Vec<String>) -> Result<Vec<ParseInputResEnum>> {
     let mut res: Vec<ParseInputResEnum> = Vec::new();
     let mut mirror_updated = false;
@@ -143,30 +206,7 @@ pub fn parse_update_inputs(packages: Vec<String>) -> Result<Vec<ParseInputResEnu
                     auto_mirror_update_all(&cfg)?;
                     mirror_updated = true;
                 }
-                // 查找 scope 并使用 scope 更新纠正大小写
-                let (scope, package_name) =
-                    find_scope_with_name(&matcher.name, matcher.scope.clone())?;
-                // 检查对应包名有没有被安装过
-                let (_global, local_diff) = info_local(&scope, &package_name).map_err(|_| {
-                    anyhow!("Error:Package '{scope}/{package_name}' hasn't been installed, use 'ept install' instead")
-                })?;
-                // 检查包的版本号是否允许升级
-                let (online_item, _url_template) =
-                    info_online(&scope, &package_name, matcher.mirror.clone())?;
-                let selected_release =
-                    filter_release(online_item.releases, matcher.version_req.clone(), true)?;
-                if selected_release.version <= ExSemVer::parse(&local_diff.version)? {
-                    return Err(anyhow!("Error:Package '{name}' has been up to date ({local_version}), can't update to the version of given package ({fresh_version})",name=package_name,local_version=&local_diff.version,fresh_version=&selected_release.version));
-                }
-                // 解析 url
-                let (url, target_release) = get_url_with_version_req(matcher)?;
-                res.push(ParseInputResEnum::PackageMatcher(ParsePackageInputRes {
-                    name: package_name,
-                    scope,
-                    current_version: Some(local_diff.version),
-                    target_version: target_release.version.to_string(),
-                    download_url: url,
-                }))
+                res.push(parse_update_matcher(matcher)?);
             }
         };
     }
@@ -0,0 +1,330 @@
+use anyhow::{anyhow, Result};
+
+use crate::types::{
+    cfg::{PermissionAction, PermissionPolicy},
+    permissions::{Permission, PermissionLevel},
+};
+
+use super::{
+    cfg::get_config,
+    flags::{get_flag, Flag},
+    fmt_print::fmt_permission_line,
+    is_confirm_mode,
+    term::ask_yn_strict,
+};
+
+// 按照权限策略决定对某个权限的操作，优先使用按权限类型的覆盖配置
+pub fn get_permission_action(
+    policy: &PermissionPolicy,
+    permission: &Permission,
+) -> PermissionAction {
+    let key = format!("{:?}", permission.key);
+    if let Some(action) = policy.overrides.get(&key) {
+        return action.to_owned();
+    }
+    match permission.level {
+        PermissionLevel::Normal => policy.normal.to_owned(),
+        PermissionLevel::Important => policy.important.to_owned(),
+        PermissionLevel::Sensitive => policy.sensitive.to_owned(),
+    }
+}
+
+pub fn fmt_permissions(permissions: &[Permission]) -> String {
+    permissions.iter().fold(String::new(), |acc, node| {
+        acc + &fmt_permission_line(
+            &format!("{:?}", node.level),
+            &format!("{:?}", node.key),
+            &node.targets,
+        )
+    })
+}
+
+// 按照权限策略评估权限，受信任的 scope 或签名者返回 None，存在被拒绝的权限时报错，否则返回需要询问的权限
+// signer 仅在签名通过校验时提供，未校验的包不会被视为受信任的签名者
+fn evaluate_permissions(
+    policy: &PermissionPolicy,
+    scope: &str,
+    name: &str,
+    signer: Option<String>,
+    permissions: &[Permission],
+) -> Result<Option<Vec<Permission>>> {
+    // 受信任的 scope 或签名者不受限制
+    if policy
+        .trusted_scopes
+        .iter()
+        .any(|s| s.eq_ignore_ascii_case(scope))
+    {
+        log!("Debug:Skip permission check for trusted scope '{scope}'");
+        return Ok(None);
+    }
+    if let Some(signer) = signer {
+        if policy.trusted_signers.contains(&signer) {
+            log!("Debug:Skip permission check for trusted signer '{signer}'");
+            return Ok(None);
+        }
+    }
+
+    let mut denied = Vec::new();
+    let mut asked = Vec::new();
+    for node in permissions {
+        match get_permission_action(policy, node) {
+            PermissionAction::Allow => {}
+            PermissionAction::Ask => asked.push(node.to_owned()),
+            PermissionAction::Deny => denied.push(node.to_owned()),
+        }
+    }
+
+    if !denied.is_empty() {
+        return Err(anyhow!(
+            "Error:Package '{scope}/{name}' requires permissions denied by config :\n{list}Adjust table 'permission' in config if you trust this package",
+            list = fmt_permissions(&denied)
+        ));
+    }
+    Ok(Some(asked))
+}
+
+// 确认模式会自动回答询问，需要询问的权限不能因此被授予；批量操作隐式开启的确认模式仍会询问用户
+fn ensure_askable(scope: &str, name: &str) -> Result<()> {
+    if is_confirm_mode() && !get_flag(Flag::ImplicitConfirm, false) {
+        return Err(anyhow!(
+            "Error:Package '{scope}/{name}' requires permissions that can't be granted in confirm mode, run without '-y' to review them or allow them in table 'permission' of config"
+        ));
+    }
+    Ok(())
+}
+
+// 在执行任何工作流前，使用配置中的权限策略检查包需要的权限
+pub fn check_permissions(
+    scope: &str,
+    name: &str,
+    signer: Option<String>,
+    permissions: &[Permission],
+) -> Result<()> {
+    let policy = get_config().permission;
+    let asked =
+        evaluate_permissions(&policy, scope, name, signer, permissions)?.unwrap_or_default();
+    if !asked.is_empty() {
+        log!(
+            "Warning:Package '{scope}/{name}' requires following permissions :\n{list}",
+            list = fmt_permissions(&asked)
+        );
+        ensure_askable(scope, name)?;
+        if !ask_yn_strict(
+            format!("Grant these permissions to package '{scope}/{name}'?"),
+            false,
+        ) {
+            return Err(anyhow!("Error:Operation canceled by user"));
+        }
+    }
+
+    Ok(())
+}
+
+// 比较新旧权限，返回 (新增的权限，移除的权限)，按照权限类型和等级分组
+pub fn diff_permissions(
+    old: &[Permission],
+    new: &[Permission],
+) -> (Vec<Permission>, Vec<Permission>) {
+    let subtract = |from: &[Permission], other: &[Permission]| -> Vec<Permission> {
+        from.iter()
+            .filter_map(|node| {
+                let targets: Vec<String> = node
+                    .targets
+                    .iter()
+                    .filter(|target| {
+                        !other.iter().any(|o| {
+                            o.key == node.key && o.level == node.level && o.targets.contains(target)
+                        })
+                    })
+                    .cloned()
+                    .collect();
+                if targets.is_empty() {
+                    None
+                } else {
+                    Some(Permission {
+                        key: node.key.to_owned(),
+                        level: node.level.to_owned(),
+                        targets,
+                    })
+                }
+            })
+            .collect()
+    };
+    (subtract(new, old), subtract(old, new))
+}
+
+// 更新前展示权限变化，新增重要或敏感权限时即使处于批量更新中也需要用户确认
+pub fn check_permissions_diff(
+    scope: &str,
+    name: &str,
+    signer: Option<String>,
+    old: &[Permission],
+    new: &[Permission],
+) -> Result<()> {
+    let (added, removed) = diff_permissions(old, new);
+    if !removed.is_empty() {
+        log!(
+            "Info:Package '{scope}/{name}' no longer requires following permissions :\n{list}",
+            list = fmt_permissions(&removed)
+        );
+    }
+    if added.is_empty() {
+        return Ok(());
+    }
+    log!(
+        "Warning:Package '{scope}/{name}' requires following new permissions :\n{list}",
+        list = fmt_permissions(&added)
+    );
+
+    // 新增的权限同样受权限策略约束
+    let policy = get_config().permission;
+    let asked = match evaluate_permissions(&policy, scope, name, signer, &added)? {
+        Some(asked) => asked,
+        None => return Ok(()),
+    };
+    if !asked.is_empty() {
+        ensure_askable(scope, name)?;
+    }
+    let need_confirm = !asked.is_empty()
+        || added
+            .iter()
+            .any(|node| node.level != PermissionLevel::Normal);
+    if need_confirm
+        && !ask_yn_strict(
+            format!("Grant new permissions to package '{scope}/{name}' and continue updating?"),
+            false,
+        )
+    {
+        return Err(anyhow!("Error:Update canceled by user"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_permissions() {
+    use crate::types::permissions::PermissionKey;
+    let old = vec![
+        Permission {
+            key: PermissionKey::fs_write,
+            level: PermissionLevel::Normal,
+            targets: vec!["./lib".to_string(), "./bin".to_string()],
+        },
+        Permission {
+            key: PermissionKey::link_desktop,
+            level: PermissionLevel::Normal,
+            targets: vec!["VSCode".to_string()],
+        },
+    ];
+    let new = vec![
+        Permission {
+            key: PermissionKey::execute_installer,
+            level: PermissionLevel::Important,
+            targets: vec!["installer.exe /S".to_string()],
+        },
+        Permission {
+            key: PermissionKey::fs_write,
+            level: PermissionLevel::Normal,
+            targets: vec!["./lib".to_string(), "./data".to_string()],
+        },
+        Permission {
+            key: PermissionKey::link_desktop,
+            level: PermissionLevel::Normal,
+            targets: vec!["VSCode".to_string()],
+        },
+    ];
+    let (added, removed) = diff_permissions(&old, &new);
+    assert_eq!(
+        added,
+        vec![
+            Permission {
+                key: PermissionKey::execute_installer,
+                level: PermissionLevel::Important,
+                targets: vec!["installer.exe /S".to_string()],
+            },
+            Permission {
+                key: PermissionKey::fs_write,
+                level: PermissionLevel::Normal,
+                targets: vec!["./data".to_string()],
+            },
+        ]
+    );
+    assert_eq!(
+        removed,
+        vec![Permission {
+            key: PermissionKey::fs_write,
+            level: PermissionLevel::Normal,
+            targets: vec!["./bin".to_string()],
+        }]
+    );
+    assert_eq!(diff_permissions(&new, &new), (Vec::new(), Vec::new()));
+}
+
+#[test]
+fn test_evaluate_permissions() {
+    use crate::types::permissions::PermissionKey;
+    use crate::utils::flags::{set_flag, Flag};
+    use std::collections::BTreeMap;
+    set_flag(Flag::Confirm, true);
+
+    let permissions = vec![
+        Permission {
+            key: PermissionKey::execute_custom,
+            level: PermissionLevel::Sensitive,
+            targets: vec!["unknown.exe --silent".to_string()],
+        },
+        Permission {
+            key: PermissionKey::link_desktop,
+            level: PermissionLevel::Normal,
+            targets: vec!["Visual Studio Code".to_string()],
+        },
+    ];
+    let mut policy = PermissionPolicy {
+        normal: PermissionAction::Allow,
+        important: PermissionAction::Ask,
+        sensitive: PermissionAction::Deny,
+        overrides: BTreeMap::new(),
+        trusted_scopes: Vec::new(),
+        trusted_signers: Vec::new(),
+    };
+    assert_eq!(
+        get_permission_action(&policy, &permissions[0]),
+        PermissionAction::Deny
+    );
+    assert!(evaluate_permissions(&policy, "Microsoft", "VSCode", None, &permissions).is_err());
+
+    // 按权限类型覆盖
+    policy
+        .overrides
+        .insert("execute_custom".to_string(), PermissionAction::Ask);
+    assert_eq!(
+        evaluate_permissions(&policy, "Microsoft", "VSCode", None, &permissions).unwrap(),
+        Some(vec![permissions[0].to_owned()])
+    );
+    policy
+        .overrides
+        .insert("link_desktop".to_string(), PermissionAction::Deny);
+    assert!(evaluate_permissions(&policy, "Microsoft", "VSCode", None, &permissions).is_err());
+
+    // 受信任的 scope 和签名者
+    policy.trusted_scopes.push("microsoft".to_string());
+    assert_eq!(
+        evaluate_permissions(&policy, "Microsoft", "VSCode", None, &permissions).unwrap(),
+        None
+    );
+    policy.trusted_scopes.clear();
+    policy.trusted_signers.push("test@edgeless.top".to_string());
+    assert!(evaluate_permissions(
+        &policy,
+        "Microsoft",
+        "VSCode",
+        Some("test@edgeless.top".to_string()),
+        &permissions
+    )
+    .unwrap()
+    .is_none());
+    assert!(evaluate_permissions(&policy, "Microsoft", "VSCode", None, &permissions).is_err());
+
+    // 确认模式下不会自动授予需要询问的权限
+    assert!(ensure_askable("Microsoft", "VSCode").is_err());
+}
@@ -0,0 +1,59 @@
+use anyhow::Result;
+
+use crate::types::pin::{PinNode, Pins};
+
+use super::fs::{read_toml_store, write_toml_store};
+
+pub fn read_pins() -> Result<Pins> {
+    read_toml_store("pins.toml", "pins")
+}
+
+fn write_pins(pins: &Pins) -> Result<()> {
+    write_toml_store("pins.toml", "pins", pins)
+}
+
+pub fn get_pin(scope: &str, name: &str) -> Result<Option<PinNode>> {
+    let pins = read_pins()?;
+    Ok(pins.pins.get(&format!("{scope}/{name}")).cloned())
+}
+
+pub fn set_pin(scope: &str, name: &str, node: PinNode) -> Result<()> {
+    let mut pins = read_pins()?;
+    pins.pins.insert(format!("{scope}/{name}"), node);
+    write_pins(&pins)
+}
+
+// 返回是否存在该锁定
+pub fn remove_pin(scope: &str, name: &str) -> Result<bool> {
+    let mut pins = read_pins()?;
+    let removed = pins.pins.remove(&format!("{scope}/{name}")).is_some();
+    if removed {
+        write_pins(&pins)?;
+    }
+    Ok(removed)
+}
+
+#[test]
+fn test_pins() {
+    let pins_bak = read_pins().unwrap();
+
+    set_pin(
+        "Microsoft",
+        "VSCode",
+        PinNode {
+            version_req: Some("~1.75".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        get_pin("Microsoft", "VSCode").unwrap(),
+        Some(PinNode {
+            version_req: Some("~1.75".to_string())
+        })
+    );
+    assert!(remove_pin("Microsoft", "VSCode").unwrap());
+    assert!(!remove_pin("Microsoft", "VSCode").unwrap());
+    assert!(get_pin("Microsoft", "VSCode").unwrap().is_none());
+
+    write_pins(&pins_bak).unwrap();
+}
@@ -0,0 +1,187 @@
+use anyhow::Result;
+
+use crate::types::{
+    extended_semver::ExSemVer,
+    mirror::MirrorPkgSoftwareRelease,
+    policy::{Policies, PolicyNode, UpdateDecision},
+};
+
+use super::{
+    cfg::get_config,
+    flags::{get_flag, Flag},
+    fs::{read_toml_store, write_toml_store},
+    mirror::filter_release,
+    pin::get_pin,
+};
+
+pub fn read_policies() -> Result<Policies> {
+    read_toml_store("policies.toml", "policies")
+}
+
+fn write_policies(policies: &Policies) -> Result<()> {
+    write_toml_store("policies.toml", "policies", policies)
+}
+
+pub fn set_package_policy(scope: &str, name: &str, node: PolicyNode) -> Result<()> {
+    let mut policies = read_policies()?;
+    policies.policies.insert(format!("{scope}/{name}"), node);
+    write_policies(&policies)
+}
+
+// 返回是否存在该策略
+pub fn remove_package_policy(scope: &str, name: &str) -> Result<bool> {
+    let mut policies = read_policies()?;
+    let removed = policies
+        .policies
+        .remove(&format!("{scope}/{name}"))
+        .is_some();
+    if removed {
+        write_policies(&policies)?;
+    }
+    Ok(removed)
+}
+
+// 返回生效的更新策略，优先使用包的策略，否则使用全局配置
+pub fn get_policy(scope: &str, name: &str) -> Result<PolicyNode> {
+    let policies = read_policies()?;
+    if let Some(node) = policies.policies.get(&format!("{scope}/{name}")) {
+        return Ok(node.to_owned());
+    }
+    let cfg = get_config();
+    Ok(PolicyNode {
+        policy: cfg.update.policy,
+        allow_prerelease: cfg.update.allow_prerelease,
+    })
+}
+
+// 综合锁定规则和更新策略，决定包应该更新到的版本
+pub fn decide_update(
+    scope: &str,
+    name: &str,
+    releases: Vec<MirrorPkgSoftwareRelease>,
+    local_version: &ExSemVer,
+) -> Result<UpdateDecision> {
+    let latest = filter_release(releases.clone(), None, true)?;
+    if &latest.version <= local_version {
+        return Ok(UpdateDecision::UpToDate);
+    }
+    if get_flag(Flag::Force, false) {
+        return Ok(UpdateDecision::Update(latest));
+    }
+
+    // 完全冻结的包不更新
+    let pin = get_pin(scope, name)?;
+    let pinned_req = if let Some(node) = &pin {
+        let req = node.get_version_req()?;
+        if req.is_none() {
+            return Ok(UpdateDecision::Held(node.describe()));
+        }
+        req
+    } else {
+        None
+    };
+
+    // 按照更新策略筛选
+    let policy = get_policy(scope, name)?;
+    let candidates: Vec<MirrorPkgSoftwareRelease> = releases
+        .into_iter()
+        .filter(|node| policy.allows(local_version, &node.version))
+        .collect();
+    let selected = filter_release(candidates, pinned_req, true)
+        .ok()
+        .filter(|node| &node.version > local_version);
+    if let Some(release) = selected {
+        return Ok(UpdateDecision::Update(release));
+    }
+
+    // 生成被拦截的原因
+    let mut reasons = Vec::new();
+    if let Some(node) = pin {
+        reasons.push(node.describe());
+    }
+    if !policy.allows(local_version, &latest.version) {
+        reasons.push(policy.describe());
+    }
+    Ok(UpdateDecision::Held(reasons.join(", ")))
+}
+
+#[test]
+fn test_decide_update() {
+    use crate::types::policy::UpdatePolicy;
+    let release = |v: &str| MirrorPkgSoftwareRelease {
+        file_name: format!("VSCode_{v}_Cno.nep"),
+        version: ExSemVer::parse(&v.to_string()).unwrap(),
+        size: 0,
+        timestamp: 0,
+        integrity: None,
+    };
+    let releases = vec![
+        release("1.75.4.0"),
+        release("1.75.4.2"),
+        release("1.75.5.0"),
+        release("1.76.0.0"),
+        release("2.0.0.0-beta.1"),
+    ];
+    let local = ExSemVer::parse(&"1.75.4.0".to_string()).unwrap();
+    let policies_bak = read_policies().unwrap();
+    let (scope, name) = ("Microsoft", "PolicyTest");
+
+    // 默认策略更新到最新的正式版本
+    remove_package_policy(scope, name).unwrap();
+    assert_eq!(
+        decide_update(scope, name, releases.clone(), &local).unwrap(),
+        UpdateDecision::Update(release("1.76.0.0"))
+    );
+
+    // 仅允许修订号更新
+    set_package_policy(
+        scope,
+        name,
+        PolicyNode {
+            policy: UpdatePolicy::Patch,
+            allow_prerelease: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        decide_update(scope, name, releases.clone(), &local).unwrap(),
+        UpdateDecision::Update(release("1.75.5.0"))
+    );
+
+    // 仅允许保留号更新
+    set_package_policy(
+        scope,
+        name,
+        PolicyNode {
+            policy: UpdatePolicy::Reserved,
+            allow_prerelease: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        decide_update(scope, name, releases.clone(), &local).unwrap(),
+        UpdateDecision::Update(release("1.75.4.2"))
+    );
+    let newest_reserved = ExSemVer::parse(&"1.75.4.2".to_string()).unwrap();
+    assert!(matches!(
+        decide_update(scope, name, releases.clone(), &newest_reserved).unwrap(),
+        UpdateDecision::Held(_)
+    ));
+
+    // 启用预发布版本
+    set_package_policy(
+        scope,
+        name,
+        PolicyNode {
+            policy: UpdatePolicy::Major,
+            allow_prerelease: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        decide_update(scope, name, releases.clone(), &local).unwrap(),
+        UpdateDecision::Update(release("2.0.0.0-beta.1"))
+    );
+
+    write_policies(&policies_bak).unwrap();
+}
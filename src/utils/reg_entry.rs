@@ -1,10 +1,12 @@
 use crate::types::uninstall_reg_entry::UninstallRegEntry;
+#[cfg(windows)]
 use winreg::{
     enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
     RegKey,
 };
 
 // 预读所有可能的 uninstall 注册表位置
+#[cfg(windows)]
 fn possible_tables() -> Vec<RegKey> {
     // 定义已知的 uninstall 位置
     let possible_uninstall_positions = vec![
@@ -31,6 +33,7 @@ fn possible_tables() -> Vec<RegKey> {
         .collect()
 }
 
+#[cfg(windows)]
 pub fn get_reg_entry(entry_id: &String) -> UninstallRegEntry {
     for table in possible_tables() {
         // 尝试打开指定 id
@@ -70,6 +73,15 @@ pub fn get_reg_entry(entry_id: &String) -> UninstallRegEntry {
     }
 }
 
+// 其他平台没有注册表，视为找不到对应的条目
+#[cfg(not(windows))]
+pub fn get_reg_entry(_: &String) -> UninstallRegEntry {
+    UninstallRegEntry {
+        version: None,
+        uninstall_string: None,
+    }
+}
+
 #[test]
 fn test_get_reg_entry() {
     let res = get_reg_entry(&"Rustup".to_string());
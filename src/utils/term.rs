@@ -1,8 +1,9 @@
+use crate::utils::flags::{get_flag, Flag};
 use crate::utils::fmt_print::{fmt_log, fmt_log_in_step};
 use crate::utils::is_confirm_mode;
-use anyhow::anyhow;
+use anyhow::{anyhow, Result};
 use colored::{ColoredString, Colorize};
-use dialoguer::Confirm;
+use dialoguer::{Confirm, Input, MultiSelect, Select};
 use encoding::all::GBK;
 use encoding::{DecoderTrap, Encoding};
 
@@ -14,17 +15,21 @@ fn get_question_head(default_value: bool) -> ColoredString {
     }
 }
 
+fn interact_yn(prompt: String, default_value: bool) -> bool {
+    Confirm::new()
+        .with_prompt(&prompt)
+        .default(default_value)
+        .interact()
+        .map_err(|e| anyhow!("Error:Failed to ask yn question '{prompt}' : {e}"))
+        .unwrap()
+}
+
 fn ask_yn_impl(prompt: String, default_value: bool) -> bool {
     if is_confirm_mode() {
         log!("{prompt} (confirmed)");
         true
     } else {
-        Confirm::new()
-            .with_prompt(&prompt)
-            .default(default_value)
-            .interact()
-            .map_err(|e| anyhow!("Error:Failed to ask yn question '{prompt}' : {e}"))
-            .unwrap()
+        interact_yn(prompt, default_value)
     }
 }
 
@@ -36,6 +41,17 @@ pub fn ask_yn(prompt: String, default_value: bool) -> bool {
     )
 }
 
+// 确认模式由批量操作隐式开启时仍然询问用户，用于不应被自动确认的问题
+pub fn ask_yn_strict(prompt: String, default_value: bool) -> bool {
+    debug_assert!(prompt.as_bytes().first().unwrap().is_ascii_uppercase() && prompt.ends_with('?'));
+    let prompt = fmt_log(get_question_head(default_value), &prompt);
+    if get_flag(Flag::ImplicitConfirm, false) {
+        interact_yn(prompt, default_value)
+    } else {
+        ask_yn_impl(prompt, default_value)
+    }
+}
+
 pub fn ask_yn_in_step(step_name: &str, prompt: String, default_value: bool) -> bool {
     debug_assert!(prompt.as_bytes().first().unwrap().is_ascii_uppercase() && prompt.ends_with('?'));
     ask_yn_impl(
@@ -44,6 +60,51 @@ pub fn ask_yn_in_step(step_name: &str, prompt: String, default_value: bool) -> b
     )
 }
 
+// 确认模式下直接使用缺省值，没有缺省值时报错
+pub fn ask_input(prompt: &str, default_value: Option<String>) -> Result<String> {
+    if is_confirm_mode() {
+        let value = default_value.ok_or(anyhow!(
+            "Error:Value of '{prompt}' is required in confirm mode"
+        ))?;
+        log!("Info:{prompt} : {value} (confirmed)");
+        return Ok(value);
+    }
+    let mut input = Input::<String>::new().with_prompt(fmt_log(get_question_head(true), prompt));
+    if let Some(default_value) = default_value {
+        input = input.default(default_value);
+    }
+    input
+        .interact_text()
+        .map_err(|e| anyhow!("Error:Failed to read input of '{prompt}' : {e}"))
+}
+
+// 返回选中项的下标
+pub fn ask_select(prompt: &str, items: &[String], default_index: usize) -> Result<usize> {
+    if is_confirm_mode() {
+        log!("Info:{prompt} : {} (confirmed)", items[default_index]);
+        return Ok(default_index);
+    }
+    Select::new()
+        .with_prompt(fmt_log(get_question_head(true), prompt))
+        .items(items)
+        .default(default_index)
+        .interact()
+        .map_err(|e| anyhow!("Error:Failed to read selection of '{prompt}' : {e}"))
+}
+
+// 返回选中项的下标列表
+pub fn ask_multi_select(prompt: &str, items: &[String], defaults: &[bool]) -> Result<Vec<usize>> {
+    if is_confirm_mode() {
+        return Ok((0..items.len()).filter(|&i| defaults[i]).collect());
+    }
+    MultiSelect::new()
+        .with_prompt(fmt_log(get_question_head(true), prompt))
+        .items(items)
+        .defaults(defaults)
+        .interact()
+        .map_err(|e| anyhow!("Error:Failed to read selection of '{prompt}' : {e}"))
+}
+
 pub fn read_console(v: Vec<u8>) -> String {
     // 先尝试使用 GBK 编码转换
     if let Ok(str) = GBK.decode(&v, DecoderTrap::Strict) {
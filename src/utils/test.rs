@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::types::matcher::PackageMatcher;
+use crate::types::{cfg::PermissionAction, install_record::InstallReason, matcher::PackageMatcher};
 use anyhow::anyhow;
 use httpmock::prelude::*;
 use which::which;
@@ -8,7 +8,13 @@ use which::which;
 pub fn _ensure_testing_vscode() -> String {
     if crate::entrances::info_local(&"Microsoft".to_string(), &"VSCode".to_string()).is_err() {
         crate::utils::fs::copy_dir("examples/VSCode", "test/VSCode").unwrap();
-        crate::install_using_package(&"test/VSCode".to_string(), false).unwrap();
+        crate::install_using_package(
+            &"test/VSCode".to_string(),
+            false,
+            InstallReason::Explicit,
+            None,
+        )
+        .unwrap();
     }
 
     crate::meta(
@@ -26,14 +32,20 @@ pub fn _ensure_testing_vscode() -> String {
 
 pub fn _ensure_testing_vscode_uninstalled() {
     if crate::entrances::info_local(&"Microsoft".to_string(), &"VSCode".to_string()).is_ok() {
-        crate::uninstall(Some("Microsoft".to_string()), &"VSCode".to_string()).unwrap();
+        crate::entrances::uninstall(Some("Microsoft".to_string()), &"VSCode".to_string()).unwrap();
     }
 }
 
 pub fn _ensure_testing(scope: &str, name: &str) -> String {
     if crate::entrances::info_local(&scope.to_string(), &name.to_string()).is_err() {
         crate::utils::fs::copy_dir(format!("examples/{name}"), format!("test/{name}")).unwrap();
-        crate::install_using_package(&format!("test/{name}"), false).unwrap();
+        crate::install_using_package(
+            &format!("test/{name}"),
+            false,
+            InstallReason::Explicit,
+            None,
+        )
+        .unwrap();
     }
 
     crate::meta(
@@ -52,7 +64,7 @@ pub fn _ensure_testing(scope: &str, name: &str) -> String {
 pub fn _ensure_testing_uninstalled(scope: &str, name: &str) {
     let s = scope.to_string();
     if crate::entrances::info_local(&s, &name.to_string()).is_ok() {
-        crate::uninstall(Some(s), &name.to_string()).unwrap();
+        crate::entrances::uninstall(Some(s), &name.to_string()).unwrap();
     }
 }
 
@@ -312,3 +324,12 @@ pub fn _restore_mirror_data(tup: (bool, PathBuf, PathBuf)) {
         rename(&bak_p, &origin_p).unwrap();
     }
 }
+
+// 在内存中按权限类型放行权限，仅对当前测试进程生效
+pub fn _allow_testing_permission(key: &str) {
+    crate::utils::cfg::_update_config_in_memory(|cfg| {
+        cfg.permission
+            .overrides
+            .insert(key.to_string(), PermissionAction::Allow);
+    });
+}